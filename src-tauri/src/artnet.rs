@@ -1,19 +1,185 @@
+use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
+use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
 use tokio::net::UdpSocket;
 
+use crate::merge::MergeMode;
+
 pub const ARTNET_PORT: u16 = 6454;
-const ARTNET_ID: &[u8; 8] = b"Art-Net\0"; // Zero-terminated string
+pub const ARTNET_ID: &[u8; 8] = b"Art-Net\0"; // Zero-terminated string
+pub const OP_POLL: u16 = 0x2000; // ArtPoll
+pub const OP_POLL_REPLY: u16 = 0x2100; // ArtPollReply
+pub const OP_COMMAND: u16 = 0x2400; // ArtCommand
+pub const OP_NZS: u16 = 0x5100; // ArtNzs
+pub const OP_ADDRESS: u16 = 0x6000; // ArtAddress
+pub const OP_TOD_REQUEST: u16 = 0x8000; // ArtTodRequest
+pub const OP_TOD_DATA: u16 = 0x8100; // ArtTodData
+pub const OP_TOD_CONTROL: u16 = 0x8200; // ArtTodControl
+pub const OP_RDM: u16 = 0x8300; // ArtRdm
+pub const OP_TIME_CODE: u16 = 0x9700; // ArtTimeCode
+pub const OP_TRIGGER: u16 = 0x9900; // ArtTrigger
+pub const OP_IP_PROG: u16 = 0xf800; // ArtIpProg
+pub const OP_IP_PROG_REPLY: u16 = 0xf900; // ArtIpProgReply
 const OP_OUTPUT: u16 = 0x5000; // ArtDMX
-const PROT_VER: u16 = 14; // As per spec
+const OP_SYNC: u16 = 0x5200; // ArtSync
+pub const PROT_VER: u16 = 14; // As per spec
+
+/// 15-bit Art-Net Port-Address: Net (bits 14-8), Sub-Net (bits 7-4) and
+/// Universe (bits 3-0).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PortAddress(u16);
+
+impl PortAddress {
+    pub const MAX: u16 = 0x7fff;
+
+    pub fn new(value: u16) -> Result<Self> {
+        if value > Self::MAX {
+            return Err(anyhow!(
+                "Port-Address {} out of range (0..={})",
+                value,
+                Self::MAX
+            ));
+        }
+        Ok(Self(value))
+    }
+
+    pub fn from_parts(net: u8, subnet: u8, universe: u8) -> Result<Self> {
+        if net > 0x7f {
+            return Err(anyhow!("Net {} out of range (0..=127)", net));
+        }
+        if subnet > 0x0f {
+            return Err(anyhow!("Sub-Net {} out of range (0..=15)", subnet));
+        }
+        if universe > 0x0f {
+            return Err(anyhow!("Universe {} out of range (0..=15)", universe));
+        }
+        Ok(Self(
+            ((net as u16) << 8) | ((subnet as u16) << 4) | universe as u16,
+        ))
+    }
+
+    /// Builds an address from the SubUni and Net bytes of an Art-Net packet.
+    pub fn from_wire(sub_uni: u8, net: u8) -> Result<Self> {
+        Self::from_parts(net, sub_uni >> 4, sub_uni & 0x0f)
+    }
+
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    pub fn net(self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    pub fn subnet(self) -> u8 {
+        ((self.0 >> 4) & 0x0f) as u8
+    }
+
+    pub fn universe(self) -> u8 {
+        (self.0 & 0x0f) as u8
+    }
+
+    /// SubUni byte: high nibble Sub-Net, low nibble Universe.
+    pub fn sub_uni(self) -> u8 {
+        (self.0 & 0xff) as u8
+    }
+
+    /// Flat decimal form, e.g. "291" for 1:2:3.
+    pub fn to_flat_string(self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl fmt::Display for PortAddress {
+    /// "net:subnet:universe", e.g. "1:2:3".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.net(), self.subnet(), self.universe())
+    }
+}
+
+impl FromStr for PortAddress {
+    type Err = anyhow::Error;
+
+    /// Accepts "net:subnet:universe" (also '.' or '/' separated) or the flat
+    /// decimal Port-Address.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let parts: Vec<&str> = s.split([':', '.', '/']).map(str::trim).collect();
+        match parts.as_slice() {
+            [flat] => Self::new(
+                flat.parse()
+                    .map_err(|_| anyhow!("Invalid Port-Address: {:?}", s))?,
+            ),
+            [net, subnet, universe] => {
+                let parse = |v: &str| {
+                    v.parse::<u8>()
+                        .map_err(|_| anyhow!("Invalid Port-Address: {:?}", s))
+                };
+                Self::from_parts(parse(net)?, parse(subnet)?, parse(universe)?)
+            }
+            _ => Err(anyhow!("Invalid Port-Address: {:?}", s)),
+        }
+    }
+}
+
+impl TryFrom<u16> for PortAddress {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u16) -> Result<Self> {
+        Self::new(value)
+    }
+}
+
+impl From<PortAddress> for u16 {
+    fn from(addr: PortAddress) -> u16 {
+        addr.0
+    }
+}
+
+impl serde::Serialize for PortAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(self.0)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PortAddress {
+    /// Accepts the flat number, a "1:2:3" / "291" string or a
+    /// `{ net, subnet, universe }` object.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Flat(u16),
+            Text(String),
+            Parts { net: u8, subnet: u8, universe: u8 },
+        }
+        let addr = match Repr::deserialize(deserializer)? {
+            Repr::Flat(v) => PortAddress::new(v),
+            Repr::Text(s) => s.parse(),
+            Repr::Parts {
+                net,
+                subnet,
+                universe,
+            } => PortAddress::from_parts(net, subnet, universe),
+        };
+        addr.map_err(serde::de::Error::custom)
+    }
+}
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ReceiverConfig {
     pub bind_ip: String, // e.g., "0.0.0.0"
     pub port: u16,       // usually 6454
+    /// How frames from two sources on the same universe are combined.
+    #[serde(default)]
+    pub merge_mode: MergeMode,
+    /// Discard ArtDmx packets that arrive behind the last accepted sequence.
+    #[serde(default)]
+    pub reject_out_of_order: bool,
 }
 
 impl Default for ReceiverConfig {
@@ -21,18 +187,64 @@ impl Default for ReceiverConfig {
         Self {
             bind_ip: "0.0.0.0".into(),
             port: ARTNET_PORT,
+            merge_mode: MergeMode::default(),
+            reject_out_of_order: false,
         }
     }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "SenderConfigRepr")]
 pub struct SenderConfig {
     pub target_ip: String, // e.g., broadcast 255.255.255.255
     pub port: u16,         // usually 6454
-    pub net: u8,           // 0..=127
-    pub subnet: u8,        // 0..=15
-    pub universe: u8,      // 0..=15
-    pub fps: u32,          // sending frequency
+    pub port_address: PortAddress,
+    pub fps: u32,   // sending frequency
+    pub sync: bool, // send one ArtSync after the ArtDmx frames of each tick
+    /// Art-Net 4 unicast: send each universe only to the discovered nodes
+    /// whose output ports subscribe to it, broadcasting to `target_ip` while
+    /// none are known.
+    pub unicast_subscribers: bool,
+}
+
+/// Wire form of `SenderConfig`; still accepts the separate net/subnet/universe
+/// fields written by older settings files.
+#[derive(serde::Deserialize)]
+struct SenderConfigRepr {
+    target_ip: String,
+    port: u16,
+    #[serde(default)]
+    port_address: Option<PortAddress>,
+    #[serde(default)]
+    net: u8,
+    #[serde(default)]
+    subnet: u8,
+    #[serde(default)]
+    universe: u8,
+    fps: u32,
+    #[serde(default)]
+    sync: bool,
+    #[serde(default)]
+    unicast_subscribers: bool,
+}
+
+impl TryFrom<SenderConfigRepr> for SenderConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(r: SenderConfigRepr) -> Result<Self> {
+        let port_address = match r.port_address {
+            Some(addr) => addr,
+            None => PortAddress::from_parts(r.net, r.subnet, r.universe)?,
+        };
+        Ok(Self {
+            target_ip: r.target_ip,
+            port: r.port,
+            port_address,
+            fps: r.fps,
+            sync: r.sync,
+            unicast_subscribers: r.unicast_subscribers,
+        })
+    }
 }
 
 impl Default for SenderConfig {
@@ -40,25 +252,55 @@ impl Default for SenderConfig {
         Self {
             target_ip: "255.255.255.255".into(),
             port: ARTNET_PORT,
-            net: 0,
-            subnet: 0,
-            universe: 0,
+            port_address: PortAddress::default(),
             fps: 44,
+            sync: false,
+            unicast_subscribers: false,
         }
     }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DmxFrame {
-    pub net: u8,
-    pub subnet: u8,
-    pub universe: u8,
+    pub port_address: PortAddress,
     pub length: u16,
     pub sequence: u8,
     pub physical: u8,
     pub values: Vec<u8>, // length elements
 }
 
+/// An ArtNzs packet: DMX-style data with a non-zero start code, such as
+/// text (0x17) or manufacturer-specific packets.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NzsFrame {
+    pub port_address: PortAddress,
+    pub start_code: u8,
+    pub length: u16,
+    pub sequence: u8,
+    pub values: Vec<u8>, // length elements
+}
+
+/// Next ArtDmx sequence number. 0 means "sequencing disabled", so the
+/// counter cycles through 0x01..=0xFF.
+pub fn next_sequence(seq: u8) -> u8 {
+    if seq == 0xff {
+        1
+    } else {
+        seq + 1
+    }
+}
+
+/// Forward distance from `last` to `seq` in the 0x01..=0xFF sequence cycle.
+pub fn sequence_distance(last: u8, seq: u8) -> u8 {
+    (seq as i16 - last as i16).rem_euclid(255) as u8
+}
+
+/// True when `seq` is not ahead of `last` by less than half the cycle, i.e.
+/// the packet is late or repeated. A sequence of 0 never counts as behind.
+pub fn sequence_is_behind(last: u8, seq: u8) -> bool {
+    last != 0 && seq != 0 && !(1..=127).contains(&sequence_distance(last, seq))
+}
+
 fn compute_dmx_length(data: &[u8; 512]) -> u16 {
     // Per Art-Net specification: DMX length is 2..=512 bytes and should be even.
     // We trim trailing zeros to reduce packet size while staying compliant.
@@ -95,21 +337,53 @@ pub fn encode_artdmx_into(pkt: &mut Vec<u8>, cfg: &SenderConfig, data: &[u8; 512
     pkt.extend_from_slice(&PROT_VER.to_be_bytes());
     pkt.push(sequence); // Sequence
     pkt.push(0); // Physical port (not used)
-    let sub = cfg.subnet & 0x0f;
-    let uni = cfg.universe & 0x0f;
-    let subuni = (sub << 4) | uni; // SubUni: hi-nibble SubNet, lo-nibble Universe
-    pkt.push(subuni); // SubUni (lo)
-    pkt.push(cfg.net & 0x7f); // Net (hi)
+    pkt.push(cfg.port_address.sub_uni()); // SubUni (lo)
+    pkt.push(cfg.port_address.net()); // Net (hi)
     pkt.extend_from_slice(&length.to_be_bytes()); // Length hi, lo (big-endian)
     pkt.extend_from_slice(&data[..length as usize]);
 }
 
+/// ArtNzs carries `start_code` in place of ArtDmx's Physical field.
+pub fn encode_artnzs_into(
+    pkt: &mut Vec<u8>,
+    cfg: &SenderConfig,
+    start_code: u8,
+    data: &[u8; 512],
+    sequence: u8,
+) {
+    encode_artdmx_into(pkt, cfg, data, sequence);
+    pkt[8..10].copy_from_slice(&OP_NZS.to_le_bytes());
+    pkt[13] = start_code;
+}
+
 pub fn encode_artdmx(cfg: &SenderConfig, data: &[u8; 512], sequence: u8) -> Vec<u8> {
     let mut pkt = Vec::with_capacity(530);
     encode_artdmx_into(&mut pkt, cfg, data, sequence);
     pkt
 }
 
+pub fn encode_artsync_into(pkt: &mut Vec<u8>) {
+    pkt.clear();
+    pkt.reserve(14);
+    pkt.extend_from_slice(ARTNET_ID);
+    pkt.extend_from_slice(&OP_SYNC.to_le_bytes());
+    pkt.extend_from_slice(&PROT_VER.to_be_bytes());
+    pkt.push(0); // Aux1
+    pkt.push(0); // Aux2
+}
+
+/// Returns the OpCode of an Art-Net packet, or `None` if `buf` is not Art-Net.
+pub fn opcode(buf: &[u8]) -> Option<u16> {
+    if buf.len() < 10 || &buf[0..8] != ARTNET_ID {
+        return None;
+    }
+    Some(u16::from_le_bytes([buf[8], buf[9]]))
+}
+
+pub fn is_artsync(buf: &[u8]) -> bool {
+    buf.len() >= 14 && opcode(buf) == Some(OP_SYNC)
+}
+
 pub fn parse_artdmx(buf: &[u8]) -> Result<DmxFrame> {
     if buf.len() < 18 {
         return Err(anyhow!("Packet too short"));
@@ -130,11 +404,10 @@ pub fn parse_artdmx(buf: &[u8]) -> Result<DmxFrame> {
     if buf.len() < 18 + len as usize {
         return Err(anyhow!("Length mismatch"));
     }
+    let port_address = PortAddress::from_wire(subuni, net)?;
     let values = buf[18..18 + len as usize].to_vec();
     Ok(DmxFrame {
-        net,
-        subnet: (subuni >> 4) & 0x0f,
-        universe: subuni & 0x0f,
+        port_address,
         length: len,
         sequence,
         physical,
@@ -142,12 +415,41 @@ pub fn parse_artdmx(buf: &[u8]) -> Result<DmxFrame> {
     })
 }
 
+pub fn parse_artnzs(buf: &[u8]) -> Result<NzsFrame> {
+    if buf.len() < 18 {
+        return Err(anyhow!("Packet too short"));
+    }
+    if opcode(buf) != Some(OP_NZS) {
+        return Err(anyhow!("Not ArtNzs"));
+    }
+    let start_code = buf[13];
+    if start_code == 0 {
+        return Err(anyhow!("ArtNzs with a null start code"));
+    }
+    let len = u16::from_be_bytes([buf[16], buf[17]]);
+    if len > 512 || buf.len() < 18 + len as usize {
+        return Err(anyhow!("Length mismatch"));
+    }
+    Ok(NzsFrame {
+        port_address: PortAddress::from_wire(buf[14], buf[15])?,
+        start_code,
+        length: len,
+        sequence: buf[12],
+        values: buf[18..18 + len as usize].to_vec(),
+    })
+}
+
 pub async fn bind_receiver_socket(cfg: &ReceiverConfig) -> Result<UdpSocket> {
+    bind_reuse_socket(&cfg.bind_ip, cfg.port)
+}
+
+/// Binds a non-blocking UDP socket that other processes may share.
+pub fn bind_reuse_socket(bind_ip: &str, port: u16) -> Result<UdpSocket> {
     use socket2::{Domain, Protocol, Socket, Type};
     use std::net::SocketAddr as StdSocketAddr;
 
-    let ip: IpAddr = cfg.bind_ip.parse()?;
-    let addr = SocketAddr::new(ip, cfg.port);
+    let ip: IpAddr = bind_ip.parse()?;
+    let addr = SocketAddr::new(ip, port);
     let std_addr: StdSocketAddr = addr.into();
 
     // Create socket with SO_REUSEADDR to allow port sharing
@@ -215,3 +517,28 @@ pub async fn send_artdmx_with_buffer(
     sock.send_to(pkt, target).await?;
     Ok(())
 }
+
+pub async fn send_artnzs_with_buffer(
+    sock: &UdpSocket,
+    cfg: &SenderConfig,
+    start_code: u8,
+    data: &[u8; 512],
+    sequence: u8,
+    pkt: &mut Vec<u8>,
+) -> Result<()> {
+    encode_artnzs_into(pkt, cfg, start_code, data, sequence);
+    let target: SocketAddr = format!("{}:{}", cfg.target_ip, cfg.port).parse()?;
+    sock.send_to(pkt, target).await?;
+    Ok(())
+}
+
+pub async fn send_artsync_with_buffer(
+    sock: &UdpSocket,
+    cfg: &SenderConfig,
+    pkt: &mut Vec<u8>,
+) -> Result<()> {
+    encode_artsync_into(pkt);
+    let target: SocketAddr = format!("{}:{}", cfg.target_ip, cfg.port).parse()?;
+    sock.send_to(pkt, target).await?;
+    Ok(())
+}
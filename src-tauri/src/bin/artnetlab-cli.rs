@@ -0,0 +1,1251 @@
+//! Headless front end to the ArtNetLab engine, for show servers and scripts.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use artnetlab_lib::artnet::{PortAddress, ReceiverConfig, SenderConfig};
+use artnetlab_lib::events::{EventSink, NoEvents};
+use artnetlab_lib::inspector::{self, Field, InspectedPacket};
+use artnetlab_lib::merge::MergeMode;
+use artnetlab_lib::preroll::{Crossing, PreRollConfig, PreRollTrigger};
+use artnetlab_lib::remote::{AddressCommand, AddressRequest, IpProgRequest, IpSettings};
+use artnetlab_lib::sacn::{OutputProtocol, SacnReceiverConfig};
+use artnetlab_lib::show::{self, Compression};
+use artnetlab_lib::state::{self, AppState, RecordData, SpillConfig};
+use artnetlab_lib::timecode::{Timecode, TimecodeConfig, TimecodeType};
+use artnetlab_lib::trigger::{self, TriggerMatch};
+use artnetlab_lib::{discovery, pcap, recording};
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage: artnetlab-cli <command> [options]
+
+Commands:
+  monitor   Print received DMX frames
+            --bind IP  --port N  --universe N:S:U  --merge htp|ltp
+            --reject-out-of-order  --sacn U1,U2,..  --stats  --json
+  inspect   Decode every packet arriving on the Art-Net port
+            --bind IP  --port N  --only OP1,OP2 (e.g. ArtPoll,ArtRdm)
+            --hex  --json  --pcapng FILE (also save every packet on exit)
+  send      Stream DMX to a universe until interrupted
+            --target IP  --port N  --universe N:S:U  --fps N
+            --protocol artnet|sacn  --sync  --set CH=V,CH-CH=V  --all V
+            --once  --duration SEC
+            --start-code N (non-zero sends ArtNzs / sACN with that start code)
+            --unicast (only to nodes whose outputs take the universe)
+            --timecode film|ebu|df|smpte  --tc-start HH:MM:SS:FF
+  trigger   Send an ArtTrigger
+            KEY SUBKEY  --target IP  --port N  --oem N  --data TEXT
+            KEY: ascii, macro, soft, show or 0-255
+  command   Send an ArtCommand, e.g. \"SwoutText=Playback&\"
+            TEXT  --target IP  --port N  --esta N
+  record    Record received frames to FILE (.jsonl, .artrec or .wav), a
+            track per universe; .wav writes one file per universe
+            FILE  --bind IP  --port N  --universe N:S:U,..  --duration SEC
+            --compression none|zstd|lz4 (.artrec, default zstd)
+            .artrec is written as frames arrive, keeping --window SEC
+            (default 300) in memory, so it suits day-long captures
+  preroll   Keep the last seconds of received frames and save them with a
+            post-roll to FILE (.jsonl, .artrec or .wav) when a trigger fires;
+            Enter fires it by hand
+            FILE  --bind IP  --port N  --universe N:S:U,..  --pre SEC
+            --post SEC  --above N:S:U:CH=V  --below N:S:U:CH=V
+            --change N:S:U:CH  --stopped N:S:U  --stop-ms MS
+            --on-trigger KEY[:SUBKEY]  --compression none|zstd|lz4
+            --repeat (keep going, numbering the files)
+  play      Play a .jsonl, .artrec, .wav or .pcap/.pcapng recording
+            FILE  --target IP  --port N  --universe N:S:U (wav only)
+            --start-ms MS  --loop
+            --chase HH:MM:SS:FF (follow received ArtTimeCode, starting the
+            recording at that timecode)  --bind IP  --listen-port N
+  convert   Convert a .jsonl recording to a binary .artrec show file or
+            back, or recompress a show file
+            SRC DST  --compression none|zstd|lz4  --keyframe-ms MS
+  discover  Scan the network for Art-Net nodes
+            --target IP  --port N  --timeout MS  --json
+  address   Program a node with ArtAddress and check its reply
+            IP  --bind N  --short-name S  --long-name S  --net N  --subnet N
+            --in PORT=U,..  --out PORT=U,..  --acn-priority N  --command CMD
+            --port N  --timeout MS  --json
+            CMD: cancel-merge, led-normal, led-mute, led-locate, reset-rx,
+            fail-hold, fail-zero, fail-full, fail-scene, merge-ltp:PORT,
+            merge-htp:PORT, artnet:PORT, sacn:PORT, clear:PORT
+  ipprog    Read a node's IP settings with ArtIpProg, or change them
+            IP  --ip ADDR  --mask MASK  --gateway ADDR  --prog-port N
+            --dhcp  --reset  --yes (required to program)
+            --port N  --timeout MS  --json
+";
+
+/// Minimal `--name value` / `--flag` parser.
+struct Opts {
+    positional: Vec<String>,
+    named: Vec<(String, Option<String>)>,
+}
+
+impl Opts {
+    fn parse(args: impl Iterator<Item = String>, flags: &[&str]) -> Result<Self> {
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if flags.contains(&name) => named.push((name.to_string(), None)),
+                Some(name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--{} needs a value", name))?;
+                    named.push((name.to_string(), Some(value)));
+                }
+                None => positional.push(arg),
+            }
+        }
+        Ok(Self { positional, named })
+    }
+
+    fn take(&mut self, name: &str) -> Option<String> {
+        let idx = self.named.iter().position(|(n, _)| n == name)?;
+        self.named.remove(idx).1
+    }
+
+    fn flag(&mut self, name: &str) -> bool {
+        match self.named.iter().position(|(n, _)| n == name) {
+            Some(idx) => {
+                self.named.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn parsed<T: std::str::FromStr>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T::Err: std::fmt::Display,
+    {
+        self.take(name)
+            .map(|v| {
+                v.parse()
+                    .map_err(|e| anyhow!("Invalid --{} {:?}: {}", name, v, e))
+            })
+            .transpose()
+    }
+
+    /// Fails on options the command does not know.
+    fn finish(self) -> Result<Vec<String>> {
+        if let Some((name, _)) = self.named.first() {
+            return Err(anyhow!("Unknown option --{}", name));
+        }
+        Ok(self.positional)
+    }
+}
+
+fn parse_merge_mode(s: &str) -> Result<MergeMode> {
+    match s {
+        "htp" => Ok(MergeMode::Htp),
+        "ltp" => Ok(MergeMode::Ltp),
+        _ => Err(anyhow!("Merge mode must be htp or ltp")),
+    }
+}
+
+fn parse_protocol(s: &str) -> Result<OutputProtocol> {
+    match s {
+        "artnet" => Ok(OutputProtocol::ArtNet),
+        "sacn" => Ok(OutputProtocol::Sacn),
+        _ => Err(anyhow!("Protocol must be artnet or sacn")),
+    }
+}
+
+/// Parses `1=255,10-20=128` (1-based channels) into the channel buffer.
+fn apply_channel_spec(values: &mut [u8; 512], spec: &str) -> Result<()> {
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (chans, value) = part
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected CH=V, got {:?}", part))?;
+        let value: u8 = value.trim().parse()?;
+        let (from, to) = match chans.split_once('-') {
+            Some((a, b)) => (a.trim().parse::<usize>()?, b.trim().parse::<usize>()?),
+            None => {
+                let ch = chans.trim().parse::<usize>()?;
+                (ch, ch)
+            }
+        };
+        if from == 0 || to > 512 || from > to {
+            return Err(anyhow!("Channel range {:?} outside 1..=512", chans));
+        }
+        values[from - 1..to].fill(value);
+    }
+    Ok(())
+}
+
+/// Waits for `duration` seconds or Ctrl-C. Durations too long for a
+/// `Duration`, such as `inf`, wait for Ctrl-C only.
+async fn wait_for_stop(duration: Option<f64>) {
+    match duration.and_then(|sec| Duration::try_from_secs_f64(sec.max(0.0)).ok()) {
+        Some(duration) => {
+            tokio::select! {
+                _ = tokio::time::sleep(duration) => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+        None => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+fn receiver_config(opts: &mut Opts) -> Result<ReceiverConfig> {
+    let mut cfg = ReceiverConfig::default();
+    if let Some(ip) = opts.take("bind") {
+        cfg.bind_ip = ip;
+    }
+    if let Some(port) = opts.parsed("port")? {
+        cfg.port = port;
+    }
+    Ok(cfg)
+}
+
+fn start_receiver<E: EventSink>(app_state: &AppState, cfg: ReceiverConfig, events: E) {
+    let st = app_state.clone();
+    let handle = tokio::spawn(async move {
+        if let Err(e) = state::run_receiver_task(cfg, events, st).await {
+            eprintln!("receiver task error: {e:?}");
+        }
+    });
+    app_state.set_receiver_task(handle);
+}
+
+/// Prints monitor events to stdout, as text or as JSON lines.
+#[derive(Clone)]
+struct Printer {
+    json: bool,
+    stats: bool,
+}
+
+impl EventSink for Printer {
+    fn emit<T: Serialize + Clone>(&self, event: &str, payload: T) {
+        let wanted = match event {
+            "artnet:dmx_filtered" | "artnet:nzs_filtered" => true,
+            "artnet:stats" => self.stats,
+            "artnet:merge_rejected" | "artnet:sync_mode" | "artnet:timecode" => true,
+            "artnet:trigger" | "artnet:command" => true,
+            _ => false,
+        };
+        if !wanted {
+            return;
+        }
+        let Ok(value) = serde_json::to_value(&payload) else {
+            return;
+        };
+        if self.json {
+            println!(
+                "{}",
+                serde_json::json!({ "event": event, "payload": value })
+            );
+            return;
+        }
+        match event {
+            "artnet:dmx_filtered" => {
+                let addr = value["port_address"]
+                    .as_u64()
+                    .and_then(|v| PortAddress::new(v as u16).ok())
+                    .unwrap_or_default();
+                let values: Vec<String> = value["values"]
+                    .as_array()
+                    .map(|a| a.iter().take(16).map(|v| v.to_string()).collect())
+                    .unwrap_or_default();
+                println!(
+                    "{:>9} seq {:>3} len {:>3} | {}",
+                    addr.to_string(),
+                    value["sequence"].as_u64().unwrap_or(0),
+                    value["length"].as_u64().unwrap_or(0),
+                    values.join(" ")
+                );
+            }
+            "artnet:nzs_filtered" => {
+                let addr = value["port_address"]
+                    .as_u64()
+                    .and_then(|v| PortAddress::new(v as u16).ok())
+                    .unwrap_or_default();
+                let values: Vec<String> = value["values"]
+                    .as_array()
+                    .map(|a| a.iter().take(16).map(|v| v.to_string()).collect())
+                    .unwrap_or_default();
+                println!(
+                    "{:>9} nzs {:#04x} seq {:>3} len {:>3} | {}",
+                    addr.to_string(),
+                    value["start_code"].as_u64().unwrap_or(0),
+                    value["sequence"].as_u64().unwrap_or(0),
+                    value["length"].as_u64().unwrap_or(0),
+                    values.join(" ")
+                );
+            }
+            "artnet:stats" => {
+                for uni in value.as_array().into_iter().flatten() {
+                    let addr = uni["port_address"]
+                        .as_u64()
+                        .and_then(|v| PortAddress::new(v as u16).ok())
+                        .unwrap_or_default();
+                    println!(
+                        "# {} {:.1} fps, jitter {:.2} ms, lost {}, out of order {}, discarded {}",
+                        addr,
+                        uni["fps"].as_f64().unwrap_or(0.0),
+                        uni["jitter_ms"].as_f64().unwrap_or(0.0),
+                        uni["lost"],
+                        uni["out_of_order"],
+                        uni["discarded"]
+                    );
+                }
+            }
+            "artnet:timecode" => println!(
+                "# timecode {} from {} (stream {})",
+                value["text"].as_str().unwrap_or_default(),
+                value["sourceIp"].as_str().unwrap_or_default(),
+                value["streamId"]
+            ),
+            "artnet:trigger" => println!(
+                "# trigger {} ({}) sub-key {} oem {:#06x} from {}",
+                value["keyName"].as_str().unwrap_or_default(),
+                value["key"],
+                value["subKey"],
+                value["oem"].as_u64().unwrap_or(0),
+                value["sourceIp"].as_str().unwrap_or_default()
+            ),
+            "artnet:command" => println!(
+                "# command {:?} from {}",
+                value["text"].as_str().unwrap_or_default(),
+                value["sourceIp"].as_str().unwrap_or_default()
+            ),
+            _ => println!("# {} {}", event, value),
+        }
+    }
+}
+
+async fn cmd_monitor(mut opts: Opts) -> Result<()> {
+    let mut cfg = receiver_config(&mut opts)?;
+    if let Some(mode) = opts.take("merge") {
+        cfg.merge_mode = parse_merge_mode(&mode)?;
+    }
+    cfg.reject_out_of_order = opts.flag("reject-out-of-order");
+    let filter: Option<PortAddress> = opts.parsed("universe")?;
+    let sacn_universes = opts.take("sacn");
+    let printer = Printer {
+        json: opts.flag("json"),
+        stats: opts.flag("stats"),
+    };
+    opts.finish()?;
+
+    let app_state = AppState::new();
+    app_state.set_event_filter(filter);
+    start_receiver(&app_state, cfg, printer.clone());
+    if let Some(list) = sacn_universes {
+        let universes = list
+            .split(',')
+            .map(|u| u.trim().parse::<u16>())
+            .collect::<Result<Vec<_>, _>>()?;
+        let sacn_cfg = SacnReceiverConfig {
+            enabled: true,
+            universes,
+            ..SacnReceiverConfig::default()
+        };
+        let st = app_state.clone();
+        let handle = tokio::spawn(async move {
+            if let Err(e) = state::run_sacn_receiver_task(sacn_cfg, printer, st).await {
+                eprintln!("sACN receiver task error: {e:?}");
+            }
+        });
+        app_state.set_sacn_receiver_task(handle);
+    }
+    wait_for_stop(None).await;
+    app_state.stop_receiver();
+    app_state.stop_sacn_receiver();
+    Ok(())
+}
+
+fn print_fields(fields: &[Field], depth: usize) {
+    for f in fields {
+        if f.children.is_empty() {
+            println!("{:w$}{}: {}", "", f.name, f.value, w = depth * 2);
+        } else {
+            println!("{:w$}{}:", "", f.name, w = depth * 2);
+            print_fields(&f.children, depth + 1);
+        }
+    }
+}
+
+fn print_packet(p: &InspectedPacket, hex: bool) {
+    let secs = p.at_ms / 1000 % 86_400;
+    println!(
+        "{:02}:{:02}:{:02}.{:03} {} {}{} {}, {} bytes",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60,
+        p.at_ms % 1000,
+        p.source,
+        p.opcode_name,
+        p.opcode
+            .map(|op| format!(" ({:#06x})", op))
+            .unwrap_or_default(),
+        p.prot_ver
+            .map(|v| format!("ProtVer {}", v))
+            .unwrap_or_else(|| "no ProtVer".into()),
+        p.length
+    );
+    print_fields(&p.fields, 1);
+    if let Some(e) = &p.error {
+        println!("  error: {}", e);
+    }
+    if hex || p.fields.is_empty() {
+        for line in p.hex.lines() {
+            println!("  {}", line);
+        }
+    }
+}
+
+async fn cmd_inspect(mut opts: Opts) -> Result<()> {
+    let cfg = receiver_config(&mut opts)?;
+    let only: Vec<String> = opts
+        .take("only")
+        .map(|l| l.split(',').map(|s| s.trim().to_lowercase()).collect())
+        .unwrap_or_default();
+    let hex = opts.flag("hex");
+    let json = opts.flag("json");
+    let pcapng = opts.take("pcapng");
+    opts.finish()?;
+
+    let app_state = AppState::new();
+    app_state.set_packet_capture(true, None);
+    let mut saved = Vec::new();
+    start_receiver(&app_state, cfg, NoEvents);
+    let mut tick = tokio::time::interval(Duration::from_millis(100));
+    loop {
+        tokio::select! {
+            _ = tick.tick() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        let raw = app_state.raw_captured_packets();
+        app_state.clear_packet_capture();
+        let packets: Vec<_> = raw.iter().map(inspector::inspect).collect();
+        if pcapng.is_some() {
+            saved.extend(raw);
+        }
+        for p in packets
+            .iter()
+            .filter(|p| only.is_empty() || only.contains(&p.opcode_name.to_lowercase()))
+        {
+            if json {
+                println!("{}", serde_json::to_string(p)?);
+            } else {
+                print_packet(p, hex);
+            }
+        }
+    }
+    app_state.stop_receiver();
+    if let Some(path) = pcapng {
+        let written = pcap::write_pcapng(&path, &saved)?;
+        eprintln!("Saved {} packets to {}", written, path);
+    }
+    Ok(())
+}
+
+async fn cmd_send(mut opts: Opts) -> Result<()> {
+    let mut cfg = SenderConfig::default();
+    if let Some(ip) = opts.take("target") {
+        cfg.target_ip = ip;
+    }
+    if let Some(port) = opts.parsed("port")? {
+        cfg.port = port;
+    }
+    if let Some(addr) = opts.parsed("universe")? {
+        cfg.port_address = addr;
+    }
+    if let Some(fps) = opts.parsed("fps")? {
+        cfg.fps = fps;
+    }
+    cfg.sync = opts.flag("sync");
+    cfg.unicast_subscribers = opts.flag("unicast");
+    let protocol = match opts.take("protocol") {
+        Some(p) => parse_protocol(&p)?,
+        None => OutputProtocol::ArtNet,
+    };
+    let start_code: u8 = opts.parsed("start-code")?.unwrap_or(0);
+    let mut values = [0u8; 512];
+    if let Some(all) = opts.parsed::<u8>("all")? {
+        values.fill(all);
+    }
+    if let Some(spec) = opts.take("set") {
+        apply_channel_spec(&mut values, &spec)?;
+    }
+    let once = opts.flag("once");
+    let duration: Option<f64> = opts.parsed("duration")?;
+    let timecode = match opts.parsed::<TimecodeType>("timecode")? {
+        Some(kind) => {
+            let tc = TimecodeConfig {
+                with_sender: true,
+                kind,
+                start: opts
+                    .take("tc-start")
+                    .unwrap_or_else(|| "00:00:00:00".into()),
+                stream_id: 0,
+            };
+            tc.start_timecode()?;
+            Some(tc)
+        }
+        None => None,
+    };
+    opts.finish()?;
+
+    let app_state = AppState::new();
+    app_state.set_sender_config(cfg.clone());
+    app_state.set_universe_protocol(cfg.port_address, protocol)?;
+    app_state.set_universe_start_code(cfg.port_address, start_code)?;
+    app_state.set_channels(&values);
+
+    if cfg.unicast_subscribers {
+        // Subscribers come from ArtPollReplies, which arrive on the Art-Net
+        // port; find them before the first frame goes out.
+        start_receiver(&app_state, ReceiverConfig::default(), NoEvents);
+        let hosts = discovery::broadcast_hosts(&cfg.target_ip, Vec::new());
+        let nodes = state::discover_nodes(&app_state, &hosts, cfg.port, 1500).await?;
+        state::apply_discovered_nodes(&NoEvents, &app_state, nodes, true);
+        for u in app_state.list_universes() {
+            if u.subscribers.is_empty() {
+                eprintln!("{}: no subscribers, broadcasting", u.port_address);
+            } else {
+                eprintln!(
+                    "{}: unicast to {}",
+                    u.port_address,
+                    u.subscribers.join(", ")
+                );
+            }
+        }
+        if !once {
+            let st = app_state.clone();
+            let handle = tokio::spawn(async move {
+                if let Err(e) = state::run_discovery_task(NoEvents, st).await {
+                    eprintln!("discovery task error: {e:?}");
+                }
+            });
+            app_state.set_discovery_task(handle);
+        }
+    }
+
+    if once {
+        let frame = app_state.snapshot_channels_tick_seq();
+        let sock = app_state.udp_for_send().await?;
+        let mut pkt = Vec::with_capacity(638);
+        state::send_universe_frame(
+            sock.as_ref(),
+            &cfg,
+            &app_state.get_sacn_config(),
+            &frame,
+            &mut pkt,
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let st = app_state.clone();
+    let sender_cfg = cfg.clone();
+    let handle = tokio::spawn(async move {
+        if let Err(e) = state::run_sender_task(sender_cfg, st).await {
+            eprintln!("sender task error: {e:?}");
+        }
+    });
+    app_state.set_sender_task(handle);
+    if let Some(tc) = timecode {
+        let st = app_state.clone();
+        let handle = tokio::spawn(async move {
+            if let Err(e) = state::run_timecode_task(tc, cfg, st).await {
+                eprintln!("timecode task error: {e:?}");
+            }
+        });
+        app_state.set_timecode_task(handle);
+    }
+    wait_for_stop(duration).await;
+    app_state.stop_sender().await;
+    app_state.stop_timecode();
+    app_state.stop_receiver();
+    Ok(())
+}
+
+/// Sender config for one-off packets: `--target` and `--port`.
+fn packet_target(opts: &mut Opts) -> Result<SenderConfig> {
+    let mut cfg = SenderConfig::default();
+    if let Some(ip) = opts.take("target") {
+        cfg.target_ip = ip;
+    }
+    if let Some(port) = opts.parsed("port")? {
+        cfg.port = port;
+    }
+    Ok(cfg)
+}
+
+fn parse_trigger_key(s: &str) -> Result<u8> {
+    match s {
+        "ascii" => Ok(0),
+        "macro" => Ok(1),
+        "soft" => Ok(2),
+        "show" => Ok(3),
+        n => n
+            .parse()
+            .map_err(|_| anyhow!("Key must be ascii, macro, soft, show or 0-255")),
+    }
+}
+
+async fn cmd_trigger(mut opts: Opts) -> Result<()> {
+    let cfg = packet_target(&mut opts)?;
+    let oem: u16 = opts.parsed("oem")?.unwrap_or(trigger::ALL_DEVICES);
+    let data = opts.take("data").unwrap_or_default();
+    let positional = opts.finish()?;
+    let [key, sub_key] = &positional[..] else {
+        return Err(anyhow!("trigger needs KEY and SUBKEY"));
+    };
+    let key = parse_trigger_key(key)?;
+    let sub_key: u8 = sub_key.parse()?;
+
+    let app_state = AppState::new();
+    app_state.set_sender_config(cfg);
+    let pkt = trigger::encode_trigger(oem, key, sub_key, data.as_bytes());
+    state::send_to_target(&app_state, None, &pkt).await
+}
+
+async fn cmd_command(mut opts: Opts) -> Result<()> {
+    let cfg = packet_target(&mut opts)?;
+    let esta: u16 = opts.parsed("esta")?.unwrap_or(trigger::ALL_DEVICES);
+    let positional = opts.finish()?;
+    let [text] = &positional[..] else {
+        return Err(anyhow!("command needs TEXT"));
+    };
+
+    let app_state = AppState::new();
+    app_state.set_sender_config(cfg);
+    let pkt = trigger::encode_command(esta, text);
+    state::send_to_target(&app_state, None, &pkt).await
+}
+
+async fn cmd_record(mut opts: Opts) -> Result<()> {
+    let cfg = receiver_config(&mut opts)?;
+    let universes = match opts.take("universe") {
+        Some(list) => list
+            .split(',')
+            .map(|u| u.trim().parse::<PortAddress>())
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    let duration: Option<f64> = opts.parsed("duration")?;
+    let compression: Compression = opts.parsed("compression")?.unwrap_or_default();
+    let window_sec: Option<u64> = opts.parsed("window")?;
+    let [path]: [String; 1] = opts
+        .finish()?
+        .try_into()
+        .map_err(|_| anyhow!("record needs exactly one output FILE"))?;
+
+    // Show files are streamed to disk rather than written at the end.
+    let spill = show::is_show_file(&path).then(|| SpillConfig {
+        path: path.clone(),
+        compression,
+        window_sec: window_sec.unwrap_or(state::DEFAULT_SPILL_WINDOW_SEC),
+    });
+    let app_state = AppState::new();
+    app_state.start_buffered_recording(universes, (0..512).collect(), spill)?;
+    start_receiver(&app_state, cfg, NoEvents);
+    eprintln!("Recording to {} (Ctrl-C to stop)", path);
+    wait_for_stop(duration).await;
+    app_state.stop_receiver();
+    app_state.stop_buffered_recording().await;
+
+    let status = app_state
+        .record_status()
+        .ok_or_else(|| anyhow!("No recording data available"))?;
+    if let Some(e) = &status.spill_error {
+        eprintln!("warning: writing {} stopped: {}", path, e);
+    }
+    if status.dropped_frames > 0 {
+        eprintln!(
+            "warning: {} of the oldest frames exceeded the memory limit and were \
+             dropped; record to .artrec to keep everything",
+            status.dropped_frames
+        );
+    }
+    if status.spill_path.is_some() {
+        eprintln!("Wrote {} frames to {}", status.spilled_frames, path);
+        return Ok(());
+    }
+
+    let data = app_state
+        .record_data_snapshot()
+        .ok_or_else(|| anyhow!("No recording data available"))?;
+    save_recording(&path, &data, compression)
+}
+
+/// Writes `data` by the extension of `path`, one .wav per universe when
+/// there are several, and prints each track.
+fn save_recording(path: &str, data: &RecordData, compression: Compression) -> Result<()> {
+    if path.to_lowercase().ends_with(".wav") && data.tracks.len() > 1 {
+        // WAV holds a single universe: name each file after its universe.
+        let stem = &path[..path.len() - 4];
+        for track in &data.tracks {
+            let addr = track.port_address;
+            let file = format!(
+                "{}-{}-{}-{}.wav",
+                stem,
+                addr.net(),
+                addr.subnet(),
+                addr.universe()
+            );
+            recording::write_wav(&file, data, Some(addr))?;
+            eprintln!("Wrote {}", file);
+        }
+    } else if path.to_lowercase().ends_with(".wav") {
+        recording::write_wav(path, data, None)?;
+    } else if show::is_show_file(path) {
+        show::write_record_data(path, data, compression, show::DEFAULT_KEYFRAME_MS)?;
+    } else {
+        recording::write_jsonl(path, data)?;
+    }
+    for track in &data.tracks {
+        eprintln!(
+            "{}: {} frames ({} ms)",
+            track.port_address,
+            track.frame_count(),
+            track.duration_ms()
+        );
+    }
+    Ok(())
+}
+
+/// `N:S:U:CH`, the universe and 1-based channel of a pre-roll trigger.
+fn parse_universe_channel(s: &str) -> Result<(PortAddress, u16)> {
+    let (addr, ch) = s
+        .rsplit_once(':')
+        .ok_or_else(|| anyhow!("Expected N:S:U:CH, got {:?}", s))?;
+    let ch: u16 = ch.parse()?;
+    if !(1..=512).contains(&ch) {
+        return Err(anyhow!("Channel must be 1-512"));
+    }
+    Ok((addr.parse()?, ch))
+}
+
+fn parse_threshold(s: &str, crossing: Crossing) -> Result<PreRollTrigger> {
+    let (target, value) = s
+        .rsplit_once('=')
+        .ok_or_else(|| anyhow!("Expected N:S:U:CH=V, got {:?}", s))?;
+    let (port_address, channel) = parse_universe_channel(target)?;
+    Ok(PreRollTrigger::Threshold {
+        port_address,
+        channel,
+        threshold: value.parse()?,
+        crossing,
+    })
+}
+
+fn preroll_triggers(opts: &mut Opts) -> Result<Vec<PreRollTrigger>> {
+    let mut triggers = Vec::new();
+    while let Some(s) = opts.take("above") {
+        triggers.push(parse_threshold(&s, Crossing::Rising)?);
+    }
+    while let Some(s) = opts.take("below") {
+        triggers.push(parse_threshold(&s, Crossing::Falling)?);
+    }
+    while let Some(s) = opts.take("change") {
+        let (port_address, channel) = parse_universe_channel(&s)?;
+        triggers.push(PreRollTrigger::Change {
+            port_address,
+            channel,
+        });
+    }
+    let timeout_ms: u64 = opts.parsed("stop-ms")?.unwrap_or(1000);
+    while let Some(s) = opts.take("stopped") {
+        triggers.push(PreRollTrigger::UniverseStopped {
+            port_address: s.parse()?,
+            timeout_ms,
+        });
+    }
+    while let Some(s) = opts.take("on-trigger") {
+        let (key, sub_key) = match s.split_once(':') {
+            Some((key, sub)) => (key, Some(sub.parse()?)),
+            None => (s.as_str(), None),
+        };
+        triggers.push(PreRollTrigger::Packet {
+            on: TriggerMatch::Trigger {
+                key: parse_trigger_key(key)?,
+                sub_key,
+            },
+        });
+    }
+    Ok(triggers)
+}
+
+async fn cmd_preroll(mut opts: Opts) -> Result<()> {
+    use tokio::io::AsyncBufReadExt;
+
+    let cfg = receiver_config(&mut opts)?;
+    let universes = match opts.take("universe") {
+        Some(list) => list
+            .split(',')
+            .map(|u| u.trim().parse::<PortAddress>())
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    let pre_sec: f64 = opts.parsed("pre")?.unwrap_or(10.0);
+    let post_sec: f64 = opts.parsed("post")?.unwrap_or(5.0);
+    let triggers = preroll_triggers(&mut opts)?;
+    let compression: Compression = opts.parsed("compression")?.unwrap_or_default();
+    let repeat = opts.flag("repeat");
+    let [path]: [String; 1] = opts
+        .finish()?
+        .try_into()
+        .map_err(|_| anyhow!("preroll needs exactly one output FILE"))?;
+
+    let app_state = AppState::new();
+    state::start_preroll(
+        &app_state,
+        PreRollConfig {
+            pre_sec,
+            post_sec,
+            universes,
+            triggers,
+        },
+        NoEvents,
+    );
+    start_receiver(&app_state, cfg, NoEvents);
+    eprintln!(
+        "Keeping the last {} s (Enter to capture, Ctrl-C to stop)",
+        pre_sec
+    );
+    let mut stdin = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+    let mut poll = tokio::time::interval(Duration::from_millis(100));
+    let mut saved = 0;
+    let mut capturing = None;
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break,
+            Ok(Some(_)) = stdin.next_line() => {
+                app_state.trigger_preroll("manual")?;
+            }
+            _ = poll.tick() => {}
+        }
+        let Some(status) = app_state.preroll_status() else {
+            break;
+        };
+        if status.capturing != capturing {
+            if let Some(reason) = &status.capturing {
+                eprintln!("Triggered: {} (recording {} s more)", reason, post_sec);
+            }
+            capturing = status.capturing;
+        }
+        if status.captures == saved {
+            continue;
+        }
+        saved = status.captures;
+        let info = app_state.load_preroll_capture()?;
+        let data = app_state
+            .record_data_snapshot()
+            .ok_or_else(|| anyhow!("No recording data available"))?;
+        let file = match (repeat, path.rsplit_once('.')) {
+            (false, _) => path.clone(),
+            (true, Some((stem, ext))) => format!("{}-{}.{}", stem, saved, ext),
+            (true, None) => format!("{}-{}", path, saved),
+        };
+        save_recording(&file, &data, compression)?;
+        eprintln!(
+            "Saved {} ({}, trigger at {} ms)",
+            file, info.reason, info.trigger_ms
+        );
+        if info.dropped_frames > 0 {
+            eprintln!(
+                "warning: {} of the oldest frames did not fit the pre-roll buffer; \
+                 shorten --pre or --post",
+                info.dropped_frames
+            );
+        }
+        if !repeat {
+            break;
+        }
+    }
+    app_state.stop_receiver();
+    Ok(())
+}
+
+async fn cmd_play(mut opts: Opts) -> Result<()> {
+    let mut cfg = SenderConfig::default();
+    if let Some(ip) = opts.take("target") {
+        cfg.target_ip = ip;
+    }
+    if let Some(port) = opts.parsed("port")? {
+        cfg.port = port;
+    }
+    if let Some(addr) = opts.parsed("universe")? {
+        cfg.port_address = addr;
+    }
+    let start_ms: u64 = opts.parsed("start-ms")?.unwrap_or(0);
+    let loop_playback = opts.flag("loop");
+    let chase = opts
+        .take("chase")
+        .map(|s| Timecode::parse(&s, TimecodeType::Smpte))
+        .transpose()?;
+    let mut recv_cfg = ReceiverConfig::default();
+    if let Some(ip) = opts.take("bind") {
+        recv_cfg.bind_ip = ip;
+    }
+    if let Some(port) = opts.parsed("listen-port")? {
+        recv_cfg.port = port;
+    }
+    let [path]: [String; 1] = opts
+        .finish()?
+        .try_into()
+        .map_err(|_| anyhow!("play needs exactly one recording FILE"))?;
+
+    let app_state = AppState::new();
+    let play = async {
+        if let Some(origin) = chase {
+            let frames = if path.to_lowercase().ends_with(".wav") {
+                state::wav_frames(&recording::load_wav(&path)?, cfg.port_address)
+            } else {
+                state::load_play_frames(&path)?
+            };
+            start_receiver(&app_state, recv_cfg, NoEvents);
+            eprintln!("Chasing timecode from {} (Ctrl-C to stop)", origin);
+            state::run_chase_task(frames, cfg, origin, NoEvents, app_state.clone()).await
+        } else if path.to_lowercase().ends_with(".wav") {
+            let wav = recording::load_wav(&path)?;
+            state::run_wav_play_task(wav, cfg, start_ms, loop_playback).await
+        } else {
+            state::run_play_task(path.clone(), cfg, start_ms, loop_playback).await
+        }
+    };
+    tokio::select! {
+        r = play => r,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+fn cmd_convert(mut opts: Opts) -> Result<()> {
+    let compression: Compression = opts.parsed("compression")?.unwrap_or_default();
+    let keyframe_ms: u32 = opts
+        .parsed("keyframe-ms")?
+        .unwrap_or(show::DEFAULT_KEYFRAME_MS);
+    let [src, dst]: [String; 2] = opts
+        .finish()?
+        .try_into()
+        .map_err(|_| anyhow!("convert needs a SRC and a DST file"))?;
+    let frames = show::convert(&src, &dst, compression, keyframe_ms)?;
+    let size = |path: &str| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    eprintln!(
+        "Wrote {} frames to {} ({} bytes, from {} bytes)",
+        frames,
+        dst,
+        size(&dst),
+        size(&src)
+    );
+    Ok(())
+}
+
+async fn cmd_discover(mut opts: Opts) -> Result<()> {
+    let target = opts.take("target").unwrap_or_default();
+    let port: u16 = opts
+        .parsed("port")?
+        .unwrap_or(artnetlab_lib::artnet::ARTNET_PORT);
+    let timeout_ms: u64 = opts.parsed("timeout")?.unwrap_or(2000);
+    let json = opts.flag("json");
+    opts.finish()?;
+
+    // Nodes reply to the Art-Net port; listen there and relay into the scan.
+    let app_state = AppState::new();
+    start_receiver(&app_state, ReceiverConfig::default(), NoEvents);
+    let hosts = discovery::broadcast_hosts(&target, Vec::new());
+    let nodes = state::discover_nodes(&app_state, &hosts, port, timeout_ms).await;
+    app_state.stop_receiver();
+    let mut nodes = nodes?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&nodes)?);
+        return Ok(());
+    }
+    nodes.sort_by(|a, b| a.reply.ip.cmp(&b.reply.ip));
+    for node in nodes {
+        println!(
+            "{:<15} {:<17} bind {:>3}  {}  {}",
+            node.reply.ip,
+            node.reply.mac,
+            node.reply.bind_index,
+            node.reply.short_name,
+            node.reply.long_name
+        );
+    }
+    Ok(())
+}
+
+fn parse_address_command(s: &str) -> Result<AddressCommand> {
+    let (name, port) = match s.split_once(':') {
+        Some((name, port)) => {
+            let port: u8 = port
+                .parse()
+                .map_err(|_| anyhow!("Invalid port in --command {:?}", s))?;
+            (name, Some(port))
+        }
+        None => (s, None),
+    };
+    let cmd = match (name, port) {
+        ("cancel-merge", None) => AddressCommand::CancelMerge,
+        ("led-normal", None) => AddressCommand::LedNormal,
+        ("led-mute", None) => AddressCommand::LedMute,
+        ("led-locate", None) => AddressCommand::LedLocate,
+        ("reset-rx", None) => AddressCommand::ResetRxFlags,
+        ("fail-hold", None) => AddressCommand::FailHold,
+        ("fail-zero", None) => AddressCommand::FailZero,
+        ("fail-full", None) => AddressCommand::FailFull,
+        ("fail-scene", None) => AddressCommand::FailScene,
+        ("merge-ltp", Some(p)) => AddressCommand::MergeLtp(p),
+        ("merge-htp", Some(p)) => AddressCommand::MergeHtp(p),
+        ("artnet", Some(p)) => AddressCommand::ArtNetSel(p),
+        ("sacn", Some(p)) => AddressCommand::AcnSel(p),
+        ("clear", Some(p)) => AddressCommand::ClearOutput(p),
+        _ => return Err(anyhow!("Unknown --command {:?}", s)),
+    };
+    Ok(cmd)
+}
+
+/// Parses `PORT=UNIVERSE,..` into per-port switch values.
+fn parse_port_universes(spec: &str) -> Result<[Option<u8>; 4]> {
+    let mut out = [None; 4];
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (port, uni) = part
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected PORT=UNIVERSE, got {:?}", part))?;
+        let port: usize = port.trim().parse()?;
+        let slot = out
+            .get_mut(port)
+            .ok_or_else(|| anyhow!("Port {} out of range 0..=3", port))?;
+        *slot = Some(uni.trim().parse()?);
+    }
+    Ok(out)
+}
+
+async fn cmd_address(mut opts: Opts) -> Result<()> {
+    let mut req = AddressRequest {
+        port: opts
+            .parsed("port")?
+            .unwrap_or(artnetlab_lib::artnet::ARTNET_PORT),
+        bind_index: opts.parsed("bind")?.unwrap_or(1),
+        short_name: opts.take("short-name"),
+        long_name: opts.take("long-name"),
+        net_switch: opts.parsed("net")?,
+        sub_switch: opts.parsed("subnet")?,
+        acn_priority: opts.parsed("acn-priority")?,
+        ..AddressRequest::default()
+    };
+    if let Some(spec) = opts.take("in") {
+        req.sw_in = parse_port_universes(&spec)?;
+    }
+    if let Some(spec) = opts.take("out") {
+        req.sw_out = parse_port_universes(&spec)?;
+    }
+    if let Some(cmd) = opts.take("command") {
+        req.command = parse_address_command(&cmd)?;
+    }
+    let timeout_ms: u64 = opts.parsed("timeout")?.unwrap_or(1500);
+    let json = opts.flag("json");
+    let mut positional = opts.finish()?;
+    if positional.len() != 1 {
+        return Err(anyhow!("address needs the node IP"));
+    }
+    req.ip = positional.remove(0);
+
+    let app_state = AppState::new();
+    start_receiver(&app_state, ReceiverConfig::default(), NoEvents);
+    let outcome = state::address_node(&NoEvents, &app_state, &req, timeout_ms).await;
+    app_state.stop_receiver();
+    let outcome = outcome?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+    } else if outcome.verified {
+        println!("{}: change confirmed by ArtPollReply", req.ip);
+    } else {
+        for m in &outcome.mismatches {
+            println!("{}: {}", req.ip, m);
+        }
+    }
+    if !outcome.verified {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_ip_settings(label: &str, s: &IpSettings) {
+    println!(
+        "{:<8} ip {}  mask {}  gateway {}  port {}  dhcp {}",
+        label,
+        s.ip,
+        s.subnet_mask,
+        s.gateway,
+        s.port,
+        if s.dhcp { "on" } else { "off" }
+    );
+}
+
+fn describe_ip_prog(req: &IpProgRequest) -> String {
+    if req.dhcp {
+        return "DHCP on".into();
+    }
+    let mut parts = Vec::new();
+    if req.reset_defaults {
+        parts.push("factory defaults".to_string());
+    }
+    for (label, value) in [
+        ("ip", &req.new_ip),
+        ("mask", &req.subnet_mask),
+        ("gateway", &req.gateway),
+    ] {
+        if let Some(v) = value {
+            parts.push(format!("{} {}", label, v));
+        }
+    }
+    if let Some(port) = req.prog_port {
+        parts.push(format!("port {}", port));
+    }
+    parts.join(", ")
+}
+
+async fn cmd_ipprog(mut opts: Opts) -> Result<()> {
+    let mut req = IpProgRequest {
+        port: opts
+            .parsed("port")?
+            .unwrap_or(artnetlab_lib::artnet::ARTNET_PORT),
+        new_ip: opts.take("ip"),
+        subnet_mask: opts.take("mask"),
+        gateway: opts.take("gateway"),
+        prog_port: opts.parsed("prog-port")?,
+        dhcp: opts.flag("dhcp"),
+        reset_defaults: opts.flag("reset"),
+        ..IpProgRequest::default()
+    };
+    let confirmed = opts.flag("yes");
+    let timeout_ms: u64 = opts.parsed("timeout")?.unwrap_or(2000);
+    let json = opts.flag("json");
+    let mut positional = opts.finish()?;
+    if positional.len() != 1 {
+        return Err(anyhow!("ipprog needs the node IP"));
+    }
+    req.ip = positional.remove(0);
+
+    let app_state = AppState::new();
+    start_receiver(&app_state, ReceiverConfig::default(), NoEvents);
+    let result = async {
+        let query = IpProgRequest {
+            ip: req.ip.clone(),
+            port: req.port,
+            ..IpProgRequest::default()
+        };
+        let current = state::ip_prog_node(&app_state, &query, timeout_ms).await?;
+        if req.is_query() {
+            return Ok(current);
+        }
+        // Show what is about to change before touching the node.
+        if let Some(s) = &current.reply {
+            print_ip_settings("current", s);
+        }
+        if !confirmed {
+            return Err(anyhow!(
+                "Would set {} on {}; add --yes to program the node",
+                describe_ip_prog(&req),
+                req.ip
+            ));
+        }
+        state::ip_prog_node(&app_state, &req, timeout_ms).await
+    }
+    .await;
+    app_state.stop_receiver();
+    let outcome = result?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&outcome)?);
+    } else {
+        if let Some(s) = &outcome.reply {
+            print_ip_settings(if req.is_query() { "node" } else { "now" }, s);
+        }
+        for m in &outcome.mismatches {
+            println!("{}: {}", req.ip, m);
+        }
+    }
+    if !outcome.verified {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Options of `command` that take no value.
+fn command_flags(command: &str) -> &'static [&'static str] {
+    match command {
+        "monitor" => &["reject-out-of-order", "stats", "json"],
+        "inspect" => &["hex", "json"],
+        "send" => &["sync", "unicast", "once"],
+        "play" => &["loop"],
+        "preroll" => &["repeat"],
+        "discover" | "address" => &["json"],
+        "ipprog" => &["dhcp", "reset", "yes", "json"],
+        _ => &[],
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(command) = args.next() else {
+        eprint!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let result = match Opts::parse(args, command_flags(&command)) {
+        Err(e) => Err(e),
+        Ok(opts) => match command.as_str() {
+            "monitor" => cmd_monitor(opts).await,
+            "inspect" => cmd_inspect(opts).await,
+            "send" => cmd_send(opts).await,
+            "trigger" => cmd_trigger(opts).await,
+            "command" => cmd_command(opts).await,
+            "record" => cmd_record(opts).await,
+            "preroll" => cmd_preroll(opts).await,
+            "play" => cmd_play(opts).await,
+            "convert" => cmd_convert(opts),
+            "discover" => cmd_discover(opts).await,
+            "address" => cmd_address(opts).await,
+            "ipprog" => cmd_ipprog(opts).await,
+            "help" | "--help" | "-h" => {
+                print!("{}", USAGE);
+                Ok(())
+            }
+            other => Err(anyhow!("Unknown command {:?}\n\n{}", other, USAGE)),
+        },
+    };
+    if let Err(e) = result {
+        eprintln!("error: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn send_opts(args: &[&str]) -> Result<Opts> {
+        Opts::parse(args.iter().map(|a| a.to_string()), command_flags("send"))
+    }
+
+    #[test]
+    fn send_flags_take_no_value() {
+        for args in [["--once", "--unicast"], ["--unicast", "--once"]] {
+            let mut opts = send_opts(&args).unwrap();
+            assert!(opts.flag("unicast"));
+            assert!(opts.flag("once"));
+            assert!(opts.finish().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn send_options_still_take_a_value() {
+        let mut opts = send_opts(&["--fps", "30", "--sync"]).unwrap();
+        assert!(opts.flag("sync"));
+        assert_eq!(opts.parsed::<u32>("fps").unwrap(), Some(30));
+        assert!(send_opts(&["--fps"]).is_err());
+    }
+}
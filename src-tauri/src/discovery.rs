@@ -7,41 +7,25 @@ use std::{
 use anyhow::{anyhow, Result};
 use artnet_protocol::{ArtCommand, Poll};
 use tokio::net::UdpSocket;
+use tokio::sync::broadcast;
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+use crate::artnet::PortAddress;
+use crate::node::{self, PollReply};
+use crate::sacn::OutputProtocol;
+
+/// Replies seen by the receiver socket (ArtPollReply, ArtIpProgReply,
+/// ArtTodData, ArtRdm), relayed to running scans and requests.
+pub type ReplyRelay = broadcast::Sender<(SocketAddr, Vec<u8>)>;
+
+/// Background scans missed before a node counts as lost.
+const LOST_AFTER_MISSED_SCANS: u32 = 3;
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ArtNetDiscoveredNode {
     pub udp_source: String,
-    pub ip: String,
-    pub artnet_port: u16,
-    pub short_name: String,
-    pub long_name: String,
-    pub node_report: String,
-    pub net_switch: u8,
-    pub sub_switch: u8,
-    pub firmware: String,
-    pub oem_hex: String,
-    pub esta_code: u16,
-    pub mac: String,
-    pub bind_ip: String,
-    pub bind_index: u8,
-    pub style: u8,
-    pub num_ports: [u8; 2],
-    pub port_types_hex: String,
-    pub swin_hex: String,
-    pub swout_hex: String,
-}
-
-fn cstr_trim(bytes: &[u8]) -> String {
-    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
-    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
-}
-
-fn format_mac(m: &[u8; 6]) -> String {
-    m.iter()
-        .map(|b| format!("{:02x}", b))
-        .collect::<Vec<_>>()
-        .join(":")
+    #[serde(flatten)]
+    pub reply: PollReply,
 }
 
 pub fn subnet_broadcast_addrs() -> Vec<String> {
@@ -69,6 +53,22 @@ pub fn subnet_broadcast_addrs() -> Vec<String> {
     addrs
 }
 
+/// Hosts an ArtPoll scan is sent to: every subnet broadcast, the sender
+/// target, the limited broadcast and any extra addresses, deduplicated.
+pub fn broadcast_hosts(target_ip: &str, extra: Vec<String>) -> Vec<String> {
+    let mut hosts = subnet_broadcast_addrs();
+    let t = target_ip.trim();
+    if !t.is_empty() {
+        hosts.push(t.to_string());
+    }
+    hosts.push("255.255.255.255".into());
+    hosts.extend(extra);
+    hosts.retain(|s| !s.trim().is_empty());
+    let mut uniq = HashSet::new();
+    hosts.retain(|s| uniq.insert(s.clone()));
+    hosts
+}
+
 fn ipv4_broadcast_from_netmask(ip: Ipv4Addr, mask: Ipv4Addr) -> Ipv4Addr {
     if mask.is_broadcast() || mask.is_unspecified() {
         return Ipv4Addr::BROADCAST;
@@ -99,7 +99,7 @@ pub async fn scan_artnet(
     broadcast_hosts: &[String],
     port: u16,
     timeout_ms: u64,
-    relay_holder: &mut Option<tokio::sync::mpsc::Receiver<(SocketAddr, Vec<u8>)>>,
+    mut relay: Option<broadcast::Receiver<(SocketAddr, Vec<u8>)>>,
 ) -> Result<Vec<ArtNetDiscoveredNode>> {
     let sock = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).await?;
     sock.set_broadcast(true)?;
@@ -117,6 +117,7 @@ pub async fn scan_artnet(
     enum Combo {
         Udp(std::io::Result<(usize, SocketAddr)>),
         Relay(SocketAddr, Vec<u8>),
+        RelayLagged,
         RelayClosed,
     }
 
@@ -124,59 +125,21 @@ pub async fn scan_artnet(
     let mut buf = [0u8; 2048];
     let mut dedup = HashSet::new();
     let mut out = Vec::new();
+    let mut relay_open = relay.is_some();
     let mut last_poll = Instant::now();
 
     let ingest_packet = |data: &[u8],
                          udp_src: SocketAddr,
-                         dedup: &mut HashSet<(Ipv4Addr, [u8; 6])>,
+                         dedup: &mut HashSet<(String, String, u8)>,
                          out: &mut Vec<ArtNetDiscoveredNode>| {
-        if let Ok(ArtCommand::PollReply(reply)) = ArtCommand::from_buffer(data) {
-            let reply = *reply;
-            let key = (reply.address, reply.mac);
+        if let Ok(reply) = node::parse_poll_reply(data) {
+            let key = (reply.ip.clone(), reply.mac.clone(), reply.bind_index);
             if !dedup.insert(key) {
                 return;
             }
             out.push(ArtNetDiscoveredNode {
                 udp_source: udp_src.to_string(),
-                ip: reply.address.to_string(),
-                artnet_port: reply.port,
-                short_name: cstr_trim(&reply.short_name),
-                long_name: cstr_trim(&reply.long_name),
-                node_report: cstr_trim(&reply.node_report),
-                net_switch: reply.port_address[0],
-                sub_switch: reply.port_address[1],
-                firmware: format!("{}.{}", reply.version[1], reply.version[0]),
-                oem_hex: format!("{:02x}{:02x}", reply.oem[0], reply.oem[1]),
-                esta_code: reply.esta_code,
-                mac: format_mac(&reply.mac),
-                bind_ip: Ipv4Addr::new(
-                    reply.bind_ip[0],
-                    reply.bind_ip[1],
-                    reply.bind_ip[2],
-                    reply.bind_ip[3],
-                )
-                .to_string(),
-                bind_index: reply.bind_index,
-                style: reply.style,
-                num_ports: reply.num_ports,
-                port_types_hex: reply
-                    .port_types
-                    .iter()
-                    .map(|b| format!("{:02x}", b))
-                    .collect::<Vec<_>>()
-                    .join(" "),
-                swin_hex: reply
-                    .swin
-                    .iter()
-                    .map(|b| format!("{:02x}", b))
-                    .collect::<Vec<_>>()
-                    .join(" "),
-                swout_hex: reply
-                    .swout
-                    .iter()
-                    .map(|b| format!("{:02x}", b))
-                    .collect::<Vec<_>>()
-                    .join(" "),
+                reply,
             });
         }
     };
@@ -193,16 +156,16 @@ pub async fn scan_artnet(
             .max(Duration::from_millis(1));
 
         let combo = async {
-            if let Some(rx) = relay_holder.as_mut() {
-                tokio::select! {
+            match relay.as_mut().filter(|_| relay_open) {
+                Some(rx) => tokio::select! {
                     r = sock.recv_from(&mut buf) => Combo::Udp(r),
-                    opt = rx.recv() => match opt {
-                        Some((relay_src, v)) => Combo::Relay(relay_src, v),
-                        None => Combo::RelayClosed,
+                    res = rx.recv() => match res {
+                        Ok((relay_src, v)) => Combo::Relay(relay_src, v),
+                        Err(broadcast::error::RecvError::Lagged(_)) => Combo::RelayLagged,
+                        Err(broadcast::error::RecvError::Closed) => Combo::RelayClosed,
                     },
-                }
-            } else {
-                Combo::Udp(sock.recv_from(&mut buf).await)
+                },
+                None => Combo::Udp(sock.recv_from(&mut buf).await),
             }
         };
 
@@ -214,10 +177,138 @@ pub async fn scan_artnet(
             Ok(Combo::Relay(src, pkt)) => {
                 ingest_packet(&pkt, src, &mut dedup, &mut out);
             }
-            Ok(Combo::RelayClosed) => {}
+            Ok(Combo::RelayLagged) => {}
+            Ok(Combo::RelayClosed) => relay_open = false,
             Err(_) => {}
         }
     }
 
     Ok(out)
 }
+
+fn unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// A discovered node with the times it was first and last heard from.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedNode {
+    #[serde(flatten)]
+    pub node: ArtNetDiscoveredNode,
+    pub first_seen_ms: u64, // unix time
+    pub last_seen_ms: u64,
+    #[serde(skip)]
+    missed_scans: u32,
+}
+
+impl TrackedNode {
+    /// Same reply fields, ignoring the node report whose counter moves on
+    /// every reply and the UDP source port.
+    fn same_fields(&self, other: &ArtNetDiscoveredNode) -> bool {
+        let strip = |n: &ArtNetDiscoveredNode| PollReply {
+            node_report: String::new(),
+            ..n.reply.clone()
+        };
+        strip(&self.node) == strip(other)
+    }
+}
+
+/// Nodes keyed by IP, MAC and bind index (one entry per ArtPollReply page).
+#[derive(Default)]
+pub struct NodeTable {
+    nodes: Vec<TrackedNode>,
+}
+
+#[derive(Default)]
+pub struct NodeChanges {
+    pub added: Vec<TrackedNode>,
+    pub changed: Vec<TrackedNode>,
+    pub lost: Vec<TrackedNode>,
+}
+
+impl NodeTable {
+    pub fn list(&self) -> Vec<TrackedNode> {
+        self.nodes.clone()
+    }
+
+    /// IPs of the nodes with an output port taking `addr` from Art-Net
+    /// (SwOut in their ArtPollReply), for unicast sending.
+    pub fn subscribers(&self, addr: PortAddress) -> Vec<String> {
+        let mut ips: Vec<String> = self
+            .nodes
+            .iter()
+            .filter(|t| {
+                t.node.reply.ports.iter().any(|p| {
+                    p.output.as_ref().is_some_and(|o| {
+                        o.port_address == addr && o.source == OutputProtocol::ArtNet
+                    })
+                })
+            })
+            .map(|t| t.node.reply.ip.clone())
+            .collect();
+        ips.sort();
+        ips.dedup();
+        ips
+    }
+
+    fn position(&self, node: &ArtNetDiscoveredNode) -> Option<usize> {
+        self.nodes.iter().position(|t| {
+            t.node.reply.ip == node.reply.ip
+                && t.node.reply.mac == node.reply.mac
+                && t.node.reply.bind_index == node.reply.bind_index
+        })
+    }
+
+    /// Merges the replies of a scan. With `complete` set (a periodic scan),
+    /// nodes absent from it count a missed scan and are dropped after
+    /// several in a row.
+    pub fn update(&mut self, replies: Vec<ArtNetDiscoveredNode>, complete: bool) -> NodeChanges {
+        let now = unix_ms();
+        let mut changes = NodeChanges::default();
+        let mut seen = vec![false; self.nodes.len()];
+        for node in replies {
+            match self.position(&node) {
+                Some(idx) => {
+                    if let Some(flag) = seen.get_mut(idx) {
+                        *flag = true;
+                    }
+                    let entry = &mut self.nodes[idx];
+                    let changed = !entry.same_fields(&node);
+                    entry.node = node;
+                    entry.last_seen_ms = now;
+                    entry.missed_scans = 0;
+                    if changed {
+                        changes.changed.push(entry.clone());
+                    }
+                }
+                None => {
+                    let entry = TrackedNode {
+                        node,
+                        first_seen_ms: now,
+                        last_seen_ms: now,
+                        missed_scans: 0,
+                    };
+                    changes.added.push(entry.clone());
+                    self.nodes.push(entry);
+                }
+            }
+        }
+        if complete {
+            for (entry, seen) in self.nodes.iter_mut().zip(seen) {
+                if !seen {
+                    entry.missed_scans += 1;
+                }
+            }
+            let (lost, kept) = std::mem::take(&mut self.nodes)
+                .into_iter()
+                .partition(|t| t.missed_scans >= LOST_AFTER_MISSED_SCANS);
+            self.nodes = kept;
+            changes.lost = lost;
+        }
+        changes
+    }
+}
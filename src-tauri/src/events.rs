@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+/// Destination for the engine's events ("artnet:dmx", "artnet:stats", ...).
+/// The desktop app forwards them to the webview; headless front ends can
+/// print, log or drop them.
+pub trait EventSink: Clone + Send + Sync + 'static {
+    fn emit<T: Serialize + Clone>(&self, event: &str, payload: T);
+}
+
+#[cfg(feature = "desktop")]
+impl EventSink for tauri::AppHandle {
+    fn emit<T: Serialize + Clone>(&self, event: &str, payload: T) {
+        let _ = tauri::Emitter::emit(self, event, payload);
+    }
+}
+
+/// Discards every event.
+#[derive(Clone, Copy, Default)]
+pub struct NoEvents;
+
+impl EventSink for NoEvents {
+    fn emit<T: Serialize + Clone>(&self, _event: &str, _payload: T) {}
+}
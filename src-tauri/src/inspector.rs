@@ -0,0 +1,663 @@
+use std::collections::VecDeque;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::artnet::{self, PortAddress};
+use crate::rdm::Uid;
+use crate::{node, remote, timecode, trigger};
+
+pub const DEFAULT_CAPTURE_CAPACITY: usize = 2000;
+pub const MAX_CAPTURE_CAPACITY: usize = 100_000;
+const HEX_ROW: usize = 16;
+
+/// Name of an Art-Net OpCode.
+pub fn opcode_name(op: u16) -> Option<&'static str> {
+    Some(match op {
+        0x2000 => "ArtPoll",
+        0x2100 => "ArtPollReply",
+        0x2300 => "ArtDiagData",
+        0x2400 => "ArtCommand",
+        0x2700 => "ArtDataRequest",
+        0x2800 => "ArtDataReply",
+        0x5000 => "ArtDmx",
+        0x5100 => "ArtNzs",
+        0x5200 => "ArtSync",
+        0x6000 => "ArtAddress",
+        0x7000 => "ArtInput",
+        0x8000 => "ArtTodRequest",
+        0x8100 => "ArtTodData",
+        0x8200 => "ArtTodControl",
+        0x8300 => "ArtRdm",
+        0x8400 => "ArtRdmSub",
+        0x9000 => "ArtMedia",
+        0x9100 => "ArtMediaPatch",
+        0x9200 => "ArtMediaControl",
+        0x9300 => "ArtMediaControlReply",
+        0x9700 => "ArtTimeCode",
+        0x9800 => "ArtTimeSync",
+        0x9900 => "ArtTrigger",
+        0x9a00 => "ArtDirectory",
+        0x9b00 => "ArtDirectoryReply",
+        0xa010 => "ArtVideoSetup",
+        0xa020 => "ArtVideoPalette",
+        0xa040 => "ArtVideoData",
+        0xf000 => "ArtMacMaster",
+        0xf100 => "ArtMacSlave",
+        0xf200 => "ArtFirmwareMaster",
+        0xf300 => "ArtFirmwareReply",
+        0xf400 => "ArtFileTnMaster",
+        0xf500 => "ArtFileFnMaster",
+        0xf600 => "ArtFileFnReply",
+        0xf800 => "ArtIpProg",
+        0xf900 => "ArtIpProgReply",
+        _ => return None,
+    })
+}
+
+/// One node of a decoded packet; groups have children and no value.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Field {
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub value: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<Field>,
+}
+
+fn leaf(name: &str, value: impl ToString) -> Field {
+    Field {
+        name: name.to_string(),
+        value: value.to_string(),
+        children: Vec::new(),
+    }
+}
+
+fn group(name: &str, children: Vec<Field>) -> Field {
+    Field {
+        name: name.to_string(),
+        value: String::new(),
+        children,
+    }
+}
+
+fn flag(name: &str, byte: u8, mask: u8) -> Field {
+    leaf(name, byte & mask != 0)
+}
+
+/// Fields of a value decoded by one of the typed parsers.
+fn json_fields(value: &impl serde::Serialize) -> Vec<Field> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map.iter().map(|(k, v)| json_field(k, v)).collect(),
+        Ok(other) => vec![json_field("value", &other)],
+        Err(e) => vec![leaf("error", e)],
+    }
+}
+
+fn json_field(name: &str, value: &Value) -> Field {
+    match value {
+        Value::Object(map) => group(name, map.iter().map(|(k, v)| json_field(k, v)).collect()),
+        Value::Array(items) => group(
+            name,
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, v)| json_field(&i.to_string(), v))
+                .collect(),
+        ),
+        Value::String(s) => leaf(name, s),
+        Value::Null => leaf(name, "—"),
+        // Port-Addresses serialize as their 15-bit value.
+        Value::Number(n) if name.ends_with("ortAddress") => {
+            match n.as_u64().and_then(|v| PortAddress::new(v as u16).ok()) {
+                Some(addr) => leaf(name, format!("{} ({})", addr, n)),
+                None => leaf(name, n),
+            }
+        }
+        other => leaf(name, other),
+    }
+}
+
+fn need(buf: &[u8], len: usize, what: &str) -> Result<()> {
+    if buf.len() < len {
+        return Err(anyhow!("{} shorter than {} bytes", what, len));
+    }
+    Ok(())
+}
+
+fn be16(buf: &[u8], at: usize) -> u16 {
+    u16::from_be_bytes([buf[at], buf[at + 1]])
+}
+
+fn ipv4(buf: &[u8], at: usize) -> Ipv4Addr {
+    Ipv4Addr::new(buf[at], buf[at + 1], buf[at + 2], buf[at + 3])
+}
+
+fn text(buf: &[u8]) -> String {
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).into_owned()
+}
+
+fn hex_bytes(buf: &[u8]) -> String {
+    buf.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn port_address(sub_uni: u8, net: u8) -> String {
+    PortAddress::from_wire(sub_uni, net & 0x7f)
+        .map(|a| a.to_string())
+        .unwrap_or_default()
+}
+
+fn values(data: &[u8]) -> String {
+    data.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// ArtAddress switch: bit 7 programs the value, 0x7f leaves it unchanged.
+fn switch(b: u8) -> String {
+    match b {
+        0x7f => "no change".into(),
+        b if b & 0x80 != 0 => format!("program {}", b & 0x7f),
+        b => format!("{:#04x}", b),
+    }
+}
+
+fn address_command_name(code: u8) -> String {
+    let port = code & 0x0f;
+    match code {
+        0x00 => "AcNone".into(),
+        0x01 => "AcCancelMerge".into(),
+        0x02 => "AcLedNormal".into(),
+        0x03 => "AcLedMute".into(),
+        0x04 => "AcLedLocate".into(),
+        0x05 => "AcResetRxFlags".into(),
+        0x06 => "AcAnalysisOn".into(),
+        0x07 => "AcAnalysisOff".into(),
+        0x08 => "AcFailHold".into(),
+        0x09 => "AcFailZero".into(),
+        0x0a => "AcFailFull".into(),
+        0x0b => "AcFailScene".into(),
+        0x0c => "AcFailRecord".into(),
+        0x10..=0x13 => format!("AcMergeLtp{}", port),
+        0x20..=0x23 => format!("AcDirectionTx{}", port),
+        0x30..=0x33 => format!("AcDirectionRx{}", port),
+        0x50..=0x53 => format!("AcMergeHtp{}", port),
+        0x60..=0x63 => format!("AcArtNetSel{}", port),
+        0x70..=0x73 => format!("AcAcnSel{}", port),
+        0x90..=0x93 => format!("AcClearOp{}", port),
+        0xa0..=0xa3 => format!("AcStyleDelta{}", port),
+        0xb0..=0xb3 => format!("AcStyleConst{}", port),
+        0xc0..=0xc3 => format!("AcRdmEnable{}", port),
+        0xd0..=0xd3 => format!("AcRdmDisable{}", port),
+        other => format!("{:#04x}", other),
+    }
+}
+
+fn rdm_command_class(cc: u8) -> String {
+    match cc {
+        0x10 => "DISCOVERY_COMMAND".into(),
+        0x11 => "DISCOVERY_COMMAND_RESPONSE".into(),
+        0x20 => "GET_COMMAND".into(),
+        0x21 => "GET_COMMAND_RESPONSE".into(),
+        0x30 => "SET_COMMAND".into(),
+        0x31 => "SET_COMMAND_RESPONSE".into(),
+        other => format!("{:#04x}", other),
+    }
+}
+
+fn decode_poll(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 14, "ArtPoll")?;
+    let flags = buf[12];
+    let mut fields = vec![
+        group(
+            "flags",
+            vec![
+                leaf("raw", format!("{:#04x}", flags)),
+                flag("replyOnChange", flags, 0x02),
+                flag("sendDiagnostics", flags, 0x04),
+                flag("diagnosticsUnicast", flags, 0x08),
+                flag("disableVlc", flags, 0x10),
+                flag("targeted", flags, 0x20),
+            ],
+        ),
+        leaf("diagPriority", format!("{:#04x}", buf[13])),
+    ];
+    if buf.len() >= 18 {
+        fields.push(leaf("targetTop", be16(buf, 14)));
+        fields.push(leaf("targetBottom", be16(buf, 16)));
+    }
+    if buf.len() >= 22 {
+        fields.push(leaf("estaMan", format!("{:#06x}", be16(buf, 18))));
+        fields.push(leaf("oem", format!("{:#06x}", be16(buf, 20))));
+    }
+    Ok(fields)
+}
+
+fn decode_diag_data(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 18, "ArtDiagData")?;
+    let len = be16(buf, 16) as usize;
+    let data = &buf[18..buf.len().min(18 + len)];
+    Ok(vec![
+        leaf("diagPriority", format!("{:#04x}", buf[13])),
+        leaf("logicalPort", buf[14]),
+        leaf("length", len),
+        leaf("text", text(data)),
+    ])
+}
+
+fn decode_dmx(buf: &[u8]) -> Result<Vec<Field>> {
+    let frame = artnet::parse_artdmx(buf)?;
+    Ok(vec![
+        leaf("sequence", frame.sequence),
+        leaf("physical", frame.physical),
+        leaf("portAddress", frame.port_address),
+        leaf("length", frame.length),
+        leaf("values", values(&frame.values)),
+    ])
+}
+
+fn decode_nzs(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 18, "ArtNzs")?;
+    let len = be16(buf, 16) as usize;
+    let data = &buf[18..buf.len().min(18 + len)];
+    Ok(vec![
+        leaf("sequence", buf[12]),
+        leaf("startCode", format!("{:#04x}", buf[13])),
+        leaf("portAddress", port_address(buf[14], buf[15])),
+        leaf("length", len),
+        leaf("values", values(data)),
+    ])
+}
+
+fn decode_sync(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 14, "ArtSync")?;
+    Ok(vec![leaf("aux1", buf[12]), leaf("aux2", buf[13])])
+}
+
+fn decode_address(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, remote::ADDRESS_LEN, "ArtAddress")?;
+    let ports = |at: usize| {
+        (0..4)
+            .map(|i| leaf(&i.to_string(), switch(buf[at + i])))
+            .collect()
+    };
+    Ok(vec![
+        leaf("netSwitch", switch(buf[12])),
+        leaf("bindIndex", buf[13]),
+        leaf("shortName", text(&buf[14..32])),
+        leaf("longName", text(&buf[32..96])),
+        group("swIn", ports(96)),
+        group("swOut", ports(100)),
+        leaf("subSwitch", switch(buf[104])),
+        leaf(
+            "acnPriority",
+            match buf[105] {
+                0xff => "no change".to_string(),
+                p => p.to_string(),
+            },
+        ),
+        leaf("command", address_command_name(buf[106])),
+    ])
+}
+
+fn decode_input(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 20, "ArtInput")?;
+    Ok(vec![
+        leaf("bindIndex", buf[13]),
+        leaf("numPorts", be16(buf, 14)),
+        group(
+            "inputDisabled",
+            (0..4)
+                .map(|i| flag(&i.to_string(), buf[16 + i], 0x01))
+                .collect(),
+        ),
+    ])
+}
+
+fn decode_tod_request(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 24, "ArtTodRequest")?;
+    let net = buf[21];
+    let count = (buf[23] as usize).min(buf.len() - 24);
+    Ok(vec![
+        leaf("net", net),
+        leaf("command", format!("{:#04x}", buf[22])),
+        leaf("adCount", buf[23]),
+        group(
+            "addresses",
+            buf[24..24 + count]
+                .iter()
+                .enumerate()
+                .map(|(i, &sub_uni)| leaf(&i.to_string(), port_address(sub_uni, net)))
+                .collect(),
+        ),
+    ])
+}
+
+fn decode_tod_data(buf: &[u8]) -> Result<Vec<Field>> {
+    let tod = crate::rdm::parse_tod_data(buf)?;
+    Ok(vec![
+        leaf("rdmVer", buf[12]),
+        leaf("port", tod.port),
+        leaf("bindIndex", tod.bind_index),
+        leaf("portAddress", tod.port_address),
+        leaf(
+            "commandResponse",
+            if tod.nak { "TodNak" } else { "TodFull" },
+        ),
+        leaf("uidTotal", tod.uid_total),
+        leaf("blockCount", buf[26]),
+        group(
+            "uids",
+            tod.uids
+                .iter()
+                .enumerate()
+                .map(|(i, uid)| leaf(&i.to_string(), uid))
+                .collect(),
+        ),
+    ])
+}
+
+fn decode_tod_control(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 24, "ArtTodControl")?;
+    Ok(vec![
+        leaf("net", buf[21]),
+        leaf(
+            "command",
+            match buf[22] {
+                0x00 => "AtcNone".to_string(),
+                0x01 => "AtcFlush".to_string(),
+                0x02 => "AtcEnd".to_string(),
+                0x03 => "AtcIncOn".to_string(),
+                0x04 => "AtcIncOff".to_string(),
+                other => format!("{:#04x}", other),
+            },
+        ),
+        leaf("portAddress", port_address(buf[23], buf[21])),
+    ])
+}
+
+fn decode_rdm(buf: &[u8]) -> Result<Vec<Field>> {
+    let (addr, msg) = crate::rdm::parse_art_rdm(buf)?;
+    let mut fields = vec![
+        leaf("rdmVer", buf[12]),
+        leaf("portAddress", addr),
+        leaf("command", format!("{:#04x}", buf[22])),
+    ];
+    // RDM message without its start code, so offsets are one less than in
+    // the RDM standard.
+    if msg.len() < 23 {
+        fields.push(leaf("rdm", hex_bytes(msg)));
+        return Ok(fields);
+    }
+    let uid = |at: usize| Uid {
+        manufacturer: be16(msg, at),
+        device: u32::from_be_bytes([msg[at + 2], msg[at + 3], msg[at + 4], msg[at + 5]]),
+    };
+    let pdl = msg[22] as usize;
+    fields.push(group(
+        "rdm",
+        vec![
+            leaf("subStartCode", format!("{:#04x}", msg[0])),
+            leaf("messageLength", msg[1]),
+            leaf("destination", uid(2)),
+            leaf("source", uid(8)),
+            leaf("transaction", msg[14]),
+            leaf("portIdOrResponseType", msg[15]),
+            leaf("messageCount", msg[16]),
+            leaf("subDevice", be16(msg, 17)),
+            leaf("commandClass", rdm_command_class(msg[19])),
+            leaf("pid", format!("{:#06x}", be16(msg, 20))),
+            leaf("pdl", pdl),
+            leaf("data", hex_bytes(&msg[23..msg.len().min(23 + pdl)])),
+        ],
+    ));
+    Ok(fields)
+}
+
+fn decode_timecode(buf: &[u8]) -> Result<Vec<Field>> {
+    let (tc, stream_id) = timecode::parse_timecode(buf)?;
+    Ok(vec![
+        leaf("streamId", stream_id),
+        leaf("timecode", tc),
+        leaf("type", format!("{:?}", tc.kind)),
+    ])
+}
+
+fn decode_ip_prog(buf: &[u8]) -> Result<Vec<Field>> {
+    need(buf, 30, "ArtIpProg")?;
+    let command = buf[14];
+    Ok(vec![
+        group(
+            "command",
+            vec![
+                leaf("raw", format!("{:#04x}", command)),
+                flag("enableProgramming", command, 0x80),
+                flag("dhcp", command, 0x40),
+                flag("programGateway", command, 0x10),
+                flag("resetDefaults", command, 0x08),
+                flag("programIp", command, 0x04),
+                flag("programMask", command, 0x02),
+                flag("programPort", command, 0x01),
+            ],
+        ),
+        leaf("progIp", ipv4(buf, 16)),
+        leaf("progSm", ipv4(buf, 20)),
+        leaf("progPort", be16(buf, 24)),
+        leaf("progGw", ipv4(buf, 26)),
+    ])
+}
+
+fn decode_fields(op: u16, buf: &[u8]) -> Result<Vec<Field>> {
+    match op {
+        0x2000 => decode_poll(buf),
+        0x2100 => Ok(json_fields(&node::parse_poll_reply(buf)?)),
+        0x2300 => decode_diag_data(buf),
+        0x2400 => Ok(json_fields(&trigger::parse_command(buf)?)),
+        0x5000 => decode_dmx(buf),
+        0x5100 => decode_nzs(buf),
+        0x5200 => decode_sync(buf),
+        0x6000 => decode_address(buf),
+        0x7000 => decode_input(buf),
+        0x8000 => decode_tod_request(buf),
+        0x8100 => decode_tod_data(buf),
+        0x8200 => decode_tod_control(buf),
+        0x8300 => decode_rdm(buf),
+        0x9700 => decode_timecode(buf),
+        0x9900 => {
+            let t = trigger::parse_trigger(buf)?;
+            let mut fields = json_fields(&t);
+            fields.insert(0, leaf("keyName", trigger::key_name(t.key)));
+            Ok(fields)
+        }
+        0xf800 => decode_ip_prog(buf),
+        0xf900 => Ok(json_fields(&remote::parse_ip_prog_reply(buf)?)),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Classic 16-bytes-per-row hex dump with an ASCII column.
+pub fn hex_dump(buf: &[u8]) -> String {
+    buf.chunks(HEX_ROW)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!(
+                "{:04x}  {:<w$}  |{}|",
+                row * HEX_ROW,
+                hex_bytes(chunk),
+                ascii,
+                w = HEX_ROW * 3 - 1
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A packet as received, before decoding.
+#[derive(Clone)]
+pub struct CapturedPacket {
+    pub id: u64,
+    /// Microseconds since the Unix epoch.
+    pub at_us: u64,
+    pub source: SocketAddr,
+    /// Address of the receiving socket: the bind IP when one is set, else
+    /// 0.0.0.0. The socket does not see the real destination, so broadcast
+    /// and unicast packets look the same.
+    pub destination: SocketAddr,
+    pub data: Vec<u8>,
+}
+
+/// Decoded view of a captured packet.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InspectedPacket {
+    pub id: u64,
+    pub at_ms: u64,
+    pub source: String,
+    pub length: usize,
+    /// `None` when the packet is not Art-Net.
+    pub opcode: Option<u16>,
+    pub opcode_name: String,
+    /// `None` for ArtPollReply, which has no ProtVer field.
+    pub prot_ver: Option<u16>,
+    pub fields: Vec<Field>,
+    /// Why the fields could not be decoded.
+    pub error: Option<String>,
+    pub hex: String,
+}
+
+pub fn inspect(p: &CapturedPacket) -> InspectedPacket {
+    let op = artnet::opcode(&p.data);
+    let (opcode_name, prot_ver, decoded) = match op {
+        None => ("Not Art-Net".to_string(), None, Ok(Vec::new())),
+        Some(op) => (
+            opcode_name(op).unwrap_or("Unknown").to_string(),
+            (op != artnet::OP_POLL_REPLY && p.data.len() >= 12).then(|| be16(&p.data, 10)),
+            decode_fields(op, &p.data),
+        ),
+    };
+    let (fields, error) = match decoded {
+        Ok(fields) => (fields, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    InspectedPacket {
+        id: p.id,
+        at_ms: p.at_us / 1000,
+        source: p.source.to_string(),
+        length: p.data.len(),
+        opcode: op,
+        opcode_name,
+        prot_ver,
+        fields,
+        error,
+        hex: hex_dump(&p.data),
+    }
+}
+
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureStatus {
+    pub enabled: bool,
+    pub capacity: usize,
+    pub count: usize,
+    /// Id the next captured packet gets.
+    pub next_id: u64,
+}
+
+/// Bounded ring of every packet the receiver sees; the oldest are dropped
+/// once `capacity` is reached. Packets are decoded when queried.
+pub struct PacketCapture {
+    enabled: bool,
+    capacity: usize,
+    next_id: u64,
+    packets: VecDeque<CapturedPacket>,
+}
+
+impl Default for PacketCapture {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: DEFAULT_CAPTURE_CAPACITY,
+            next_id: 0,
+            packets: VecDeque::new(),
+        }
+    }
+}
+
+impl PacketCapture {
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn configure(&mut self, enabled: bool, capacity: Option<usize>) {
+        self.enabled = enabled;
+        if let Some(c) = capacity {
+            self.capacity = c.clamp(1, MAX_CAPTURE_CAPACITY);
+        }
+        while self.packets.len() > self.capacity {
+            self.packets.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, source: SocketAddr, destination: SocketAddr, data: &[u8]) {
+        if !self.enabled {
+            return;
+        }
+        if self.packets.len() >= self.capacity {
+            self.packets.pop_front();
+        }
+        let at_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+        self.packets.push_back(CapturedPacket {
+            id: self.next_id,
+            at_us,
+            source,
+            destination,
+            data: data.to_vec(),
+        });
+        self.next_id += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.packets.clear();
+    }
+
+    pub fn status(&self) -> CaptureStatus {
+        CaptureStatus {
+            enabled: self.enabled,
+            capacity: self.capacity,
+            count: self.packets.len(),
+            next_id: self.next_id,
+        }
+    }
+
+    /// Up to `limit` packets with an id of at least `from_id`, oldest first.
+    pub fn query(&self, from_id: u64, limit: usize) -> Vec<CapturedPacket> {
+        let first = self.packets.front().map_or(0, |p| p.id);
+        let skip = from_id.saturating_sub(first) as usize;
+        self.packets
+            .iter()
+            .skip(skip)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
@@ -1,14 +1,20 @@
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
-#[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
-}
+//! ArtNetLab engine: Art-Net / sACN protocol handling, sender, receiver,
+//! recorder and player. Shared by the desktop app and `artnetlab-cli`.
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+pub mod artnet;
+pub mod discovery;
+pub mod events;
+pub mod inspector;
+pub mod merge;
+pub mod node;
+pub mod pcap;
+pub mod preroll;
+pub mod rdm;
+pub mod recording;
+pub mod remote;
+pub mod sacn;
+pub mod show;
+pub mod state;
+pub mod stats;
+pub mod timecode;
+pub mod trigger;
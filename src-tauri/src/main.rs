@@ -1,14 +1,15 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod artnet;
-mod discovery;
-mod state;
-
-use std::{collections::HashSet, fs, path::PathBuf};
-
-use anyhow::Result;
+use artnetlab_lib::preroll::{PreRollCaptureInfo, PreRollConfig, PreRollStatus};
+use artnetlab_lib::recording::{self, WavRecordingData};
+use artnetlab_lib::state::{self, AppState, PreviewResponse, RecordTrackInfo};
+use std::collections::BTreeMap;
+use std::{fs, path::PathBuf};
+
+use artnetlab_lib::{
+    artnet, discovery, inspector, node, pcap, rdm, remote, sacn, show, stats, timecode, trigger,
+};
 use serde::{Deserialize, Serialize};
-use state::{AppState, PreviewResponse, RecordData};
 use tauri::Manager;
 use tokio::sync::mpsc;
 
@@ -23,6 +24,12 @@ struct SettingsFile {
     sender: artnet::SenderConfig,
     #[serde(default = "default_discovery_interval_sec")]
     discovery_interval_sec: u64,
+    node: node::NodeConfig,
+    sacn: sacn::SacnConfig,
+    sacn_receiver: sacn::SacnReceiverConfig,
+    timecode: timecode::TimecodeConfig,
+    triggers: Vec<trigger::TriggerMapping>,
+    snapshots: BTreeMap<String, Vec<state::UniverseValues>>,
 }
 
 impl Default for SettingsFile {
@@ -31,6 +38,12 @@ impl Default for SettingsFile {
             receiver: artnet::ReceiverConfig::default(),
             sender: artnet::SenderConfig::default(),
             discovery_interval_sec: default_discovery_interval_sec(),
+            node: node::NodeConfig::default(),
+            sacn: sacn::SacnConfig::default(),
+            sacn_receiver: sacn::SacnReceiverConfig::default(),
+            timecode: timecode::TimecodeConfig::default(),
+            triggers: Vec::new(),
+            snapshots: BTreeMap::new(),
         }
     }
 }
@@ -41,8 +54,9 @@ struct LoadedRecording {
     channels: Vec<u16>,
     frames: usize,
     duration_ms: u64,
-    last_address: Option<(u8, u8, u8)>,
+    last_address: Option<artnet::PortAddress>,
     format: String,
+    tracks: Vec<RecordTrackInfo>,
 }
 
 fn settings_path(app: &tauri::AppHandle) -> PathBuf {
@@ -55,178 +69,6 @@ fn settings_path(app: &tauri::AppHandle) -> PathBuf {
     dir
 }
 
-fn write_buffer_as_jsonl(path: &str, data: &RecordData) -> Result<(), String> {
-    use std::io::Write;
-
-    let mut file = std::fs::File::create(path).map_err(|e| e.to_string())?;
-    let header = serde_json::json!({
-        "format": "artnet-jsonl",
-        "version": 1,
-        "channels": data.channel_numbers(),
-    });
-    writeln!(file, "{}", header.to_string()).map_err(|e| e.to_string())?;
-
-    let base = data.timestamps.first().copied().unwrap_or(0);
-
-    for idx in 0..data.frame_count() {
-        let timestamp = data
-            .timestamps
-            .get(idx)
-            .copied()
-            .unwrap_or(base)
-            .saturating_sub(base);
-        let (net, subnet, universe) = data.addresses.get(idx).copied().unwrap_or((0, 0, 0));
-        let values: Vec<u8> = data
-            .values
-            .iter()
-            .map(|channel| channel.get(idx).copied().unwrap_or(0))
-            .collect();
-        let line = serde_json::json!({
-            "t_ms": timestamp,
-            "net": net,
-            "subnet": subnet,
-            "universe": universe,
-            "length": values.len(),
-            "values": values,
-        });
-        writeln!(file, "{}", line.to_string()).map_err(|e| e.to_string())?;
-    }
-
-    Ok(())
-}
-
-fn write_buffer_as_wav(path: &str, data: &RecordData) -> Result<(), String> {
-    let frames = data.frame_count();
-    if frames == 0 {
-        return Err("No recorded frames".to_string());
-    }
-    let duration = data.duration_ms().max(1);
-    let sample_rate = ((frames as u64 * 1000) / duration).max(1) as u32;
-    let base = data.timestamps.first().copied().unwrap_or(0);
-    let timestamps: Vec<u64> = data
-        .timestamps
-        .iter()
-        .map(|t| t.saturating_sub(base))
-        .collect();
-    let channels: Vec<Vec<u8>> = data.values.iter().map(|v| v.clone()).collect();
-    let wav = WavRecordingData {
-        timestamps,
-        channels,
-        dmx_channels: Some(data.channel_numbers()),
-    };
-    save_wav_recording(path.to_string(), sample_rate, wav)
-}
-
-#[derive(Deserialize, Default)]
-struct JsonlHeader {
-    #[serde(default)]
-    format: Option<String>,
-    #[serde(default)]
-    channels: Vec<u16>,
-    #[serde(default)]
-    channel: Option<u16>,
-}
-
-#[derive(Deserialize)]
-struct JsonlRecord {
-    t_ms: u64,
-    #[serde(default)]
-    net: u8,
-    #[serde(default)]
-    subnet: u8,
-    #[serde(default)]
-    universe: u8,
-    values: Vec<u8>,
-}
-
-fn parse_jsonl_file(path: &str) -> Result<RecordData, String> {
-    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
-    let mut timestamps = Vec::new();
-    let mut addresses = Vec::new();
-    let mut channels: Vec<usize> = (0..512).collect();
-    let mut values: Vec<Vec<u8>> = Vec::new();
-    let mut first_payload_line = true;
-
-    for raw in content.lines() {
-        let trimmed = raw.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-
-        if first_payload_line {
-            first_payload_line = false;
-            if let Ok(header) = serde_json::from_str::<JsonlHeader>(trimmed) {
-                if header.format.is_some() {
-                    if !header.channels.is_empty() {
-                        channels = header
-                            .channels
-                            .into_iter()
-                            .map(|n| n.saturating_sub(1) as usize)
-                            .filter(|n| *n < 512)
-                            .collect();
-                    } else if let Some(ch) = header.channel {
-                        let idx = ch.saturating_sub(1) as usize;
-                        if idx < 512 {
-                            channels = vec![idx];
-                        }
-                    }
-                    values = channels.iter().map(|_| Vec::new()).collect();
-                    continue;
-                }
-            }
-        }
-
-        if values.is_empty() {
-            values = channels.iter().map(|_| Vec::new()).collect();
-        }
-
-        let rec: JsonlRecord = serde_json::from_str(trimmed).map_err(|e| e.to_string())?;
-        timestamps.push(rec.t_ms);
-        addresses.push((rec.net, rec.subnet, rec.universe));
-        if values.len() < channels.len() {
-            values.resize_with(channels.len(), Vec::new);
-        }
-        for (idx, _) in channels.iter().enumerate() {
-            values[idx].push(rec.values.get(idx).copied().unwrap_or(0));
-        }
-    }
-
-    let mut normalized = Vec::new();
-    let mut seen = [false; 512];
-    for ch in channels {
-        if ch < 512 && !seen[ch] {
-            seen[ch] = true;
-            normalized.push(ch);
-        }
-    }
-
-    Ok(RecordData {
-        timestamps,
-        addresses,
-        channels: normalized,
-        values,
-    })
-}
-
-fn record_data_from_wav(data: WavRecordingData) -> RecordData {
-    let channels = data.channels.len();
-    let timestamps_len = data.timestamps.len();
-    let dmx_channels = data
-        .dmx_channels
-        .unwrap_or_else(|| (1..=channels as u16).collect());
-    RecordData {
-        timestamps: data.timestamps,
-        addresses: vec![(0, 0, 0); timestamps_len],
-        channels: dmx_channels
-            .into_iter()
-            .map(|ch| ch.saturating_sub(1) as usize)
-            .filter(|ch| *ch < 512)
-            .take(channels)
-            .collect(),
-        values: data.channels,
-    }
-}
-
 #[tauri::command]
 fn get_receiver_config(state: tauri::State<AppState>) -> artnet::ReceiverConfig {
     state.get_receiver_config()
@@ -259,6 +101,48 @@ fn stop_receiver(state: tauri::State<AppState>) {
     state.stop_receiver();
 }
 
+#[tauri::command]
+fn get_sacn_receiver_config(state: tauri::State<AppState>) -> sacn::SacnReceiverConfig {
+    state.get_sacn_receiver_config()
+}
+
+#[tauri::command]
+fn set_sacn_receiver_config(state: tauri::State<AppState>, cfg: sacn::SacnReceiverConfig) {
+    state.set_sacn_receiver_config(cfg);
+}
+
+#[tauri::command]
+async fn start_sacn_receiver(
+    window: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.stop_sacn_receiver();
+    let cfg = state.get_sacn_receiver_config();
+    let st = state.inner().clone();
+    let handle = tokio::spawn(async move {
+        if let Err(e) = state::run_sacn_receiver_task(cfg, window, st).await {
+            eprintln!("sACN receiver task error: {e:?}");
+        }
+    });
+    state.set_sacn_receiver_task(handle);
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_sacn_receiver(state: tauri::State<AppState>) {
+    state.stop_sacn_receiver();
+}
+
+#[tauri::command]
+fn get_node_config(state: tauri::State<AppState>) -> node::NodeConfig {
+    state.get_node_config()
+}
+
+#[tauri::command]
+fn set_node_config(state: tauri::State<AppState>, cfg: node::NodeConfig) {
+    state.set_node_config(cfg);
+}
+
 #[tauri::command]
 fn get_sender_config(state: tauri::State<AppState>) -> artnet::SenderConfig {
     state.get_sender_config()
@@ -271,7 +155,7 @@ fn set_sender_config(state: tauri::State<AppState>, cfg: artnet::SenderConfig) {
 
 #[tauri::command]
 async fn start_sender(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    state.stop_sender();
+    state.stop_sender().await;
     let cfg = state.get_sender_config();
     let st = state.inner().clone();
     let handle = tokio::spawn(async move {
@@ -280,12 +164,52 @@ async fn start_sender(state: tauri::State<'_, AppState>) -> Result<(), String> {
         }
     });
     state.set_sender_task(handle);
+    if state.get_timecode_config().with_sender {
+        start_timecode(state)?;
+    }
     Ok(())
 }
 
 #[tauri::command]
-fn stop_sender(state: tauri::State<AppState>) {
-    state.stop_sender();
+async fn stop_sender(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.stop_sender().await;
+    if state.get_timecode_config().with_sender {
+        state.stop_timecode();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn get_timecode_config(state: tauri::State<AppState>) -> timecode::TimecodeConfig {
+    state.get_timecode_config()
+}
+
+#[tauri::command]
+fn set_timecode_config(
+    state: tauri::State<AppState>,
+    cfg: timecode::TimecodeConfig,
+) -> Result<(), String> {
+    state.set_timecode_config(cfg).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn start_timecode(state: tauri::State<AppState>) -> Result<(), String> {
+    let cfg = state.get_timecode_config();
+    cfg.start_timecode().map_err(|e| e.to_string())?;
+    let sender = state.get_sender_config();
+    let st = state.inner().clone();
+    let handle = tokio::spawn(async move {
+        if let Err(e) = state::run_timecode_task(cfg, sender, st).await {
+            eprintln!("timecode task error: {e:?}");
+        }
+    });
+    state.set_timecode_task(handle);
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_timecode(state: tauri::State<AppState>) {
+    state.stop_timecode();
 }
 
 #[tauri::command]
@@ -293,12 +217,18 @@ async fn push_frame(state: tauri::State<'_, AppState>) -> Result<(), String> {
     if !state.sender_stream_active() {
         return Ok(());
     }
-    let cfg = state.get_sender_config();
+    let frame = state.snapshot_channels_tick_seq();
     let sock = state.udp_for_send().await.map_err(|e| e.to_string())?;
-    let (data, seq) = state.snapshot_channels_tick_seq();
-    artnet::send_artdmx(sock.as_ref(), &cfg, &data, seq)
-        .await
-        .map_err(|e| e.to_string())
+    let mut pkt = Vec::with_capacity(638);
+    state::send_universe_frame(
+        sock.as_ref(),
+        &state.get_sender_config(),
+        &state.get_sacn_config(),
+        &frame,
+        &mut pkt,
+    )
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -327,11 +257,142 @@ async fn set_channels_and_push(
     if !state.sender_stream_active() {
         return Ok(());
     }
-    let cfg = state.get_sender_config();
+    let frame = state.snapshot_channels_tick_seq();
     let sock = state.udp_for_send().await.map_err(|e| e.to_string())?;
-    let (data, seq) = state.snapshot_channels_tick_seq();
-    artnet::send_artdmx(sock.as_ref(), &cfg, &data, seq)
-        .await
+    let mut pkt = Vec::with_capacity(638);
+    state::send_universe_frame(
+        sock.as_ref(),
+        &state.get_sender_config(),
+        &state.get_sacn_config(),
+        &frame,
+        &mut pkt,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_sender_universes(state: tauri::State<AppState>) -> Vec<state::UniverseInfo> {
+    state.list_universes()
+}
+
+#[tauri::command]
+fn add_sender_universe(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    target_ip: Option<String>,
+) -> Result<(), String> {
+    state
+        .add_universe(port_address, target_ip)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn remove_sender_universe(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+) -> Result<(), String> {
+    state
+        .remove_universe(port_address)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn select_sender_universe(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+) -> Result<(), String> {
+    state
+        .select_universe(port_address)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_sender_universe_enabled(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    enabled: bool,
+) -> Result<(), String> {
+    state
+        .set_universe_enabled(port_address, enabled)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_sender_universe_target(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    target_ip: Option<String>,
+) -> Result<(), String> {
+    let target_ip = target_ip.filter(|ip| !ip.trim().is_empty());
+    state
+        .set_universe_target(port_address, target_ip)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_sender_universe_protocol(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    protocol: sacn::OutputProtocol,
+) -> Result<(), String> {
+    state
+        .set_universe_protocol(port_address, protocol)
+        .map_err(|e| e.to_string())
+}
+
+/// 0 sends DMX; any other start code is sent as ArtNzs (or sACN with it).
+#[tauri::command]
+fn set_sender_universe_start_code(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    start_code: u8,
+) -> Result<(), String> {
+    state
+        .set_universe_start_code(port_address, start_code)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_sacn_config(state: tauri::State<AppState>) -> sacn::SacnConfig {
+    state.get_sacn_config()
+}
+
+#[tauri::command]
+fn set_sacn_config(state: tauri::State<AppState>, cfg: sacn::SacnConfig) -> Result<(), String> {
+    if cfg.priority > 200 {
+        return Err("sACN priority must be 0..=200".to_string());
+    }
+    state.set_sacn_config(cfg);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_universe_channel(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    index: usize,
+    value: u8,
+) -> Result<(), String> {
+    if index >= 512 {
+        return Err("Channel index out of range".to_string());
+    }
+    state
+        .set_universe_channel(port_address, index, value)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_universe_channels(
+    state: tauri::State<AppState>,
+    port_address: artnet::PortAddress,
+    values: Vec<u8>,
+) -> Result<(), String> {
+    if values.len() != 512 {
+        return Err("Expected 512 channel values".to_string());
+    }
+    state
+        .set_universe_channels(port_address, &values)
         .map_err(|e| e.to_string())
 }
 
@@ -339,9 +400,7 @@ async fn set_channels_and_push(
 async fn send_dmx_values(
     state: tauri::State<'_, AppState>,
     values: Vec<u8>,
-    net: Option<u8>,
-    subnet: Option<u8>,
-    universe: Option<u8>,
+    port_address: Option<artnet::PortAddress>,
 ) -> Result<(), String> {
     if values.len() != 512 {
         return Err("Expected 512 channel values".to_string());
@@ -349,10 +408,8 @@ async fn send_dmx_values(
     let mut data = [0u8; 512];
     data.copy_from_slice(&values);
     let mut cfg = state.get_sender_config();
-    if let (Some(n), Some(s), Some(u)) = (net, subnet, universe) {
-        cfg.net = n;
-        cfg.subnet = s;
-        cfg.universe = u;
+    if let Some(addr) = port_address {
+        cfg.port_address = addr;
     }
     let sock = state.udp_for_send().await.map_err(|e| e.to_string())?;
     artnet::send_artdmx(sock.as_ref(), &cfg, &data, 0)
@@ -373,6 +430,12 @@ fn save_settings(
         receiver: state.get_receiver_config(),
         sender: state.get_sender_config(),
         discovery_interval_sec: state.get_discovery_interval_sec(),
+        node: state.get_node_config(),
+        sacn: state.get_sacn_config(),
+        sacn_receiver: state.get_sacn_receiver_config(),
+        timecode: state.get_timecode_config(),
+        triggers: state.get_trigger_mappings(),
+        snapshots: state.get_snapshots(),
     };
     let path = settings_path(&app);
     let s = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
@@ -390,19 +453,36 @@ fn load_settings(
             state.set_receiver_config(cfg.receiver.clone());
             state.set_sender_config(cfg.sender.clone());
             state.set_discovery_interval_sec(cfg.discovery_interval_sec);
+            state.set_node_config(cfg.node.clone());
+            state.set_sacn_config(cfg.sacn.clone());
+            state.set_sacn_receiver_config(cfg.sacn_receiver.clone());
+            let _ = state.set_timecode_config(cfg.timecode.clone());
+            state.set_trigger_mappings(cfg.triggers.clone());
+            state.set_snapshots(cfg.snapshots.clone());
             return Ok(cfg);
         }
     }
     Ok(SettingsFile::default())
 }
 
+/// Records to JSON Lines, or to a binary show file when `path` ends in
+/// `.artrec` (zstd compressed unless `compression` says otherwise).
 #[tauri::command]
-fn start_recording(state: tauri::State<AppState>, path: String) -> Result<(), String> {
+fn start_recording(
+    state: tauri::State<AppState>,
+    path: String,
+    compression: Option<show::Compression>,
+) -> Result<(), String> {
     // Stop if already running
     stop_recording(state.clone());
     let (tx, rx) = mpsc::unbounded_channel();
     let handle = tokio::spawn(async move {
-        if let Err(e) = state::run_record_task(path, rx).await {
+        let result = if show::is_show_file(&path) {
+            state::run_show_record_task(path, compression.unwrap_or_default(), rx).await
+        } else {
+            state::run_record_task(path, rx).await
+        };
+        if let Err(e) = result {
             eprintln!("recorder error: {e:?}");
         }
     });
@@ -415,23 +495,41 @@ fn stop_recording(state: tauri::State<AppState>) {
     state.stop_recording();
 }
 
+/// Starts the buffered recorder; `universes` limits the captured universes
+/// (all when empty or left out), each recorded as its own track. `spill`
+/// streams the recording to a show file, keeping a recent window in memory.
 #[tauri::command]
 fn start_buffered_recording(
     state: tauri::State<AppState>,
     channels: Vec<u16>,
+    universes: Option<Vec<artnet::PortAddress>>,
+    spill: Option<state::SpillConfig>,
 ) -> Result<Vec<u16>, String> {
-    let normalized = state.start_buffered_recording(
-        channels
-            .into_iter()
-            .map(|c| c.saturating_sub(1) as usize)
-            .collect(),
-    );
+    let normalized = state
+        .start_buffered_recording(
+            universes.unwrap_or_default(),
+            channels
+                .into_iter()
+                .map(|c| c.saturating_sub(1) as usize)
+                .collect(),
+            spill,
+        )
+        .map_err(|e| e.to_string())?;
     Ok(normalized.into_iter().map(|c| (c + 1) as u16).collect())
 }
 
+/// Frame counts of the buffered recorder, including frames dropped from
+/// memory without a copy on disk.
+#[tauri::command]
+fn get_record_status(state: tauri::State<AppState>) -> Option<state::RecordStatus> {
+    state.record_status()
+}
+
+/// Stops the buffered recorder, returning once its spill file is complete.
 #[tauri::command]
-fn stop_buffered_recording(state: tauri::State<AppState>) {
-    state.stop_buffered_recording();
+async fn stop_buffered_recording(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.stop_buffered_recording().await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -439,12 +537,16 @@ fn clear_record_buffer(state: tauri::State<AppState>) {
     state.clear_record_buffer();
 }
 
+/// Selects the channels of one universe's track, or of every track when
+/// `port_address` is left out.
 #[tauri::command]
 fn set_record_channels(
     state: tauri::State<AppState>,
     channels: Vec<u16>,
+    port_address: Option<artnet::PortAddress>,
 ) -> Result<Vec<u16>, String> {
     let normalized = state.set_record_channels(
+        port_address,
         channels
             .into_iter()
             .map(|c| c.saturating_sub(1) as usize)
@@ -453,17 +555,30 @@ fn set_record_channels(
     Ok(normalized.into_iter().map(|c| (c + 1) as u16).collect())
 }
 
+#[tauri::command]
+fn set_record_universes(state: tauri::State<AppState>, universes: Vec<artnet::PortAddress>) {
+    state.set_record_universes(universes);
+}
+
+#[tauri::command]
+fn get_record_tracks(state: tauri::State<AppState>) -> Vec<RecordTrackInfo> {
+    state.record_tracks()
+}
+
+/// Preview of a channel in the track of `port_address`, or in the first
+/// track.
 #[tauri::command]
 fn get_recording_preview(
     state: tauri::State<AppState>,
     channel: u16,
     max_points: usize,
+    port_address: Option<artnet::PortAddress>,
 ) -> Result<PreviewResponse, String> {
     if channel == 0 {
         return Err("Channel must be greater than zero".into());
     }
     let preview = state
-        .record_preview(channel.saturating_sub(1) as usize, max_points)
+        .record_preview(port_address, channel.saturating_sub(1) as usize, max_points)
         .unwrap_or(PreviewResponse {
             points: Vec::new(),
             frame_count: 0,
@@ -480,31 +595,63 @@ fn save_buffered_recording_jsonl(
     let data = state
         .record_data_snapshot()
         .ok_or_else(|| "No recording data available".to_string())?;
-    write_buffer_as_jsonl(&path, &data)
+    recording::write_jsonl(&path, &data).map_err(|e| e.to_string())
 }
 
+/// WAV holds one universe: `port_address` picks the track unless the
+/// recording has only one.
 #[tauri::command]
-fn save_buffered_recording_wav(state: tauri::State<AppState>, path: String) -> Result<(), String> {
+fn save_buffered_recording_wav(
+    state: tauri::State<AppState>,
+    path: String,
+    port_address: Option<artnet::PortAddress>,
+) -> Result<(), String> {
     let data = state
         .record_data_snapshot()
         .ok_or_else(|| "No recording data available".to_string())?;
-    write_buffer_as_wav(&path, &data)
+    recording::write_wav(&path, &data, port_address).map_err(|e| e.to_string())
+}
+
+/// Arms the pre-roll ring buffer, or turns it off when `config` is left out.
+#[tauri::command]
+fn set_preroll(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    config: Option<PreRollConfig>,
+) {
+    match config {
+        Some(config) => state::start_preroll(&state, config, app),
+        None => state.stop_preroll(),
+    }
+}
+
+#[tauri::command]
+fn get_preroll_status(state: tauri::State<AppState>) -> Option<PreRollStatus> {
+    state.preroll_status()
+}
+
+/// Fires the pre-roll by hand; false while a post-roll is already running.
+#[tauri::command]
+fn trigger_preroll(state: tauri::State<AppState>) -> Result<bool, String> {
+    state.trigger_preroll("manual").map_err(|e| e.to_string())
+}
+
+/// Moves the last pre-roll capture into the record buffer so it can be
+/// saved; captures never replace the buffer on their own.
+#[tauri::command]
+fn load_preroll_capture(state: tauri::State<AppState>) -> Result<PreRollCaptureInfo, String> {
+    state.load_preroll_capture().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 fn load_recording(state: tauri::State<AppState>, path: String) -> Result<LoadedRecording, String> {
-    let lower = path.to_lowercase();
-    let (data, format) = if lower.ends_with(".wav") {
-        let wav = load_wav_recording(path.clone())?;
-        (record_data_from_wav(wav), "wav".to_string())
-    } else {
-        (parse_jsonl_file(&path)?, "jsonl".to_string())
-    };
+    let (data, format) = recording::load(&path).map_err(|e| e.to_string())?;
 
     let frames = data.frame_count();
     let duration = data.duration_ms();
     let last_address = data.last_address();
     let channels = data.channel_numbers();
+    let tracks = data.track_infos();
     state.load_record_data(data, false);
 
     Ok(LoadedRecording {
@@ -513,30 +660,55 @@ fn load_recording(state: tauri::State<AppState>, path: String) -> Result<LoadedR
         frames,
         duration_ms: duration,
         last_address,
-        format,
+        format: format.to_string(),
+        tracks,
     })
 }
 
 #[tauri::command]
 async fn play_file(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     path: String,
     start_ms: Option<u64>,
     loop_playback: Option<bool>,
+    chase_timecode: Option<String>,
 ) -> Result<(), String> {
     // Stop prior play
     stop_playback(state.clone());
     let cfg = state.get_sender_config();
+    if let Some(origin) = chase_timecode {
+        let frames = state::load_play_frames(&path).map_err(|e| e.to_string())?;
+        return spawn_chase(app, &state, frames, cfg, &origin);
+    }
+    state::start_playback(
+        &state,
+        path,
+        start_ms.unwrap_or(0),
+        loop_playback.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Plays `frames` following received ArtTimeCode; `origin` ("HH:MM:SS:FF")
+/// is the timecode at which the recording starts.
+fn spawn_chase(
+    app: tauri::AppHandle,
+    state: &tauri::State<'_, AppState>,
+    frames: Vec<state::PlayFrame>,
+    cfg: artnet::SenderConfig,
+    origin: &str,
+) -> Result<(), String> {
+    if !state.receiver_active() {
+        return Err("Chasing timecode needs the Art-Net receiver running".into());
+    }
+    // Frames are read with the incoming type; 30 allows every label.
+    let origin = timecode::Timecode::parse(origin, timecode::TimecodeType::Smpte)
+        .map_err(|e| e.to_string())?;
+    let st = state.inner().clone();
     let handle = tokio::spawn(async move {
-        if let Err(e) = state::run_play_task(
-            path,
-            cfg,
-            start_ms.unwrap_or(0),
-            loop_playback.unwrap_or(false),
-        )
-        .await
-        {
-            eprintln!("playback error: {e:?}");
+        if let Err(e) = state::run_chase_task(frames, cfg, origin, app, st).await {
+            eprintln!("timecode chase error: {e:?}");
         }
     });
     state.set_play_task(handle);
@@ -548,17 +720,48 @@ fn stop_playback(state: tauri::State<AppState>) {
     state.stop_playback();
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct EventFilter {
-    net: u8,
-    subnet: u8,
-    universe: u8,
+#[tauri::command]
+fn set_event_filter(state: tauri::State<AppState>, filter: Option<artnet::PortAddress>) {
+    state.set_event_filter(filter);
 }
 
 #[tauri::command]
-fn set_event_filter(state: tauri::State<AppState>, filter: Option<EventFilter>) {
-    let m = filter.map(|f| (f.net, f.subnet, f.universe));
-    state.set_event_filter(m);
+fn get_receiver_sync_mode(state: tauri::State<AppState>) -> state::SyncModeEvent {
+    state.get_sync_mode()
+}
+
+#[tauri::command]
+fn get_receive_stats(state: tauri::State<AppState>) -> Vec<stats::UniverseStatsInfo> {
+    state.get_receive_stats()
+}
+
+#[tauri::command]
+fn set_packet_capture(
+    state: tauri::State<AppState>,
+    enabled: bool,
+    capacity: Option<usize>,
+) -> inspector::CaptureStatus {
+    state.set_packet_capture(enabled, capacity);
+    state.packet_capture_status()
+}
+
+#[tauri::command]
+fn get_packet_capture_status(state: tauri::State<AppState>) -> inspector::CaptureStatus {
+    state.packet_capture_status()
+}
+
+#[tauri::command]
+fn get_captured_packets(
+    state: tauri::State<AppState>,
+    from_id: Option<u64>,
+    limit: Option<usize>,
+) -> Vec<inspector::InspectedPacket> {
+    state.captured_packets(from_id.unwrap_or(0), limit.unwrap_or(500))
+}
+
+#[tauri::command]
+fn clear_captured_packets(state: tauri::State<AppState>) {
+    state.clear_packet_capture();
 }
 
 #[tauri::command]
@@ -586,291 +789,88 @@ fn read_binary_file(path: String) -> Result<Vec<u8>, String> {
     std::fs::read(path).map_err(|e| e.to_string())
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
-pub struct WavRecordingData {
-    pub timestamps: Vec<u64>,
-    pub channels: Vec<Vec<u8>>,
-    #[serde(default)]
-    pub dmx_channels: Option<Vec<u16>>,
-}
-
 #[tauri::command]
 fn save_wav_recording(
     path: String,
     sample_rate: u32,
     data: WavRecordingData,
 ) -> Result<(), String> {
-    use std::io::Write;
-
     println!(
         "Saving WAV recording to: {} ({} frames, {} Hz)",
         path,
         data.timestamps.len(),
         sample_rate
     );
-
-    let mut file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
-
-    // WAV header
-    let num_channels = data.channels.len() as u16;
-    let bits_per_sample = 8u16;
-    let bytes_per_sample = bits_per_sample / 8;
-    let block_align = num_channels * bytes_per_sample;
-    let sample_rate = sample_rate as u32;
-    let byte_rate = sample_rate * block_align as u32;
-    let data_size = (data.timestamps.len() as u32) * block_align as u32;
-    let metadata = data
-        .dmx_channels
-        .as_ref()
-        .map(|channels| {
-            serde_json::json!({ "dmx_channels": channels })
-                .to_string()
-                .into_bytes()
-        })
-        .unwrap_or_default();
-    let metadata_chunk_size = if metadata.is_empty() {
-        0
-    } else {
-        8 + metadata.len() as u32 + (metadata.len() as u32 % 2)
-    };
-    let file_size = 36 + metadata_chunk_size + data_size;
-
-    // RIFF header
-    file.write_all(b"RIFF").map_err(|e| e.to_string())?;
-    file.write_all(&file_size.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-    file.write_all(b"WAVE").map_err(|e| e.to_string())?;
-
-    // fmt chunk
-    file.write_all(b"fmt ").map_err(|e| e.to_string())?;
-    file.write_all(&16u32.to_le_bytes())
-        .map_err(|e| e.to_string())?; // chunk size
-    file.write_all(&1u16.to_le_bytes())
-        .map_err(|e| e.to_string())?; // PCM format
-    file.write_all(&num_channels.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-    file.write_all(&sample_rate.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-    file.write_all(&byte_rate.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-    file.write_all(&block_align.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-    file.write_all(&bits_per_sample.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-
-    if !metadata.is_empty() {
-        file.write_all(b"anlc").map_err(|e| e.to_string())?;
-        file.write_all(&(metadata.len() as u32).to_le_bytes())
-            .map_err(|e| e.to_string())?;
-        file.write_all(&metadata).map_err(|e| e.to_string())?;
-        if metadata.len() % 2 == 1 {
-            file.write_all(&[0]).map_err(|e| e.to_string())?;
-        }
-    }
-
-    // data chunk
-    file.write_all(b"data").map_err(|e| e.to_string())?;
-    file.write_all(&data_size.to_le_bytes())
-        .map_err(|e| e.to_string())?;
-
-    // Write sample data (interleaved channels)
-    for frame_idx in 0..data.timestamps.len() {
-        for ch in 0..num_channels as usize {
-            let value = if ch < data.channels.len() && frame_idx < data.channels[ch].len() {
-                data.channels[ch][frame_idx]
-            } else {
-                0
-            };
-            file.write_all(&[value]).map_err(|e| e.to_string())?;
-        }
-    }
-
-    println!(
-        "Successfully saved WAV file with {} frames",
-        data.timestamps.len()
-    );
-    Ok(())
+    recording::save_wav(&path, sample_rate, &data).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 fn load_wav_recording(path: String) -> Result<WavRecordingData, String> {
-    use std::io::Read;
-
     println!("Loading WAV recording from: {}", path);
+    recording::load_wav(&path).map_err(|e| e.to_string())
+}
 
-    let mut file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer).map_err(|e| e.to_string())?;
-
-    if buffer.len() < 44 {
-        return Err("File too small to be a valid WAV file".to_string());
-    }
-
-    // Parse WAV header
-    let mut pos = 0;
-
-    // Check RIFF header
-    if &buffer[pos..pos + 4] != b"RIFF" {
-        return Err("Invalid RIFF header".to_string());
-    }
-    pos += 4;
-
-    let _file_size = u32::from_le_bytes([
-        buffer[pos],
-        buffer[pos + 1],
-        buffer[pos + 2],
-        buffer[pos + 3],
-    ]);
-    pos += 4;
-
-    if &buffer[pos..pos + 4] != b"WAVE" {
-        return Err("Invalid WAVE header".to_string());
-    }
-    pos += 4;
-
-    // Find fmt chunk
-    let mut sample_rate = 44100u32; // Default sample rate
-    let mut num_channels = 0u16;
-    let mut dmx_channels: Option<Vec<u16>> = None;
-    while pos < buffer.len() - 8 {
-        let chunk_id = &buffer[pos..pos + 4];
-        let chunk_size = u32::from_le_bytes([
-            buffer[pos + 4],
-            buffer[pos + 5],
-            buffer[pos + 6],
-            buffer[pos + 7],
-        ]);
-        pos += 8;
-
-        if chunk_id == b"fmt " {
-            if chunk_size < 16 {
-                return Err("Invalid fmt chunk size".to_string());
-            }
-
-            let _format = u16::from_le_bytes([buffer[pos], buffer[pos + 1]]);
-            num_channels = u16::from_le_bytes([buffer[pos + 2], buffer[pos + 3]]);
-            sample_rate = u32::from_le_bytes([
-                buffer[pos + 4],
-                buffer[pos + 5],
-                buffer[pos + 6],
-                buffer[pos + 7],
-            ]);
-            let _byte_rate = u32::from_le_bytes([
-                buffer[pos + 8],
-                buffer[pos + 9],
-                buffer[pos + 10],
-                buffer[pos + 11],
-            ]);
-            let _block_align = u16::from_le_bytes([buffer[pos + 12], buffer[pos + 13]]);
-            let bits_per_sample = u16::from_le_bytes([buffer[pos + 14], buffer[pos + 15]]);
-
-            if bits_per_sample != 8 {
-                return Err(format!(
-                    "Expected 8 bits per sample, got {}",
-                    bits_per_sample
-                ));
-            }
-
-            println!(
-                "WAV: {} channels, {} Hz, {} bits",
-                num_channels, sample_rate, bits_per_sample
-            );
-            pos += chunk_size as usize;
-            break;
-        } else {
-            pos += chunk_size as usize + (chunk_size as usize % 2);
-        }
-    }
-
-    // Find data chunk
-    while pos < buffer.len() - 8 {
-        let chunk_id = &buffer[pos..pos + 4];
-        let chunk_size = u32::from_le_bytes([
-            buffer[pos + 4],
-            buffer[pos + 5],
-            buffer[pos + 6],
-            buffer[pos + 7],
-        ]);
-        pos += 8;
-        let chunk_end = pos.saturating_add(chunk_size as usize).min(buffer.len());
-
-        if chunk_id == b"anlc" {
-            if let Ok(text) = std::str::from_utf8(&buffer[pos..chunk_end]) {
-                if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
-                    dmx_channels =
-                        value
-                            .get("dmx_channels")
-                            .and_then(|v| v.as_array())
-                            .map(|channels| {
-                                channels
-                                    .iter()
-                                    .filter_map(|ch| ch.as_u64().map(|n| n as u16))
-                                    .collect()
-                            });
-                }
-            }
-            pos = chunk_end + (chunk_size as usize % 2);
-        } else if chunk_id == b"data" {
-            // Read sample data
-            let num_frames = chunk_size as usize / num_channels as usize;
-            let mut timestamps = Vec::new();
-            let mut channels = vec![Vec::new(); num_channels as usize];
-
-            for frame_idx in 0..num_frames {
-                timestamps.push((frame_idx as u64 * 1000) / sample_rate as u64); // Convert to milliseconds
-
-                for ch in 0..num_channels as usize {
-                    if pos < buffer.len() {
-                        channels[ch].push(buffer[pos]);
-                        pos += 1;
-                    } else {
-                        channels[ch].push(0);
-                    }
-                }
-            }
+/// Loads one universe of a pcap / pcapng capture, show file or JSON Lines
+/// recording for preview; playback of the file with `play_file` sends every
+/// universe.
+#[tauri::command]
+fn load_capture_recording(
+    path: String,
+    port_address: Option<artnet::PortAddress>,
+) -> Result<recording::CaptureRecording, String> {
+    let (data, _) = recording::load(&path).map_err(|e| e.to_string())?;
+    recording::universe_recording(&data, port_address).map_err(|e| e.to_string())
+}
 
-            println!("Successfully loaded WAV file with {} frames", num_frames);
-            return Ok(WavRecordingData {
-                timestamps,
-                channels,
-                dmx_channels,
-            });
-        } else {
-            pos = chunk_end + (chunk_size as usize % 2);
-        }
-    }
+/// Converts a JSON Lines recording to a show file or back, by extension;
+/// returns the number of frames written.
+#[tauri::command]
+fn convert_recording(
+    src: String,
+    dst: String,
+    compression: Option<show::Compression>,
+    keyframe_ms: Option<u32>,
+) -> Result<usize, String> {
+    show::convert(
+        &src,
+        &dst,
+        compression.unwrap_or_default(),
+        keyframe_ms.unwrap_or(show::DEFAULT_KEYFRAME_MS),
+    )
+    .map_err(|e| e.to_string())
+}
 
-    Err("No data chunk found in WAV file".to_string())
+/// Writes the packet inspector's capture as pcapng; returns the packet count.
+#[tauri::command]
+fn export_capture_pcapng(state: tauri::State<AppState>, path: String) -> Result<usize, String> {
+    pcap::write_pcapng(&path, &state.raw_captured_packets()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn play_wav_file(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     path: String,
     start_ms: Option<u64>,
     loop_playback: Option<bool>,
+    chase_timecode: Option<String>,
 ) -> Result<(), String> {
     // Stop prior play
     stop_playback(state.clone());
 
-    // Load WAV data
-    let wav_data = load_wav_recording(path)?;
-    let cfg = state.get_sender_config();
-
-    let handle = tokio::spawn(async move {
-        if let Err(e) = state::run_wav_play_task(
-            wav_data,
-            cfg,
-            start_ms.unwrap_or(0),
-            loop_playback.unwrap_or(false),
-        )
-        .await
-        {
-            eprintln!("WAV playback error: {e:?}");
-        }
-    });
-    state.set_play_task(handle);
-    Ok(())
+    if let Some(origin) = chase_timecode {
+        let wav_data = recording::load_wav(&path).map_err(|e| e.to_string())?;
+        let cfg = state.get_sender_config();
+        let frames = state::wav_frames(&wav_data, cfg.port_address);
+        return spawn_chase(app, &state, frames, cfg, &origin);
+    }
+    state::start_playback(
+        &state,
+        path,
+        start_ms.unwrap_or(0),
+        loop_playback.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -943,34 +943,182 @@ fn stop_animation(state: tauri::State<AppState>) {
 
 #[tauri::command]
 async fn artnet_discover(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     cfg: artnet::SenderConfig,
     extra_broadcast_ips: Option<Vec<String>>,
     timeout_ms: Option<u64>,
 ) -> Result<Vec<discovery::ArtNetDiscoveredNode>, String> {
-    let port = cfg.port;
-    let timeout_ms = timeout_ms.unwrap_or(2000);
-    let mut broadcast_hosts = discovery::subnet_broadcast_addrs();
-    let t = cfg.target_ip.trim();
-    if !t.is_empty() {
-        broadcast_hosts.push(cfg.target_ip.clone());
-    }
-    broadcast_hosts.push("255.255.255.255".into());
-    if let Some(extra) = extra_broadcast_ips {
-        broadcast_hosts.extend(extra);
-    }
-    broadcast_hosts.retain(|s| !s.trim().is_empty());
-    let mut uniq = HashSet::new();
-    broadcast_hosts.retain(|s| uniq.insert(s.clone()));
-
-    let (tx, rx) = mpsc::channel(256);
-    let mut relay_slot = Some(rx);
-    state.set_discovery_poll_reply_tx(Some(tx)).await;
-    let out = discovery::scan_artnet(&broadcast_hosts, port, timeout_ms, &mut relay_slot)
+    let hosts = discovery::broadcast_hosts(&cfg.target_ip, extra_broadcast_ips.unwrap_or_default());
+    let nodes = state::discover_nodes(&state, &hosts, cfg.port, timeout_ms.unwrap_or(2000))
+        .await
+        .map_err(|e| e.to_string())?;
+    state::apply_discovered_nodes(&app, &state, nodes.clone(), false);
+    Ok(nodes)
+}
+
+#[tauri::command]
+async fn artnet_address(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    request: remote::AddressRequest,
+    timeout_ms: Option<u64>,
+) -> Result<remote::AddressOutcome, String> {
+    state::address_node(&app, &state, &request, timeout_ms.unwrap_or(1500))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn artnet_ip_prog(
+    state: tauri::State<'_, AppState>,
+    request: remote::IpProgRequest,
+    timeout_ms: Option<u64>,
+) -> Result<remote::IpProgOutcome, String> {
+    state::ip_prog_node(&state, &request, timeout_ms.unwrap_or(2000))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn rdm_discover(
+    state: tauri::State<'_, AppState>,
+    node_ip: String,
+    port_address: Option<artnet::PortAddress>,
+    flush: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<Vec<rdm::TodEntry>, String> {
+    let flush = flush.unwrap_or(false);
+    // A full discovery on a busy line can take several seconds.
+    let default_ms = if flush { 5000 } else { 2000 };
+    state::rdm_discover(
+        &state,
+        &node_ip,
+        port_address,
+        flush,
+        timeout_ms.unwrap_or(default_ms),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_rdm_devices(state: tauri::State<AppState>) -> Vec<rdm::TodEntry> {
+    state.list_rdm_devices()
+}
+
+#[tauri::command]
+async fn rdm_get(
+    state: tauri::State<'_, AppState>,
+    node_ip: String,
+    port_address: artnet::PortAddress,
+    uid: rdm::Uid,
+    param: rdm::RdmParam,
+    timeout_ms: Option<u64>,
+) -> Result<rdm::RdmReply, String> {
+    state::rdm_get(
+        &state,
+        &node_ip,
+        port_address,
+        uid,
+        param,
+        timeout_ms.unwrap_or(2000),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn rdm_set(
+    state: tauri::State<'_, AppState>,
+    node_ip: String,
+    port_address: artnet::PortAddress,
+    uid: rdm::Uid,
+    value: rdm::RdmSetValue,
+    timeout_ms: Option<u64>,
+) -> Result<rdm::RdmReply, String> {
+    state::rdm_set(
+        &state,
+        &node_ip,
+        port_address,
+        uid,
+        &value,
+        timeout_ms.unwrap_or(2000),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_trigger_mappings(state: tauri::State<AppState>) -> Vec<trigger::TriggerMapping> {
+    state.get_trigger_mappings()
+}
+
+#[tauri::command]
+fn set_trigger_mappings(state: tauri::State<AppState>, mappings: Vec<trigger::TriggerMapping>) {
+    state.set_trigger_mappings(mappings);
+}
+
+#[tauri::command]
+fn run_trigger_action(
+    app: tauri::AppHandle,
+    state: tauri::State<AppState>,
+    action: trigger::TriggerAction,
+) -> Result<(), String> {
+    state::run_trigger_action(&app, &state, &action).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn send_art_trigger(
+    state: tauri::State<'_, AppState>,
+    target_ip: Option<String>,
+    key: u8,
+    sub_key: u8,
+    oem: Option<u16>,
+    data: Option<String>,
+) -> Result<(), String> {
+    let pkt = trigger::encode_trigger(
+        oem.unwrap_or(trigger::ALL_DEVICES),
+        key,
+        sub_key,
+        data.unwrap_or_default().as_bytes(),
+    );
+    state::send_to_target(&state, target_ip.as_deref(), &pkt)
         .await
-        .map_err(|e| e.to_string());
-    state.set_discovery_poll_reply_tx(None).await;
-    out
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn send_art_command(
+    state: tauri::State<'_, AppState>,
+    target_ip: Option<String>,
+    text: String,
+    esta: Option<u16>,
+) -> Result<(), String> {
+    let pkt = trigger::encode_command(esta.unwrap_or(trigger::ALL_DEVICES), &text);
+    state::send_to_target(&state, target_ip.as_deref(), &pkt)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_snapshots(state: tauri::State<AppState>) -> Vec<String> {
+    state.get_snapshots().into_keys().collect()
+}
+
+#[tauri::command]
+fn save_snapshot(state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    state.save_snapshot(&name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_snapshot(state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    state.delete_snapshot(&name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_discovered_nodes(state: tauri::State<AppState>) -> Vec<discovery::TrackedNode> {
+    state.list_discovered_nodes()
 }
 
 fn main() {
@@ -987,6 +1135,12 @@ fn main() {
                     state.set_receiver_config(cfg.receiver);
                     state.set_sender_config(cfg.sender);
                     state.set_discovery_interval_sec(cfg.discovery_interval_sec);
+                    state.set_node_config(cfg.node);
+                    state.set_sacn_config(cfg.sacn);
+                    state.set_sacn_receiver_config(cfg.sacn_receiver);
+                    let _ = state.set_timecode_config(cfg.timecode);
+                    state.set_trigger_mappings(cfg.triggers);
+                    state.set_snapshots(cfg.snapshots);
                 }
             }
             // Auto-start receiver on app launch (run inline to avoid 'static issues)
@@ -995,6 +1149,24 @@ fn main() {
                 let state: tauri::State<AppState> = app.state();
                 let _ = tauri::async_runtime::block_on(start_receiver(app_handle, state));
             }
+            {
+                let state: tauri::State<AppState> = app.state();
+                let app_handle = app.handle().clone();
+                let st = state.inner().clone();
+                let handle = tauri::async_runtime::spawn(async move {
+                    if let Err(e) = state::run_discovery_task(app_handle, st).await {
+                        eprintln!("discovery task error: {e:?}");
+                    }
+                });
+                state.set_discovery_task(handle);
+            }
+            {
+                let state: tauri::State<AppState> = app.state();
+                if state.get_sacn_receiver_config().enabled {
+                    let app_handle = app.handle().clone();
+                    let _ = tauri::async_runtime::block_on(start_sacn_receiver(app_handle, state));
+                }
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1002,14 +1174,36 @@ fn main() {
             set_receiver_config,
             start_receiver,
             stop_receiver,
+            get_sacn_receiver_config,
+            set_sacn_receiver_config,
+            start_sacn_receiver,
+            stop_sacn_receiver,
+            get_node_config,
+            set_node_config,
             get_sender_config,
             set_sender_config,
             start_sender,
             stop_sender,
+            get_timecode_config,
+            set_timecode_config,
+            start_timecode,
+            stop_timecode,
             push_frame,
             set_channel,
             set_channels,
             set_channels_and_push,
+            list_sender_universes,
+            add_sender_universe,
+            remove_sender_universe,
+            select_sender_universe,
+            set_sender_universe_enabled,
+            set_sender_universe_target,
+            set_sender_universe_protocol,
+            set_sender_universe_start_code,
+            get_sacn_config,
+            set_sacn_config,
+            set_universe_channel,
+            set_universe_channels,
             send_dmx_values,
             save_settings,
             load_settings,
@@ -1019,6 +1213,13 @@ fn main() {
             stop_buffered_recording,
             clear_record_buffer,
             set_record_channels,
+            set_record_universes,
+            get_record_tracks,
+            get_record_status,
+            set_preroll,
+            get_preroll_status,
+            trigger_preroll,
+            load_preroll_capture,
             get_recording_preview,
             save_buffered_recording_jsonl,
             save_buffered_recording_wav,
@@ -1026,6 +1227,15 @@ fn main() {
             play_file,
             stop_playback,
             set_event_filter,
+            get_receiver_sync_mode,
+            get_receive_stats,
+            set_packet_capture,
+            get_packet_capture_status,
+            get_captured_packets,
+            clear_captured_packets,
+            load_capture_recording,
+            convert_recording,
+            export_capture_pcapng,
             write_text_file,
             read_text_file,
             read_binary_file,
@@ -1035,7 +1245,22 @@ fn main() {
             save_wav_recording,
             load_wav_recording,
             play_wav_file,
-            artnet_discover
+            artnet_discover,
+            list_discovered_nodes,
+            artnet_address,
+            artnet_ip_prog,
+            rdm_discover,
+            list_rdm_devices,
+            rdm_get,
+            rdm_set,
+            get_trigger_mappings,
+            set_trigger_mappings,
+            run_trigger_action,
+            send_art_trigger,
+            send_art_command,
+            list_snapshots,
+            save_snapshot,
+            delete_snapshot
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use crate::artnet::{self, DmxFrame, PortAddress};
+
+/// Art-Net: a source that has not sent for 10 seconds leaves the merge.
+pub const SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Art-Net nodes merge at most two sources per universe.
+const MAX_SOURCES: usize = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    /// Highest Takes Precedence, per channel.
+    #[default]
+    Htp,
+    /// Latest Takes Precedence: the most recent packet wins.
+    Ltp,
+}
+
+/// A source is identified by its IP address and the Physical field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceKey {
+    pub ip: IpAddr,
+    pub physical: u8,
+}
+
+struct Source {
+    key: SourceKey,
+    values: Vec<u8>,
+    last_seen: Instant,
+}
+
+#[derive(Default)]
+struct UniverseSources {
+    sources: Vec<Source>,
+    // Extra sources that were refused, so each is reported only once.
+    rejected: HashMap<SourceKey, Instant>,
+}
+
+pub enum MergeOutcome {
+    /// Frame to output for the universe (merged when two sources are active).
+    Output(DmxFrame),
+    /// A third source appeared and was ignored.
+    Rejected,
+    /// Repeated packet from an already rejected source.
+    Ignored,
+}
+
+#[derive(Default)]
+pub struct Merger {
+    mode: MergeMode,
+    universes: HashMap<PortAddress, UniverseSources>,
+}
+
+impl Merger {
+    pub fn new(mode: MergeMode) -> Self {
+        Self {
+            mode,
+            universes: HashMap::new(),
+        }
+    }
+
+    pub fn mode(&self) -> MergeMode {
+        self.mode
+    }
+
+    /// Sources that sent to `addr` within the source timeout.
+    pub fn active_sources(&self, addr: PortAddress, now: Instant) -> usize {
+        self.universes.get(&addr).map_or(0, |uni| {
+            uni.sources
+                .iter()
+                .filter(|s| now.duration_since(s.last_seen) < SOURCE_TIMEOUT)
+                .count()
+        })
+    }
+
+    pub fn push(&mut self, frame: DmxFrame, key: SourceKey, now: Instant) -> MergeOutcome {
+        let uni = self.universes.entry(frame.port_address).or_default();
+        uni.sources
+            .retain(|s| now.duration_since(s.last_seen) < SOURCE_TIMEOUT);
+        uni.rejected
+            .retain(|_, seen| now.duration_since(*seen) < SOURCE_TIMEOUT);
+
+        match uni.sources.iter_mut().position(|s| s.key == key) {
+            Some(idx) => {
+                let src = &mut uni.sources[idx];
+                src.values.clone_from(&frame.values);
+                src.last_seen = now;
+                // Keep the latest source last for LTP.
+                let latest = uni.sources.remove(idx);
+                uni.sources.push(latest);
+            }
+            None if uni.sources.len() < MAX_SOURCES => uni.sources.push(Source {
+                key,
+                values: frame.values.clone(),
+                last_seen: now,
+            }),
+            None => {
+                let first = uni.rejected.insert(key, now).is_none();
+                return if first {
+                    MergeOutcome::Rejected
+                } else {
+                    MergeOutcome::Ignored
+                };
+            }
+        }
+
+        if uni.sources.len() == 1 {
+            return MergeOutcome::Output(frame);
+        }
+
+        let len = uni
+            .sources
+            .iter()
+            .map(|s| s.values.len())
+            .max()
+            .unwrap_or(0);
+        let mut values = vec![0u8; len];
+        match self.mode {
+            MergeMode::Htp => {
+                for src in &uni.sources {
+                    for (out, v) in values.iter_mut().zip(&src.values) {
+                        *out = (*out).max(*v);
+                    }
+                }
+            }
+            MergeMode::Ltp => {
+                // Oldest first so the latest packet overwrites.
+                for src in &uni.sources {
+                    values[..src.values.len()].copy_from_slice(&src.values);
+                }
+            }
+        }
+        MergeOutcome::Output(DmxFrame {
+            length: len as u16,
+            values,
+            ..frame
+        })
+    }
+}
+
+/// Silence after which a source's sequence is forgotten, so a node that
+/// restarts its count (after a reboot, say) is not discarded. Reordered
+/// packets arrive milliseconds apart.
+pub(crate) const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Drops ArtDmx packets that arrive behind the last accepted sequence number
+/// of their source and universe.
+#[derive(Default)]
+pub struct SequenceFilter {
+    last: HashMap<(PortAddress, SourceKey), (u8, Instant)>,
+    pruned: Option<Instant>,
+}
+
+impl SequenceFilter {
+    /// Returns false when the frame is stale and must be discarded.
+    pub fn accept(&mut self, frame: &DmxFrame, source: SourceKey, now: Instant) -> bool {
+        if self
+            .pruned
+            .is_none_or(|at| now.duration_since(at) >= SEQUENCE_TIMEOUT)
+        {
+            self.pruned = Some(now);
+            self.last
+                .retain(|_, (_, seen)| now.duration_since(*seen) < SEQUENCE_TIMEOUT);
+        }
+        if frame.sequence == 0 {
+            return true;
+        }
+        let (last, seen) = self
+            .last
+            .entry((frame.port_address, source))
+            .or_insert((0, now));
+        if now.duration_since(*seen) < SEQUENCE_TIMEOUT
+            && artnet::sequence_is_behind(*last, frame.sequence)
+        {
+            return false;
+        }
+        *last = frame.sequence;
+        *seen = now;
+        true
+    }
+}
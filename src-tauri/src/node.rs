@@ -0,0 +1,592 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use anyhow::{anyhow, Result};
+
+use crate::artnet::{self, PortAddress, ARTNET_PORT};
+use crate::merge::MergeMode;
+use crate::sacn::OutputProtocol;
+
+const STATUS1_INDICATORS_NORMAL: u8 = 0b1100_0000;
+const STATUS1_PAPA_FRONT_PANEL: u8 = 0b0001_0000;
+const STATUS2_PORT_ADDRESS_15BIT: u8 = 0b0000_1000;
+const PORT_TYPE_OUTPUT: u8 = 0x80; // Art-Net -> DMX
+const PORT_TYPE_INPUT: u8 = 0x40; // DMX -> Art-Net
+const GOOD_DATA: u8 = 0x80;
+const GOOD_OUTPUT_A_MERGING: u8 = 0x08;
+const GOOD_OUTPUT_A_LTP: u8 = 0x02;
+const GOOD_OUTPUT_B_RDM_DISABLED: u8 = 0x80;
+const POLL_FLAG_TARGETED: u8 = 0b0010_0000;
+const REPORT_POWER_OK: u16 = 0x0001;
+pub const POLL_REPLY_LEN: usize = 239;
+
+/// Identity and port layout ArtNetLab advertises when answering ArtPoll.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NodeConfig {
+    pub enabled: bool,
+    pub short_name: String, // up to 17 characters
+    pub long_name: String,  // up to 63 characters
+    pub oem: u16,
+    pub esta_code: u16,
+    pub style: u8,
+    /// Universes advertised as output ports (Art-Net -> DMX).
+    pub output_universes: Vec<PortAddress>,
+    /// Overrides the interface MAC, e.g. where it cannot be read.
+    pub mac: Option<String>,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            short_name: "ArtNetLab".into(),
+            long_name: "ArtNetLab Art-Net monitor and controller".into(),
+            oem: 0x00ff, // OemUnknown
+            esta_code: 0,
+            style: 0x00, // StNode
+            output_universes: Vec::new(),
+            mac: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodePort {
+    pub port_address: PortAddress,
+    pub input: bool,
+    pub output: bool,
+    /// Art-Net sources feeding the output, for its GoodOutputA flags.
+    pub sources: usize,
+}
+
+/// Merges sender (input) and receiver (output) universes into node ports.
+pub fn node_ports(inputs: &[PortAddress], outputs: &[PortAddress]) -> Vec<NodePort> {
+    let mut ports: Vec<NodePort> = Vec::new();
+    for (addr, is_input) in inputs
+        .iter()
+        .map(|a| (*a, true))
+        .chain(outputs.iter().map(|a| (*a, false)))
+    {
+        let port = match ports.iter_mut().find(|p| p.port_address == addr) {
+            Some(p) => p,
+            None => {
+                ports.push(NodePort {
+                    port_address: addr,
+                    input: false,
+                    output: false,
+                    sources: 0,
+                });
+                ports.last_mut().unwrap()
+            }
+        };
+        if is_input {
+            port.input = true;
+        } else {
+            port.output = true;
+        }
+    }
+    ports.sort_by_key(|p| p.port_address);
+    ports
+}
+
+/// Splits ports into ArtPollReply pages. One reply carries at most four ports
+/// sharing the same Net and Sub-Net; each page gets its own BindIndex.
+pub fn reply_pages(ports: &[NodePort]) -> Vec<Vec<NodePort>> {
+    let mut pages: Vec<Vec<NodePort>> = Vec::new();
+    for port in ports {
+        match pages.last_mut() {
+            Some(page)
+                if page.len() < 4
+                    && page[0].port_address.net() == port.port_address.net()
+                    && page[0].port_address.subnet() == port.port_address.subnet() =>
+            {
+                page.push(*port)
+            }
+            _ => pages.push(vec![*port]),
+        }
+    }
+    if pages.is_empty() {
+        pages.push(Vec::new());
+    }
+    pages
+}
+
+/// Port-Address range requested by a targeted ArtPoll (Art-Net 4).
+pub fn poll_target_range(poll: &[u8]) -> Option<(u16, u16)> {
+    if poll.len() < 18 || poll[12] & POLL_FLAG_TARGETED == 0 {
+        return None;
+    }
+    let top = u16::from_be_bytes([poll[14], poll[15]]);
+    let bottom = u16::from_be_bytes([poll[16], poll[17]]);
+    Some((bottom.min(top), bottom.max(top)))
+}
+
+fn put_str(buf: &mut [u8], s: &str) {
+    // Null terminated: keep the last byte zero.
+    let bytes = s.as_bytes();
+    let n = bytes.len().min(buf.len() - 1);
+    buf[..n].copy_from_slice(&bytes[..n]);
+}
+
+pub struct PollReplyInfo<'a> {
+    pub identity: &'a NodeConfig,
+    pub ip: Ipv4Addr,
+    pub mac: [u8; 6],
+    pub ports: &'a [NodePort],
+    pub bind_index: u8,
+    pub report_count: u16,
+    pub sending: bool,
+    pub merge_mode: MergeMode,
+}
+
+pub fn encode_poll_reply(info: &PollReplyInfo) -> Vec<u8> {
+    let mut pkt = vec![0u8; POLL_REPLY_LEN];
+    pkt[0..8].copy_from_slice(artnet::ARTNET_ID);
+    pkt[8..10].copy_from_slice(&artnet::OP_POLL_REPLY.to_le_bytes());
+    pkt[10..14].copy_from_slice(&info.ip.octets());
+    pkt[14..16].copy_from_slice(&ARTNET_PORT.to_le_bytes());
+    let major: u8 = env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap_or(0);
+    let minor: u8 = env!("CARGO_PKG_VERSION_MINOR").parse().unwrap_or(0);
+    pkt[16] = major; // VersInfoH
+    pkt[17] = minor; // VersInfoL
+    if let Some(first) = info.ports.first() {
+        pkt[18] = first.port_address.net(); // NetSwitch
+        pkt[19] = first.port_address.subnet(); // SubSwitch
+    }
+    pkt[20..22].copy_from_slice(&info.identity.oem.to_be_bytes());
+    pkt[23] = STATUS1_INDICATORS_NORMAL | STATUS1_PAPA_FRONT_PANEL;
+    pkt[24..26].copy_from_slice(&info.identity.esta_code.to_le_bytes());
+    put_str(&mut pkt[26..44], &info.identity.short_name);
+    put_str(&mut pkt[44..108], &info.identity.long_name);
+    let report = format!(
+        "#{:04x} [{:04}] Power On Tests successful",
+        REPORT_POWER_OK,
+        info.report_count % 10000
+    );
+    put_str(&mut pkt[108..172], &report);
+    pkt[173] = info.ports.len().min(4) as u8; // NumPortsLo
+    for (i, port) in info.ports.iter().take(4).enumerate() {
+        let mut kind = 0u8; // DMX512
+        if port.output {
+            kind |= PORT_TYPE_OUTPUT;
+            pkt[190 + i] = port.port_address.universe(); // SwOut
+            let mut good_a = 0;
+            if port.sources > 0 {
+                good_a |= GOOD_DATA;
+            }
+            if port.sources > 1 {
+                good_a |= GOOD_OUTPUT_A_MERGING;
+            }
+            if info.merge_mode == MergeMode::Ltp {
+                good_a |= GOOD_OUTPUT_A_LTP;
+            }
+            pkt[182 + i] = good_a; // GoodOutputA
+            pkt[213 + i] = GOOD_OUTPUT_B_RDM_DISABLED;
+        }
+        if port.input {
+            kind |= PORT_TYPE_INPUT;
+            pkt[186 + i] = port.port_address.universe(); // SwIn
+            if info.sending {
+                pkt[178 + i] = GOOD_DATA; // GoodInput
+            }
+        }
+        pkt[174 + i] = kind;
+    }
+    pkt[200] = info.identity.style;
+    pkt[201..207].copy_from_slice(&info.mac);
+    pkt[207..211].copy_from_slice(&info.ip.octets()); // BindIp
+    pkt[211] = info.bind_index;
+    pkt[212] = STATUS2_PORT_ADDRESS_15BIT;
+    pkt
+}
+
+pub fn parse_mac(s: &str) -> Option<[u8; 6]> {
+    let parts: Vec<u8> = s
+        .split([':', '-'])
+        .map(|p| u8::from_str_radix(p.trim(), 16))
+        .collect::<Result<_, _>>()
+        .ok()?;
+    parts.try_into().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn interface_mac(name: &str) -> Option<[u8; 6]> {
+    let raw = std::fs::read_to_string(format!("/sys/class/net/{}/address", name)).ok()?;
+    parse_mac(&raw)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interface_mac(_name: &str) -> Option<[u8; 6]> {
+    None
+}
+
+/// Picks the local IPv4 interface a reply to `peer` should describe: the bound
+/// address when the receiver is bound to one, otherwise the interface whose
+/// subnet contains the peer.
+pub fn local_interface_for(peer: IpAddr, bind_ip: &str) -> (Ipv4Addr, [u8; 6]) {
+    let bound: Option<Ipv4Addr> = bind_ip
+        .parse()
+        .ok()
+        .filter(|ip: &Ipv4Addr| !ip.is_unspecified());
+    let peer = match peer {
+        IpAddr::V4(v4) => v4,
+        IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
+    };
+    let mut fallback: Option<(Ipv4Addr, [u8; 6])> = None;
+    if let Ok(ifaces) = if_addrs::get_if_addrs() {
+        for iface in ifaces {
+            if iface.is_loopback() && !peer.is_loopback() {
+                continue;
+            }
+            if let if_addrs::IfAddr::V4(v4) = &iface.addr {
+                let mac = interface_mac(&iface.name).unwrap_or_default();
+                let mask = u32::from(v4.netmask);
+                let matches = match bound {
+                    Some(ip) => v4.ip == ip,
+                    None => u32::from(v4.ip) & mask == u32::from(peer) & mask,
+                };
+                if matches {
+                    return (v4.ip, mac);
+                }
+                if fallback.is_none() && !iface.is_loopback() {
+                    fallback = Some((v4.ip, mac));
+                }
+            }
+        }
+    }
+    match bound {
+        Some(ip) => (ip, [0; 6]),
+        None => fallback.unwrap_or((Ipv4Addr::UNSPECIFIED, [0; 6])),
+    }
+}
+
+/// Protocol carried by a node port (PortTypes bits 0-5).
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PortProtocol {
+    Dmx512,
+    Midi,
+    Avab,
+    ColortranCmx,
+    Adb625,
+    ArtNet,
+    Dali,
+    Other(u8),
+}
+
+impl From<u8> for PortProtocol {
+    fn from(code: u8) -> Self {
+        match code & 0x3f {
+            0 => Self::Dmx512,
+            1 => Self::Midi,
+            2 => Self::Avab,
+            3 => Self::ColortranCmx,
+            4 => Self::Adb625,
+            5 => Self::ArtNet,
+            6 => Self::Dali,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// Input port (DMX -> Art-Net) status from GoodInput.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputPortStatus {
+    pub port_address: PortAddress,
+    pub data_received: bool,
+    pub test_packets: bool,
+    pub sips: bool,
+    pub text_packets: bool,
+    pub disabled: bool,
+    pub receive_errors: bool,
+    /// Protocol the input is converted to.
+    pub converts_to: OutputProtocol,
+}
+
+/// Output port (Art-Net -> DMX) status from GoodOutputA/B.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputPortStatus {
+    pub port_address: PortAddress,
+    pub data_transmitting: bool,
+    pub test_packets: bool,
+    pub sips: bool,
+    pub text_packets: bool,
+    pub merging: bool,
+    pub short_detected: bool,
+    pub merge_mode: MergeMode,
+    /// Protocol the output takes its data from.
+    pub source: OutputProtocol,
+    pub rdm_enabled: bool,
+    pub continuous: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodePortInfo {
+    /// Port index within this reply page (0..=3).
+    pub index: u8,
+    pub protocol: PortProtocol,
+    pub input: Option<InputPortStatus>,
+    pub output: Option<OutputPortStatus>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndicatorState {
+    Unknown,
+    Locate,
+    Mute,
+    Normal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProgrammingAuthority {
+    Unknown,
+    FrontPanel,
+    Network,
+    NotUsed,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status1 {
+    pub indicators: IndicatorState,
+    pub programming_authority: ProgrammingAuthority,
+    pub rom_boot: bool,
+    pub rdm_capable: bool,
+    pub ubea_present: bool,
+}
+
+impl From<u8> for Status1 {
+    fn from(b: u8) -> Self {
+        Self {
+            indicators: match b >> 6 {
+                1 => IndicatorState::Locate,
+                2 => IndicatorState::Mute,
+                3 => IndicatorState::Normal,
+                _ => IndicatorState::Unknown,
+            },
+            programming_authority: match (b >> 4) & 0x03 {
+                1 => ProgrammingAuthority::FrontPanel,
+                2 => ProgrammingAuthority::Network,
+                3 => ProgrammingAuthority::NotUsed,
+                _ => ProgrammingAuthority::Unknown,
+            },
+            rom_boot: b & 0x04 != 0,
+            rdm_capable: b & 0x02 != 0,
+            ubea_present: b & 0x01 != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status2 {
+    pub web_config: bool,
+    pub dhcp_configured: bool,
+    pub dhcp_capable: bool,
+    pub port_address_15bit: bool,
+    pub sacn_switchable: bool,
+    pub squawking: bool,
+    pub output_style_switchable: bool,
+    pub rdm_via_art_command: bool,
+}
+
+impl From<u8> for Status2 {
+    fn from(b: u8) -> Self {
+        Self {
+            web_config: b & 0x01 != 0,
+            dhcp_configured: b & 0x02 != 0,
+            dhcp_capable: b & 0x04 != 0,
+            port_address_15bit: b & 0x08 != 0,
+            sacn_switchable: b & 0x10 != 0,
+            squawking: b & 0x20 != 0,
+            output_style_switchable: b & 0x40 != 0,
+            rdm_via_art_command: b & 0x80 != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FailsafeState {
+    HoldLast,
+    AllZero,
+    AllFull,
+    Scene,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status3 {
+    pub failsafe: FailsafeState,
+    pub failsafe_programmable: bool,
+    pub llrp: bool,
+    pub port_direction_switchable: bool,
+    pub rdmnet: bool,
+    pub background_queue: bool,
+    pub background_discovery_programmable: bool,
+}
+
+impl From<u8> for Status3 {
+    fn from(b: u8) -> Self {
+        Self {
+            failsafe: match b >> 6 {
+                1 => FailsafeState::AllZero,
+                2 => FailsafeState::AllFull,
+                3 => FailsafeState::Scene,
+                _ => FailsafeState::HoldLast,
+            },
+            failsafe_programmable: b & 0x20 != 0,
+            llrp: b & 0x10 != 0,
+            port_direction_switchable: b & 0x08 != 0,
+            rdmnet: b & 0x04 != 0,
+            background_queue: b & 0x02 != 0,
+            background_discovery_programmable: b & 0x01 != 0,
+        }
+    }
+}
+
+pub fn style_name(style: u8) -> &'static str {
+    match style {
+        0x00 => "StNode",
+        0x01 => "StController",
+        0x02 => "StMedia",
+        0x03 => "StRoute",
+        0x04 => "StBackup",
+        0x05 => "StConfig",
+        0x06 => "StVisual",
+        _ => "Unknown",
+    }
+}
+
+/// Decoded ArtPollReply.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollReply {
+    pub ip: String,
+    pub artnet_port: u16,
+    pub firmware: String,
+    pub net_switch: u8,
+    pub sub_switch: u8,
+    pub oem: u16,
+    pub esta_code: u16,
+    pub short_name: String,
+    pub long_name: String,
+    pub node_report: String,
+    pub num_ports: u16,
+    pub ports: Vec<NodePortInfo>,
+    pub acn_priority: u8,
+    pub style: u8,
+    pub style_name: String,
+    pub mac: String,
+    pub bind_ip: String,
+    pub bind_index: u8,
+    pub status1: Status1,
+    pub status2: Status2,
+    pub status3: Status3,
+}
+
+fn get_str(buf: &[u8]) -> String {
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).trim().to_string()
+}
+
+pub fn format_mac(mac: &[u8]) -> String {
+    mac.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Shortest reply accepted; fields past it (Style, MAC, BindIp, Status2,
+/// GoodOutputB, Status3 ...) were added over protocol revisions and read
+/// as zero when absent.
+const POLL_REPLY_MIN_LEN: usize = 197;
+
+pub fn parse_poll_reply(buf: &[u8]) -> Result<PollReply> {
+    if buf.len() < POLL_REPLY_MIN_LEN || artnet::opcode(buf) != Some(artnet::OP_POLL_REPLY) {
+        return Err(anyhow!("Not an ArtPollReply"));
+    }
+    let mut pkt = [0u8; POLL_REPLY_LEN];
+    let n = buf.len().min(POLL_REPLY_LEN);
+    pkt[..n].copy_from_slice(&buf[..n]);
+
+    let net = pkt[18] & 0x7f;
+    let sub = pkt[19] & 0x0f;
+    let port_address = |sw: u8| PortAddress::from_parts(net, sub, sw & 0x0f).unwrap_or_default();
+    let protocol_of = |sacn: bool| {
+        if sacn {
+            OutputProtocol::Sacn
+        } else {
+            OutputProtocol::ArtNet
+        }
+    };
+    let num_ports = u16::from_be_bytes([pkt[172], pkt[173]]);
+    let ports = (0..num_ports.min(4) as usize)
+        .map(|i| {
+            let kind = pkt[174 + i];
+            let good_in = pkt[178 + i];
+            let good_a = pkt[182 + i];
+            let good_b = pkt[213 + i];
+            NodePortInfo {
+                index: i as u8,
+                protocol: PortProtocol::from(kind),
+                input: (kind & PORT_TYPE_INPUT != 0).then(|| InputPortStatus {
+                    port_address: port_address(pkt[186 + i]),
+                    data_received: good_in & 0x80 != 0,
+                    test_packets: good_in & 0x40 != 0,
+                    sips: good_in & 0x20 != 0,
+                    text_packets: good_in & 0x10 != 0,
+                    disabled: good_in & 0x08 != 0,
+                    receive_errors: good_in & 0x04 != 0,
+                    converts_to: protocol_of(good_in & 0x01 != 0),
+                }),
+                output: (kind & PORT_TYPE_OUTPUT != 0).then(|| OutputPortStatus {
+                    port_address: port_address(pkt[190 + i]),
+                    data_transmitting: good_a & 0x80 != 0,
+                    test_packets: good_a & 0x40 != 0,
+                    sips: good_a & 0x20 != 0,
+                    text_packets: good_a & 0x10 != 0,
+                    merging: good_a & 0x08 != 0,
+                    short_detected: good_a & 0x04 != 0,
+                    merge_mode: if good_a & 0x02 != 0 {
+                        MergeMode::Ltp
+                    } else {
+                        MergeMode::Htp
+                    },
+                    source: protocol_of(good_a & 0x01 != 0),
+                    rdm_enabled: good_b & GOOD_OUTPUT_B_RDM_DISABLED == 0,
+                    continuous: good_b & 0x40 != 0,
+                }),
+            }
+        })
+        .collect();
+
+    Ok(PollReply {
+        ip: Ipv4Addr::new(pkt[10], pkt[11], pkt[12], pkt[13]).to_string(),
+        artnet_port: u16::from_le_bytes([pkt[14], pkt[15]]),
+        firmware: format!("{}.{}", pkt[16], pkt[17]),
+        net_switch: pkt[18],
+        sub_switch: pkt[19],
+        oem: u16::from_be_bytes([pkt[20], pkt[21]]),
+        esta_code: u16::from_le_bytes([pkt[24], pkt[25]]),
+        short_name: get_str(&pkt[26..44]),
+        long_name: get_str(&pkt[44..108]),
+        node_report: get_str(&pkt[108..172]),
+        num_ports,
+        ports,
+        acn_priority: pkt[194],
+        style: pkt[200],
+        style_name: style_name(pkt[200]).to_string(),
+        mac: format_mac(&pkt[201..207]),
+        bind_ip: Ipv4Addr::new(pkt[207], pkt[208], pkt[209], pkt[210]).to_string(),
+        bind_index: pkt[211],
+        status1: Status1::from(pkt[23]),
+        status2: Status2::from(pkt[212]),
+        status3: Status3::from(pkt[217]),
+    })
+}
@@ -0,0 +1,590 @@
+//! Reading Art-Net / sACN traffic from Wireshark pcap and pcapng captures,
+//! and writing the receiver's packet capture as pcapng.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use anyhow::{anyhow, Result};
+
+use crate::artnet::{self, NzsFrame};
+use crate::inspector::CapturedPacket;
+use crate::recording;
+use crate::sacn;
+use crate::state::{NzsRecord, PlayFrame, RecordData};
+
+const PCAP_MAGIC_US: u32 = 0xa1b2_c3d4;
+const PCAP_MAGIC_NS: u32 = 0xa1b2_3c4d;
+const PCAPNG_SHB: u32 = 0x0a0d_0d0a;
+const PCAPNG_BYTE_ORDER: u32 = 0x1a2b_3c4d;
+const PCAPNG_IDB: u32 = 0x0000_0001;
+const PCAPNG_PB: u32 = 0x0000_0002; // obsolete Packet Block
+const PCAPNG_EPB: u32 = 0x0000_0006;
+const OPT_IF_TSRESOL: u16 = 9;
+
+const LINKTYPE_NULL: u16 = 0;
+const LINKTYPE_ETHERNET: u16 = 1;
+const LINKTYPE_RAW: u16 = 101;
+const LINKTYPE_LOOP: u16 = 108;
+const LINKTYPE_LINUX_SLL: u16 = 113;
+const LINKTYPE_IPV4: u16 = 228;
+const LINKTYPE_IPV6: u16 = 229;
+const LINKTYPE_LINUX_SLL2: u16 = 276;
+// DLT_RAW as written by some BSDs.
+const DLT_RAW_BSD: [u16; 2] = [12, 14];
+
+const IP_PROTO_UDP: u8 = 17;
+
+/// Whether `path` looks like a Wireshark capture.
+pub fn is_capture_file(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".pcap") || lower.ends_with(".pcapng") || lower.ends_with(".cap")
+}
+
+/// UDP payload on the Art-Net or sACN port, with its capture time.
+pub struct Datagram {
+    /// Microseconds since the Unix epoch.
+    pub t_us: u64,
+    pub source: SocketAddr,
+    pub destination: SocketAddr,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Clone, Copy)]
+enum Endian {
+    Little,
+    Big,
+}
+
+impl Endian {
+    fn u16(self, b: &[u8]) -> u16 {
+        let b = [b[0], b[1]];
+        match self {
+            Self::Little => u16::from_le_bytes(b),
+            Self::Big => u16::from_be_bytes(b),
+        }
+    }
+
+    fn u32(self, b: &[u8]) -> u32 {
+        let b = [b[0], b[1], b[2], b[3]];
+        match self {
+            Self::Little => u32::from_le_bytes(b),
+            Self::Big => u32::from_be_bytes(b),
+        }
+    }
+}
+
+fn be16(b: &[u8], at: usize) -> u16 {
+    u16::from_be_bytes([b[at], b[at + 1]])
+}
+
+/// Returns the IP packet inside a link-layer frame.
+fn ip_packet(linktype: u16, frame: &[u8]) -> Option<&[u8]> {
+    match linktype {
+        LINKTYPE_RAW | LINKTYPE_IPV4 | LINKTYPE_IPV6 => Some(frame),
+        l if DLT_RAW_BSD.contains(&l) => Some(frame),
+        // Four-byte address family, in host or network order.
+        LINKTYPE_NULL | LINKTYPE_LOOP => frame.get(4..),
+        LINKTYPE_ETHERNET => {
+            let mut at = 12;
+            // Skip 802.1Q / 802.1ad tags.
+            while matches!(frame.get(at..at + 2), Some([0x81, 0x00] | [0x88, 0xa8])) {
+                at += 4;
+            }
+            match frame.get(at..at + 2)? {
+                [0x08, 0x00] | [0x86, 0xdd] => frame.get(at + 2..),
+                _ => None,
+            }
+        }
+        LINKTYPE_LINUX_SLL => frame.get(16..),
+        LINKTYPE_LINUX_SLL2 => frame.get(20..),
+        _ => None,
+    }
+}
+
+/// Source, destination and payload of a UDP datagram in an IP packet.
+/// Fragments are skipped; Art-Net and sACN packets fit a single one.
+fn udp_datagram(ip: &[u8]) -> Option<(SocketAddr, SocketAddr, &[u8])> {
+    let (src, dst, udp) = match ip.first()? >> 4 {
+        4 => {
+            let ihl = (ip[0] & 0x0f) as usize * 4;
+            if ip.len() < 20 || ihl < 20 || ip.len() < ihl || ip[9] != IP_PROTO_UDP {
+                return None;
+            }
+            let frag = be16(ip, 6);
+            if frag & 0x3fff != 0 {
+                return None;
+            }
+            let total = (be16(ip, 2) as usize).clamp(ihl, ip.len());
+            let src = IpAddr::V4(Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]));
+            let dst = IpAddr::V4(Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]));
+            (src, dst, ip.get(ihl..total)?)
+        }
+        6 => {
+            if ip.len() < 40 {
+                return None;
+            }
+            let mut next = ip[6];
+            let mut at = 40;
+            // Hop-by-hop, routing and destination options headers.
+            while matches!(next, 0 | 43 | 60) {
+                let hdr = ip.get(at..at + 2)?;
+                next = hdr[0];
+                at += (hdr[1] as usize + 1) * 8;
+            }
+            if next != IP_PROTO_UDP {
+                return None;
+            }
+            let octets = |from: usize| -> [u8; 16] { ip[from..from + 16].try_into().unwrap() };
+            let end = (40 + be16(ip, 4) as usize).min(ip.len());
+            (
+                IpAddr::V6(Ipv6Addr::from(octets(8))),
+                IpAddr::V6(Ipv6Addr::from(octets(24))),
+                ip.get(at..end)?,
+            )
+        }
+        _ => return None,
+    };
+    if udp.len() < 8 {
+        return None;
+    }
+    let len = (be16(udp, 4) as usize).clamp(8, udp.len());
+    Some((
+        SocketAddr::new(src, be16(udp, 0)),
+        SocketAddr::new(dst, be16(udp, 2)),
+        &udp[8..len],
+    ))
+}
+
+fn is_dmx_port(port: u16) -> bool {
+    port == artnet::ARTNET_PORT || port == sacn::SACN_PORT
+}
+
+fn push_datagram(out: &mut Vec<Datagram>, linktype: u16, t_us: u64, frame: &[u8]) {
+    let Some((source, destination, payload)) = ip_packet(linktype, frame).and_then(udp_datagram)
+    else {
+        return;
+    };
+    // Art-Net sent to a non-standard port is still recognised by its header.
+    if is_dmx_port(destination.port())
+        || is_dmx_port(source.port())
+        || artnet::opcode(payload).is_some()
+    {
+        out.push(Datagram {
+            t_us,
+            source,
+            destination,
+            payload: payload.to_vec(),
+        });
+    }
+}
+
+fn read_pcap(buf: &[u8]) -> Result<Vec<Datagram>> {
+    if buf.len() < 24 {
+        return Err(anyhow!("Truncated pcap header"));
+    }
+    let (endian, nanos) = match (
+        u32::from_le_bytes(buf[0..4].try_into()?),
+        u32::from_be_bytes(buf[0..4].try_into()?),
+    ) {
+        (PCAP_MAGIC_US, _) => (Endian::Little, false),
+        (PCAP_MAGIC_NS, _) => (Endian::Little, true),
+        (_, PCAP_MAGIC_US) => (Endian::Big, false),
+        (_, PCAP_MAGIC_NS) => (Endian::Big, true),
+        _ => return Err(anyhow!("Not a pcap file")),
+    };
+    // The upper bits of the link type field carry FCS flags.
+    let linktype = endian.u32(&buf[20..24]) as u16;
+    let mut out = Vec::new();
+    let mut at = 24;
+    while at + 16 <= buf.len() {
+        let sec = endian.u32(&buf[at..]) as u64;
+        let frac = endian.u32(&buf[at + 4..]) as u64;
+        let incl = endian.u32(&buf[at + 8..]) as usize;
+        let data = buf
+            .get(at + 16..at + 16 + incl)
+            .ok_or_else(|| anyhow!("Truncated pcap record"))?;
+        let t_us = sec * 1_000_000 + if nanos { frac / 1000 } else { frac };
+        push_datagram(&mut out, linktype, t_us, data);
+        at += 16 + incl;
+    }
+    Ok(out)
+}
+
+struct Interface {
+    linktype: u16,
+    /// Timestamp units per second.
+    resolution: u64,
+}
+
+fn tsresol(value: u8) -> u64 {
+    let exp = (value & 0x7f) as u32;
+    if value & 0x80 == 0 {
+        10u64.checked_pow(exp).unwrap_or(1_000_000)
+    } else {
+        2u64.checked_pow(exp).unwrap_or(1_000_000)
+    }
+}
+
+fn read_pcapng(buf: &[u8]) -> Result<Vec<Datagram>> {
+    let mut out = Vec::new();
+    let mut endian = Endian::Little;
+    let mut interfaces: Vec<Interface> = Vec::new();
+    let mut at = 0;
+    while at + 12 <= buf.len() {
+        if u32::from_le_bytes(buf[at..at + 4].try_into()?) == PCAPNG_SHB {
+            // A new section may switch byte order and resets the interfaces.
+            endian = match buf[at + 8..at + 12] {
+                [0x4d, 0x3c, 0x2b, 0x1a] => Endian::Little,
+                [0x1a, 0x2b, 0x3c, 0x4d] => Endian::Big,
+                _ => return Err(anyhow!("Bad pcapng byte-order magic")),
+            };
+            interfaces.clear();
+        }
+        let kind = endian.u32(&buf[at..]);
+        let len = endian.u32(&buf[at + 4..]) as usize;
+        if len < 12 || !len.is_multiple_of(4) {
+            return Err(anyhow!("Bad pcapng block length {}", len));
+        }
+        let block = buf
+            .get(at..at + len)
+            .ok_or_else(|| anyhow!("Truncated pcapng block"))?;
+        match kind {
+            PCAPNG_IDB if len >= 20 => {
+                let mut iface = Interface {
+                    linktype: endian.u16(&block[8..]),
+                    resolution: 1_000_000,
+                };
+                let mut opt = 16;
+                while opt + 4 <= len - 4 {
+                    let code = endian.u16(&block[opt..]);
+                    let opt_len = endian.u16(&block[opt + 2..]) as usize;
+                    if code == 0 {
+                        break;
+                    }
+                    if code == OPT_IF_TSRESOL && opt_len >= 1 {
+                        iface.resolution = tsresol(block[opt + 4]);
+                    }
+                    opt += 4 + opt_len.div_ceil(4) * 4;
+                }
+                interfaces.push(iface);
+            }
+            PCAPNG_EPB | PCAPNG_PB if len >= 32 => {
+                let iface_id = if kind == PCAPNG_EPB {
+                    endian.u32(&block[8..]) as usize
+                } else {
+                    endian.u16(&block[8..]) as usize
+                };
+                let iface = interfaces
+                    .get(iface_id)
+                    .ok_or_else(|| anyhow!("Packet for undeclared interface {}", iface_id))?;
+                let ts =
+                    ((endian.u32(&block[12..]) as u64) << 32) | endian.u32(&block[16..]) as u64;
+                let cap = endian.u32(&block[20..]) as usize;
+                let data = block
+                    .get(28..28 + cap)
+                    .ok_or_else(|| anyhow!("Truncated pcapng packet"))?;
+                let t_us = (ts as u128 * 1_000_000 / iface.resolution as u128) as u64;
+                push_datagram(&mut out, iface.linktype, t_us, data);
+            }
+            // Simple Packet Blocks carry no timestamp; other blocks are
+            // metadata.
+            _ => {}
+        }
+        at += len;
+    }
+    Ok(out)
+}
+
+/// Reads the Art-Net and sACN datagrams of a pcap or pcapng capture.
+pub fn read_datagrams(path: &str) -> Result<Vec<Datagram>> {
+    let buf = std::fs::read(path)?;
+    if buf.len() >= 4 && u32::from_le_bytes(buf[0..4].try_into()?) == PCAPNG_SHB {
+        read_pcapng(&buf)
+    } else {
+        read_pcap(&buf)
+    }
+}
+
+/// DMX frames of the datagrams, timed from the earliest datagram. Preview,
+/// stream terminated and non-zero start code sACN packets are left out.
+pub fn dmx_frames(datagrams: &[Datagram]) -> Vec<PlayFrame> {
+    let mut frames = Vec::new();
+    // Captures from several interfaces are not always in time order.
+    let base = datagrams.iter().map(|d| d.t_us).min().unwrap_or(0);
+    for d in datagrams {
+        let dmx = if let Ok(frame) = artnet::parse_artdmx(&d.payload) {
+            Some((frame.port_address, frame.values))
+        } else if let Ok(packet) = sacn::parse_data(&d.payload) {
+            (!packet.preview && !packet.stream_terminated && packet.start_code == 0)
+                .then(|| sacn::port_address_for(packet.universe))
+                .flatten()
+                .map(|addr| (addr, packet.values))
+        } else {
+            None
+        };
+        let Some((port_address, values)) = dmx else {
+            continue;
+        };
+        let mut data = [0u8; 512];
+        let n = values.len().min(512);
+        data[..n].copy_from_slice(&values[..n]);
+        frames.push(PlayFrame {
+            t_ms: d.t_us.saturating_sub(base) / 1000,
+            port_address,
+            data,
+        });
+    }
+    frames.sort_by_key(|f| f.t_ms);
+    frames
+}
+
+/// ArtNzs and alternate start code sACN frames of the datagrams, timed like
+/// `dmx_frames`.
+pub fn nzs_records(datagrams: &[Datagram]) -> Vec<NzsRecord> {
+    let base = datagrams.iter().map(|d| d.t_us).min().unwrap_or(0);
+    let mut records: Vec<NzsRecord> = datagrams
+        .iter()
+        .filter_map(|d| {
+            let frame = if let Ok(frame) = artnet::parse_artnzs(&d.payload) {
+                frame
+            } else {
+                let packet = sacn::parse_data(&d.payload).ok()?;
+                if packet.preview || packet.stream_terminated || packet.start_code == 0 {
+                    return None;
+                }
+                NzsFrame {
+                    port_address: sacn::port_address_for(packet.universe)?,
+                    start_code: packet.start_code,
+                    length: packet.values.len() as u16,
+                    sequence: packet.sequence,
+                    values: packet.values,
+                }
+            };
+            Some(NzsRecord {
+                t_ms: d.t_us.saturating_sub(base) / 1000,
+                frame,
+            })
+        })
+        .collect();
+    records.sort_by_key(|r| r.t_ms);
+    records
+}
+
+fn require_dmx(path: &str, frames: Vec<PlayFrame>) -> Result<Vec<PlayFrame>> {
+    if frames.is_empty() {
+        return Err(anyhow!("No Art-Net or sACN DMX found in {}", path));
+    }
+    Ok(frames)
+}
+
+/// DMX frames of a capture, for playback.
+pub fn load_frames(path: &str) -> Result<Vec<PlayFrame>> {
+    require_dmx(path, dmx_frames(&read_datagrams(path)?))
+}
+
+/// Loads a capture as a recording with a track per universe.
+pub fn load_record_data(path: &str) -> Result<RecordData> {
+    let datagrams = read_datagrams(path)?;
+    let frames = require_dmx(path, dmx_frames(&datagrams))?;
+    Ok(recording::record_data_from_frames(
+        &frames,
+        nzs_records(&datagrams),
+    ))
+}
+
+fn block(kind: u32, body: &[u8]) -> Vec<u8> {
+    let len = (12 + body.len().div_ceil(4) * 4) as u32;
+    let mut out = Vec::with_capacity(len as usize);
+    out.extend_from_slice(&kind.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(body);
+    out.resize(len as usize - 4, 0);
+    out.extend_from_slice(&len.to_le_bytes());
+    out
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|w| u16::from_be_bytes([w[0], w[1]]) as u32)
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// IPv4 / UDP packet around a captured payload. The UDP checksum is left
+/// at zero, which IPv4 allows.
+fn ipv4_udp(id: u16, src: Ipv4Addr, dst: SocketAddr, sport: u16, payload: &[u8]) -> Vec<u8> {
+    let dst_ip = match dst.ip() {
+        IpAddr::V4(v4) => v4,
+        IpAddr::V6(_) => Ipv4Addr::UNSPECIFIED,
+    };
+    let udp_len = 8 + payload.len();
+    let total = 20 + udp_len;
+    let mut pkt = Vec::with_capacity(total);
+    pkt.extend_from_slice(&[0x45, 0]);
+    pkt.extend_from_slice(&(total as u16).to_be_bytes());
+    pkt.extend_from_slice(&id.to_be_bytes());
+    pkt.extend_from_slice(&[0x40, 0, 64, IP_PROTO_UDP, 0, 0]); // DF, TTL 64
+    pkt.extend_from_slice(&src.octets());
+    pkt.extend_from_slice(&dst_ip.octets());
+    let sum = ipv4_checksum(&pkt);
+    pkt[10..12].copy_from_slice(&sum.to_be_bytes());
+    pkt.extend_from_slice(&sport.to_be_bytes());
+    pkt.extend_from_slice(&dst.port().to_be_bytes());
+    pkt.extend_from_slice(&(udp_len as u16).to_be_bytes());
+    pkt.extend_from_slice(&[0, 0]);
+    pkt.extend_from_slice(payload);
+    pkt
+}
+
+/// Writes captured packets as pcapng with raw IPv4 frames. Art-Net is IPv4
+/// only; packets from IPv6 sources are skipped. Returns the number written.
+///
+/// The destination IP is synthetic: the receiver's bind IP, or 0.0.0.0 when
+/// it listens on every interface (see `CapturedPacket::destination`).
+pub fn write_pcapng(path: &str, packets: &[CapturedPacket]) -> Result<usize> {
+    let mut out = Vec::new();
+    let mut shb = Vec::new();
+    shb.extend_from_slice(&PCAPNG_BYTE_ORDER.to_le_bytes());
+    shb.extend_from_slice(&1u16.to_le_bytes()); // major
+    shb.extend_from_slice(&0u16.to_le_bytes()); // minor
+    shb.extend_from_slice(&(-1i64).to_le_bytes()); // section length unknown
+    out.extend(block(PCAPNG_SHB, &shb));
+    let mut idb = Vec::new();
+    idb.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+    idb.extend_from_slice(&0u16.to_le_bytes());
+    idb.extend_from_slice(&0u32.to_le_bytes()); // no snap length
+    out.extend(block(PCAPNG_IDB, &idb));
+
+    let mut written = 0;
+    for p in packets {
+        let IpAddr::V4(src) = p.source.ip() else {
+            continue;
+        };
+        let frame = ipv4_udp(p.id as u16, src, p.destination, p.source.port(), &p.data);
+        let mut epb = Vec::with_capacity(20 + frame.len());
+        epb.extend_from_slice(&0u32.to_le_bytes()); // interface
+        epb.extend_from_slice(&((p.at_us >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(p.at_us as u32).to_le_bytes());
+        epb.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&frame);
+        out.extend(block(PCAPNG_EPB, &epb));
+        written += 1;
+    }
+    std::fs::write(path, out)?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("artnetlab-{}-{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn packets() -> Vec<CapturedPacket> {
+        (0..5u64)
+            .map(|i| CapturedPacket {
+                id: i,
+                at_us: 1_700_000_000_000_000 + i * 22_727,
+                source: SocketAddr::from(([10, 0, 0, 10 + i as u8], 6454)),
+                destination: SocketAddr::from(([10, 0, 0, 255], 6454)),
+                data: (0..18 + i as usize * 100).map(|b| b as u8).collect(),
+            })
+            .collect()
+    }
+
+    fn check(datagrams: &[Datagram], packets: &[CapturedPacket]) {
+        assert_eq!(datagrams.len(), packets.len());
+        for (d, p) in datagrams.iter().zip(packets) {
+            assert_eq!(d.t_us, p.at_us);
+            assert_eq!(d.source, p.source);
+            assert_eq!(d.destination, p.destination);
+            assert_eq!(d.payload, p.data);
+        }
+    }
+
+    /// Classic pcap, microsecond timestamps, of the packets in Ethernet
+    /// frames.
+    fn pcap_file(packets: &[CapturedPacket]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&PCAP_MAGIC_US.to_le_bytes());
+        out.extend_from_slice(&[2, 0, 4, 0]);
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&65535u32.to_le_bytes());
+        out.extend_from_slice(&(LINKTYPE_ETHERNET as u32).to_le_bytes());
+        for p in packets {
+            let IpAddr::V4(src) = p.source.ip() else {
+                unreachable!()
+            };
+            let mut frame = vec![0xff; 6];
+            frame.extend_from_slice(&[2, 0, 0, 0, 0, 1]);
+            frame.extend_from_slice(&[0x81, 0x00, 0, 1]); // VLAN 1
+            frame.extend_from_slice(&[0x08, 0x00]);
+            frame.extend(ipv4_udp(0, src, p.destination, p.source.port(), &p.data));
+            out.extend_from_slice(&((p.at_us / 1_000_000) as u32).to_le_bytes());
+            out.extend_from_slice(&((p.at_us % 1_000_000) as u32).to_le_bytes());
+            out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            out.extend(frame);
+        }
+        out
+    }
+
+    #[test]
+    fn pcapng_round_trip() {
+        let packets = packets();
+        let path = temp_path("round-trip.pcapng");
+        assert_eq!(write_pcapng(&path, &packets).unwrap(), packets.len());
+        let datagrams = read_datagrams(&path);
+        std::fs::remove_file(&path).unwrap();
+        check(&datagrams.unwrap(), &packets);
+    }
+
+    #[test]
+    fn pcap_round_trip() {
+        let packets = packets();
+        let path = temp_path("round-trip.pcap");
+        std::fs::write(&path, pcap_file(&packets)).unwrap();
+        let datagrams = read_datagrams(&path);
+        std::fs::remove_file(&path).unwrap();
+        check(&datagrams.unwrap(), &packets);
+    }
+
+    #[test]
+    fn skips_truncated_frames() {
+        let p = &packets()[3];
+        let frame = ipv4_udp(0, Ipv4Addr::new(10, 0, 0, 1), p.destination, 6454, &p.data);
+        // Header length beyond the captured bytes.
+        let mut short = frame[..24].to_vec();
+        short[0] = 0x4f;
+        assert!(udp_datagram(&short).is_none());
+        // Snap length cutting into the UDP header and into the payload.
+        assert!(udp_datagram(&frame[..24]).is_none());
+        let (_, _, payload) = udp_datagram(&frame[..100]).unwrap();
+        assert_eq!(payload, &p.data[..72]);
+
+        let mut packets = packets();
+        packets.truncate(2);
+        let mut file = pcap_file(&packets);
+        file.extend_from_slice(&[0; 4]);
+        file.extend_from_slice(&[0; 4]);
+        file.extend_from_slice(&(short.len() as u32 + 14).to_le_bytes());
+        file.extend_from_slice(&(frame.len() as u32 + 14).to_le_bytes());
+        file.extend_from_slice(&[0xff; 12]);
+        file.extend_from_slice(&[0x08, 0x00]);
+        file.extend_from_slice(&short);
+        let path = temp_path("truncated.pcap");
+        std::fs::write(&path, file).unwrap();
+        let datagrams = read_datagrams(&path);
+        std::fs::remove_file(&path).unwrap();
+        check(&datagrams.unwrap(), &packets);
+    }
+}
@@ -0,0 +1,444 @@
+//! Pre-roll recording: an always-on ring buffer of the last seconds of
+//! received frames, frozen together with a post-roll into a recording when a
+//! trigger condition fires.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::artnet::{DmxFrame, NzsFrame, PortAddress};
+use crate::recording::{self, TimedFrame};
+use crate::state::{NzsRecord, RecordData, RecordTrackInfo};
+use crate::trigger::{self, TriggerMatch};
+
+/// Longest pre- or post-roll, bounding the memory of the ring buffer.
+pub const MAX_ROLL_SEC: f64 = 600.0;
+/// Most frames the ring holds, whatever the rolls and frame rates. Frames
+/// the limit pushes out of the window are counted in `dropped_frames`.
+const MAX_RING_FRAMES: usize = 200_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreRollConfig {
+    /// Seconds kept before the trigger.
+    #[serde(default = "default_pre_sec")]
+    pub pre_sec: f64,
+    /// Seconds recorded after the trigger.
+    #[serde(default = "default_post_sec")]
+    pub post_sec: f64,
+    /// Universes buffered; empty buffers every universe.
+    #[serde(default)]
+    pub universes: Vec<PortAddress>,
+    #[serde(default)]
+    pub triggers: Vec<PreRollTrigger>,
+}
+
+fn default_pre_sec() -> f64 {
+    10.0
+}
+
+fn default_post_sec() -> f64 {
+    5.0
+}
+
+/// Direction a channel value passes its threshold in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Crossing {
+    #[default]
+    Rising,
+    Falling,
+    Either,
+}
+
+/// Condition that freezes the pre-roll. Channels are 1-based.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum PreRollTrigger {
+    /// The channel passes `threshold`: rising when it goes from below to at
+    /// or above it, falling the other way.
+    #[serde(rename_all = "camelCase")]
+    Threshold {
+        port_address: PortAddress,
+        channel: u16,
+        threshold: u8,
+        #[serde(default)]
+        crossing: Crossing,
+    },
+    /// The channel takes any new value.
+    #[serde(rename_all = "camelCase")]
+    Change {
+        port_address: PortAddress,
+        channel: u16,
+    },
+    /// No frames on a universe that was being received for `timeout_ms`.
+    #[serde(rename_all = "camelCase")]
+    UniverseStopped {
+        port_address: PortAddress,
+        #[serde(default = "default_stop_timeout_ms")]
+        timeout_ms: u64,
+    },
+    /// A received ArtTrigger (or ArtCommand), matched like trigger mappings.
+    Packet { on: TriggerMatch },
+}
+
+fn default_stop_timeout_ms() -> u64 {
+    1000
+}
+
+impl PreRollTrigger {
+    /// Reason for firing on a frame changing from `prev` to `values`.
+    fn on_frame(&self, addr: PortAddress, prev: &[u8], values: &[u8]) -> Option<String> {
+        let (port_address, channel) = match self {
+            Self::Threshold {
+                port_address,
+                channel,
+                ..
+            }
+            | Self::Change {
+                port_address,
+                channel,
+            } => (*port_address, *channel),
+            _ => return None,
+        };
+        if port_address != addr || channel == 0 {
+            return None;
+        }
+        let idx = channel as usize - 1;
+        // Senders trim trailing zeros, so channels past the end are 0.
+        let before = prev.get(idx).copied().unwrap_or(0);
+        let after = values.get(idx).copied().unwrap_or(0);
+        let fired = match self {
+            Self::Threshold {
+                threshold,
+                crossing,
+                ..
+            } => {
+                let rising = before < *threshold && after >= *threshold;
+                let falling = before >= *threshold && after < *threshold;
+                match crossing {
+                    Crossing::Rising => rising,
+                    Crossing::Falling => falling,
+                    Crossing::Either => rising || falling,
+                }
+            }
+            _ => before != after,
+        };
+        fired.then(|| format!("{} channel {}: {} -> {}", addr, channel, before, after))
+    }
+}
+
+/// Universe last seen by the buffer, for change and stop detection.
+struct SeenUniverse {
+    values: Vec<u8>,
+    at_ms: u64,
+    /// A stop has been reported and frames have not resumed since.
+    stopped: bool,
+}
+
+struct Capture {
+    reason: String,
+    fired_ms: u64,
+    until_ms: u64,
+}
+
+/// A frozen pre-roll, in the form the buffered recorder saves.
+pub struct PreRollCapture {
+    pub reason: String,
+    /// Time of the trigger within the recording.
+    pub trigger_ms: u64,
+    /// Frames of the window lost to the ring's frame limit.
+    pub dropped_frames: usize,
+    pub data: RecordData,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreRollCaptureInfo {
+    pub reason: String,
+    pub trigger_ms: u64,
+    pub frames: usize,
+    pub duration_ms: u64,
+    pub dropped_frames: usize,
+    pub tracks: Vec<RecordTrackInfo>,
+}
+
+impl PreRollCapture {
+    pub fn info(&self) -> PreRollCaptureInfo {
+        PreRollCaptureInfo {
+            reason: self.reason.clone(),
+            trigger_ms: self.trigger_ms,
+            frames: self.data.frame_count(),
+            duration_ms: self.data.duration_ms(),
+            dropped_frames: self.dropped_frames,
+            tracks: self.data.track_infos(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreRollStatus {
+    pub config: PreRollConfig,
+    /// Length of the pre-roll held right now.
+    pub buffered_ms: u64,
+    pub buffered_frames: usize,
+    /// Frames of the pre-roll (and running post-roll) lost to the ring's
+    /// frame limit; more than zero means the window does not fit.
+    pub dropped_frames: usize,
+    /// Reason of the trigger whose post-roll is being recorded.
+    pub capturing: Option<String>,
+    /// Captures completed since the buffer was armed.
+    pub captures: usize,
+    pub last_capture: Option<PreRollCaptureInfo>,
+}
+
+pub struct PreRoll {
+    config: PreRollConfig,
+    start: Instant,
+    ring: VecDeque<TimedFrame>,
+    /// Times of frames the frame limit pushed out of the ring, with how
+    /// many at each.
+    dropped: VecDeque<(u64, usize)>,
+    seen: BTreeMap<PortAddress, SeenUniverse>,
+    capture: Option<Capture>,
+    captures: usize,
+    last_capture: Option<PreRollCapture>,
+}
+
+impl PreRoll {
+    pub fn new(mut config: PreRollConfig) -> Self {
+        let clamp = |sec: f64| {
+            if sec.is_finite() {
+                sec.clamp(0.0, MAX_ROLL_SEC)
+            } else {
+                0.0
+            }
+        };
+        config.pre_sec = clamp(config.pre_sec);
+        config.post_sec = clamp(config.post_sec);
+        Self {
+            config,
+            start: Instant::now(),
+            ring: VecDeque::new(),
+            dropped: VecDeque::new(),
+            seen: BTreeMap::new(),
+            capture: None,
+            captures: 0,
+            last_capture: None,
+        }
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    fn buffers(&self, addr: PortAddress) -> bool {
+        self.config.universes.is_empty() || self.config.universes.contains(&addr)
+    }
+
+    /// Start of the window a capture keeps: the pre-roll before the
+    /// running trigger, or before now.
+    fn window_start(&self, now: u64) -> u64 {
+        let from = self.capture.as_ref().map_or(now, |c| c.fired_ms);
+        from.saturating_sub((self.config.pre_sec * 1000.0) as u64)
+    }
+
+    /// Frames from `from` on that the frame limit pushed out.
+    fn dropped_since(&self, from: u64) -> usize {
+        self.dropped
+            .iter()
+            .filter(|(t, _)| *t >= from)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Time trimming waits for `tick` and stops during a post-roll, so the
+    /// ring is also held to a frame count.
+    fn push(&mut self, frame: TimedFrame) {
+        if self.ring.len() >= MAX_RING_FRAMES {
+            if let Some(old) = self.ring.pop_front() {
+                match self.dropped.back_mut() {
+                    Some((t, n)) if *t == old.t_ms() => *n += 1,
+                    _ => self.dropped.push_back((old.t_ms(), 1)),
+                }
+            }
+        }
+        self.ring.push_back(frame);
+    }
+
+    /// Buffers a DMX frame; returns the reason when it fires a trigger.
+    pub fn push_dmx(&mut self, frame: &DmxFrame) -> Option<String> {
+        let now = self.now_ms();
+        let fired = match self.seen.get(&frame.port_address) {
+            Some(prev) => self
+                .config
+                .triggers
+                .iter()
+                .find_map(|t| t.on_frame(frame.port_address, &prev.values, &frame.values)),
+            None => None,
+        };
+        self.seen.insert(
+            frame.port_address,
+            SeenUniverse {
+                values: frame.values.clone(),
+                at_ms: now,
+                stopped: false,
+            },
+        );
+        if self.buffers(frame.port_address) {
+            self.push(TimedFrame::Dmx {
+                t_ms: now,
+                port_address: frame.port_address,
+                values: frame.values.clone(),
+            });
+        }
+        fired.filter(|reason| self.fire(reason.clone()))
+    }
+
+    pub fn push_nzs(&mut self, frame: &NzsFrame) {
+        if self.buffers(frame.port_address) {
+            let t_ms = self.now_ms();
+            self.push(TimedFrame::Nzs(NzsRecord {
+                t_ms,
+                frame: frame.clone(),
+            }));
+        }
+    }
+
+    /// Checks a received ArtTrigger or ArtCommand against the triggers.
+    pub fn on_packet(&mut self, packet: &trigger::Incoming) -> Option<String> {
+        let fired = self.config.triggers.iter().any(|t| match t {
+            PreRollTrigger::Packet { on } => on.matches(packet),
+            _ => false,
+        });
+        if !fired {
+            return None;
+        }
+        let reason = match packet {
+            trigger::Incoming::Trigger(t) => {
+                format!("ArtTrigger {} {}", trigger::key_name(t.key), t.sub_key)
+            }
+            trigger::Incoming::Command(c) => format!("ArtCommand {}", c.text),
+        };
+        self.fire(reason.clone()).then_some(reason)
+    }
+
+    /// Starts the post-roll; false while one is already running.
+    pub fn fire(&mut self, reason: String) -> bool {
+        if self.capture.is_some() {
+            return false;
+        }
+        let now = self.now_ms();
+        self.capture = Some(Capture {
+            reason,
+            fired_ms: now,
+            until_ms: now + (self.config.post_sec * 1000.0) as u64,
+        });
+        true
+    }
+
+    /// Runs the time-based parts: universe stop triggers, ending the
+    /// post-roll and trimming the ring. Returns the stop reason when one
+    /// fires, and whether a capture was completed.
+    pub fn tick(&mut self) -> (Option<String>, bool) {
+        let now = self.now_ms();
+        let mut fired = None;
+        for t in &self.config.triggers {
+            let PreRollTrigger::UniverseStopped {
+                port_address,
+                timeout_ms,
+            } = t
+            else {
+                continue;
+            };
+            if let Some(seen) = self.seen.get_mut(port_address) {
+                if !seen.stopped && now.saturating_sub(seen.at_ms) >= *timeout_ms {
+                    seen.stopped = true;
+                    fired.get_or_insert_with(|| {
+                        format!("{} stopped for {} ms", port_address, timeout_ms)
+                    });
+                }
+            }
+        }
+        let fired = fired.filter(|reason| self.fire(reason.clone()));
+        let completed = match &self.capture {
+            Some(capture) if now >= capture.until_ms => {
+                self.freeze();
+                true
+            }
+            _ => false,
+        };
+        if self.capture.is_none() {
+            let keep_from = self.window_start(now);
+            while self.ring.front().is_some_and(|f| f.t_ms() < keep_from) {
+                self.ring.pop_front();
+            }
+            while self.dropped.front().is_some_and(|(t, _)| *t < keep_from) {
+                self.dropped.pop_front();
+            }
+        }
+        (fired, completed)
+    }
+
+    /// Turns the ring into the capture; the ring keeps filling for the
+    /// next trigger.
+    fn freeze(&mut self) {
+        let keep_from = self.window_start(self.now_ms());
+        let dropped_frames = self.dropped_since(keep_from);
+        let Some(capture) = self.capture.take() else {
+            return;
+        };
+        let base = self
+            .ring
+            .iter()
+            .map(TimedFrame::t_ms)
+            .find(|t| *t >= keep_from)
+            .unwrap_or(capture.fired_ms);
+        let mut frames = Vec::new();
+        let mut nzs = Vec::new();
+        for frame in &self.ring {
+            if frame.t_ms() < keep_from {
+                continue;
+            }
+            match frame {
+                TimedFrame::Nzs(rec) => nzs.push(NzsRecord {
+                    t_ms: rec.t_ms - base,
+                    frame: rec.frame.clone(),
+                }),
+                frame => frames.extend(frame.play_frame().map(|mut f| {
+                    f.t_ms -= base;
+                    f
+                })),
+            }
+        }
+        self.captures += 1;
+        self.last_capture = Some(PreRollCapture {
+            reason: capture.reason,
+            trigger_ms: capture.fired_ms.saturating_sub(base),
+            dropped_frames,
+            data: recording::record_data_from_frames(&frames, nzs),
+        });
+    }
+
+    pub fn last_capture(&self) -> Option<&PreRollCapture> {
+        self.last_capture.as_ref()
+    }
+
+    pub fn status(&self) -> PreRollStatus {
+        let buffered_ms = match (self.ring.front(), self.ring.back()) {
+            (Some(first), Some(last)) => last.t_ms() - first.t_ms(),
+            _ => 0,
+        };
+        PreRollStatus {
+            config: self.config.clone(),
+            buffered_ms,
+            buffered_frames: self.ring.len(),
+            dropped_frames: self.dropped_since(self.window_start(self.now_ms())),
+            capturing: self.capture.as_ref().map(|c| c.reason.clone()),
+            captures: self.captures,
+            last_capture: self.last_capture.as_ref().map(PreRollCapture::info),
+        }
+    }
+}
@@ -0,0 +1,690 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use tokio::sync::broadcast;
+
+use crate::artnet::{self, PortAddress};
+use crate::remote;
+
+const RDM_START_CODE: u8 = 0xcc;
+const RDM_SUB_START_CODE: u8 = 0x01;
+const RDM_HEADER_LEN: usize = 24; // Start code through PDL
+const ART_RDM_VERSION: u8 = 0x01; // RDM Standard V1.0
+const TOD_FULL: u8 = 0x00;
+const TOD_NAK: u8 = 0xff;
+const ATC_FLUSH: u8 = 0x01;
+const AR_PROCESS: u8 = 0x00;
+const MAX_TOD_ADDRESSES: usize = 32;
+const RDM_RESEND: Duration = Duration::from_millis(700);
+
+const CC_GET: u8 = 0x20;
+const CC_SET: u8 = 0x30;
+
+const PID_DEVICE_INFO: u16 = 0x0060;
+const PID_DEVICE_LABEL: u16 = 0x0082;
+const PID_DMX_PERSONALITY: u16 = 0x00e0;
+const PID_DMX_START_ADDRESS: u16 = 0x00f0;
+const PID_SENSOR_VALUE: u16 = 0x0201;
+const PID_IDENTIFY_DEVICE: u16 = 0x1000;
+
+static TRANSACTION: AtomicU8 = AtomicU8::new(0);
+
+/// RDM UID, written "mmmm:dddddddd" (manufacturer ID : device ID) in hex.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Uid {
+    pub manufacturer: u16,
+    pub device: u32,
+}
+
+/// Source UID of requests; 0x7ff0 is a prototype manufacturer ID.
+pub const CONTROLLER_UID: Uid = Uid {
+    manufacturer: 0x7ff0,
+    device: 0x4c41_4201,
+};
+
+impl Uid {
+    fn from_bytes(b: &[u8]) -> Self {
+        Self {
+            manufacturer: u16::from_be_bytes([b[0], b[1]]),
+            device: u32::from_be_bytes([b[2], b[3], b[4], b[5]]),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; 6] {
+        let mut out = [0u8; 6];
+        out[..2].copy_from_slice(&self.manufacturer.to_be_bytes());
+        out[2..].copy_from_slice(&self.device.to_be_bytes());
+        out
+    }
+}
+
+impl fmt::Display for Uid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04x}:{:08x}", self.manufacturer, self.device)
+    }
+}
+
+impl FromStr for Uid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (m, d) = s
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid RDM UID {:?}, expected mmmm:dddddddd", s))?;
+        Ok(Self {
+            manufacturer: u16::from_str_radix(m, 16)
+                .map_err(|_| anyhow!("Invalid manufacturer in RDM UID {:?}", s))?,
+            device: u32::from_str_radix(d, 16)
+                .map_err(|_| anyhow!("Invalid device in RDM UID {:?}", s))?,
+        })
+    }
+}
+
+impl TryFrom<String> for Uid {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Uid> for String {
+    fn from(uid: Uid) -> Self {
+        uid.to_string()
+    }
+}
+
+fn art_header(pkt: &mut Vec<u8>, opcode: u16) {
+    pkt.extend_from_slice(artnet::ARTNET_ID);
+    pkt.extend_from_slice(&opcode.to_le_bytes());
+    pkt.extend_from_slice(&artnet::PROT_VER.to_be_bytes());
+}
+
+/// ArtTodRequest for up to 32 universes sharing one Net.
+pub fn encode_tod_request(net: u8, sub_unis: &[u8]) -> Vec<u8> {
+    let count = sub_unis.len().min(MAX_TOD_ADDRESSES);
+    let mut pkt = Vec::with_capacity(24 + count);
+    art_header(&mut pkt, artnet::OP_TOD_REQUEST);
+    pkt.extend_from_slice(&[0; 9]); // Filler1-2, Spare1-7
+    pkt.push(net);
+    pkt.push(TOD_FULL); // Command
+    pkt.push(count as u8); // AdCount
+    pkt.extend_from_slice(&sub_unis[..count]);
+    pkt
+}
+
+/// ArtTodControl AtcFlush: the node runs a full discovery on the port and
+/// answers with ArtTodData.
+pub fn encode_tod_flush(addr: PortAddress) -> Vec<u8> {
+    let mut pkt = Vec::with_capacity(24);
+    art_header(&mut pkt, artnet::OP_TOD_CONTROL);
+    pkt.extend_from_slice(&[0; 9]); // Filler1-2, Spare1-7
+    pkt.push(addr.net());
+    pkt.push(ATC_FLUSH);
+    pkt.push(addr.sub_uni());
+    pkt
+}
+
+/// One ArtTodData block.
+pub struct TodData {
+    pub port: u8, // physical port 1..=4
+    pub bind_index: u8,
+    pub port_address: PortAddress,
+    pub nak: bool,
+    pub uid_total: u16,
+    pub uids: Vec<Uid>,
+}
+
+pub fn parse_tod_data(buf: &[u8]) -> Result<TodData> {
+    if buf.len() < 28 || artnet::opcode(buf) != Some(artnet::OP_TOD_DATA) {
+        return Err(anyhow!("Not an ArtTodData"));
+    }
+    let count = buf[27] as usize;
+    let uids = buf[28..]
+        .chunks_exact(6)
+        .take(count)
+        .map(Uid::from_bytes)
+        .collect();
+    Ok(TodData {
+        port: buf[13],
+        bind_index: buf[20],
+        port_address: PortAddress::from_wire(buf[23], buf[21] & 0x7f)?,
+        nak: buf[22] == TOD_NAK,
+        uid_total: u16::from_be_bytes([buf[24], buf[25]]),
+        uids,
+    })
+}
+
+/// ArtRdm carrying `rdm`, an RDM packet without its start code.
+pub fn encode_art_rdm(addr: PortAddress, rdm: &[u8]) -> Vec<u8> {
+    let mut pkt = Vec::with_capacity(24 + rdm.len());
+    art_header(&mut pkt, artnet::OP_RDM);
+    pkt.push(ART_RDM_VERSION);
+    pkt.extend_from_slice(&[0; 8]); // Filler2, Spare1-5, FifoAvail, FifoMax
+    pkt.push(addr.net());
+    pkt.push(AR_PROCESS);
+    pkt.push(addr.sub_uni());
+    pkt.extend_from_slice(rdm);
+    pkt
+}
+
+/// Port-Address and RDM packet (without start code) of an ArtRdm.
+pub fn parse_art_rdm(buf: &[u8]) -> Result<(PortAddress, &[u8])> {
+    if buf.len() < 24 || artnet::opcode(buf) != Some(artnet::OP_RDM) {
+        return Err(anyhow!("Not an ArtRdm"));
+    }
+    Ok((PortAddress::from_wire(buf[23], buf[21] & 0x7f)?, &buf[24..]))
+}
+
+struct RdmRequest {
+    dest: Uid,
+    tn: u8,
+    cc: u8,
+    pid: u16,
+    data: Vec<u8>,
+}
+
+/// RDM message without the start code, checksum included.
+fn encode_rdm(req: &RdmRequest) -> Vec<u8> {
+    let len = RDM_HEADER_LEN + req.data.len();
+    let mut msg = Vec::with_capacity(len + 2);
+    msg.push(RDM_START_CODE);
+    msg.push(RDM_SUB_START_CODE);
+    msg.push(len as u8);
+    msg.extend_from_slice(&req.dest.to_bytes());
+    msg.extend_from_slice(&CONTROLLER_UID.to_bytes());
+    msg.push(req.tn);
+    msg.push(1); // Port ID
+    msg.push(0); // Message count
+    msg.extend_from_slice(&0u16.to_be_bytes()); // Root device
+    msg.push(req.cc);
+    msg.extend_from_slice(&req.pid.to_be_bytes());
+    msg.push(req.data.len() as u8);
+    msg.extend_from_slice(&req.data);
+    let checksum = msg.iter().fold(0u16, |acc, b| acc.wrapping_add(*b as u16));
+    msg.extend_from_slice(&checksum.to_be_bytes());
+    msg.remove(0);
+    msg
+}
+
+struct RdmResponse {
+    src: Uid,
+    tn: u8,
+    response_type: u8,
+    cc: u8,
+    pid: u16,
+    data: Vec<u8>,
+}
+
+fn parse_rdm(msg: &[u8]) -> Result<RdmResponse> {
+    // Offsets are one less than in the RDM standard: no start code.
+    if msg.len() < RDM_HEADER_LEN + 1 || msg[0] != RDM_SUB_START_CODE {
+        return Err(anyhow!("Not an RDM message"));
+    }
+    let len = msg[1] as usize;
+    if len < RDM_HEADER_LEN || msg.len() < len + 1 {
+        return Err(anyhow!("Truncated RDM message"));
+    }
+    let sum = msg[..len - 1]
+        .iter()
+        .fold(RDM_START_CODE as u16, |acc, b| acc.wrapping_add(*b as u16));
+    if sum != u16::from_be_bytes([msg[len - 1], msg[len]]) {
+        return Err(anyhow!("RDM checksum mismatch"));
+    }
+    let pdl = msg[22] as usize;
+    Ok(RdmResponse {
+        src: Uid::from_bytes(&msg[8..14]),
+        tn: msg[14],
+        response_type: msg[15],
+        cc: msg[19],
+        pid: u16::from_be_bytes([msg[20], msg[21]]),
+        data: msg[23..(23 + pdl).min(len - 1)].to_vec(),
+    })
+}
+
+/// Parameters that can be read.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RdmParam {
+    DeviceInfo,
+    DeviceLabel,
+    DmxStartAddress,
+    DmxPersonality,
+    IdentifyDevice,
+    /// Sensor number.
+    SensorValue(u8),
+}
+
+/// Parameters that can be written.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RdmSetValue {
+    DeviceLabel(String), // up to 32 characters
+    DmxStartAddress(u16),
+    DmxPersonality(u8),
+    IdentifyDevice(bool),
+    /// Resets the recorded values of a sensor (0xff for all).
+    SensorValue(u8),
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    pub protocol_version: u16,
+    pub model_id: u16,
+    pub product_category: u16,
+    pub software_version: u32,
+    pub footprint: u16,
+    pub personality: u8,
+    pub personality_count: u8,
+    pub start_address: u16,
+    pub sub_device_count: u16,
+    pub sensor_count: u8,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorReading {
+    pub sensor: u8,
+    pub value: i16,
+    pub lowest: i16,
+    pub highest: i16,
+    pub recorded: i16,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RdmValue {
+    DeviceInfo(DeviceInfo),
+    DeviceLabel(String),
+    DmxStartAddress(u16),
+    DmxPersonality { current: u8, count: u8 },
+    IdentifyDevice(bool),
+    SensorValue(SensorReading),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RdmStatus {
+    Ack,
+    AckTimer,
+    Nack,
+    AckOverflow,
+}
+
+/// Parsed response to a GET or SET.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RdmReply {
+    pub status: RdmStatus,
+    /// With `AckTimer`: when to ask again.
+    pub ack_timer_ms: Option<u32>,
+    pub nack_reason: Option<String>,
+    pub value: Option<RdmValue>,
+}
+
+fn nack_reason(code: u16) -> String {
+    let name = match code {
+        0x0000 => "unknown PID",
+        0x0001 => "format error",
+        0x0002 => "hardware fault",
+        0x0003 => "proxy reject",
+        0x0004 => "write protect",
+        0x0005 => "unsupported command class",
+        0x0006 => "data out of range",
+        0x0007 => "buffer full",
+        0x0008 => "packet size unsupported",
+        0x0009 => "sub-device out of range",
+        0x000a => "proxy buffer full",
+        _ => return format!("reason 0x{:04x}", code),
+    };
+    name.to_string()
+}
+
+fn be16(d: &[u8], at: usize) -> u16 {
+    u16::from_be_bytes([d[at], d[at + 1]])
+}
+
+fn parse_value(pid: u16, d: &[u8]) -> Result<Option<RdmValue>> {
+    let short = || anyhow!("RDM response for PID 0x{:04x} too short", pid);
+    let value = match pid {
+        PID_DEVICE_INFO => {
+            if d.len() < 19 {
+                return Err(short());
+            }
+            RdmValue::DeviceInfo(DeviceInfo {
+                protocol_version: be16(d, 0),
+                model_id: be16(d, 2),
+                product_category: be16(d, 4),
+                software_version: u32::from_be_bytes([d[6], d[7], d[8], d[9]]),
+                footprint: be16(d, 10),
+                personality: d[12],
+                personality_count: d[13],
+                start_address: be16(d, 14),
+                sub_device_count: be16(d, 16),
+                sensor_count: d[18],
+            })
+        }
+        PID_DEVICE_LABEL => RdmValue::DeviceLabel(
+            String::from_utf8_lossy(d)
+                .trim_end_matches('\0')
+                .to_string(),
+        ),
+        PID_DMX_START_ADDRESS if d.len() >= 2 => RdmValue::DmxStartAddress(be16(d, 0)),
+        PID_DMX_PERSONALITY if d.len() >= 2 => RdmValue::DmxPersonality {
+            current: d[0],
+            count: d[1],
+        },
+        PID_IDENTIFY_DEVICE if !d.is_empty() => RdmValue::IdentifyDevice(d[0] != 0),
+        PID_SENSOR_VALUE if d.len() >= 9 => {
+            let i = |at: usize| be16(d, at) as i16;
+            RdmValue::SensorValue(SensorReading {
+                sensor: d[0],
+                value: i(1),
+                lowest: i(3),
+                highest: i(5),
+                recorded: i(7),
+            })
+        }
+        // SET acknowledgements carry no data.
+        _ if d.is_empty() => return Ok(None),
+        _ => return Err(short()),
+    };
+    Ok(Some(value))
+}
+
+fn reply_from(resp: &RdmResponse) -> Result<RdmReply> {
+    let mut reply = RdmReply {
+        status: RdmStatus::Ack,
+        ack_timer_ms: None,
+        nack_reason: None,
+        value: None,
+    };
+    match resp.response_type {
+        // SET acknowledgements carry no parameter data.
+        0x00 if resp.cc == CC_SET + 1 => {}
+        0x00 => reply.value = parse_value(resp.pid, &resp.data)?,
+        0x01 => {
+            reply.status = RdmStatus::AckTimer;
+            if resp.data.len() >= 2 {
+                reply.ack_timer_ms = Some(be16(&resp.data, 0) as u32 * 100);
+            }
+        }
+        0x02 => {
+            reply.status = RdmStatus::Nack;
+            if resp.data.len() >= 2 {
+                reply.nack_reason = Some(nack_reason(be16(&resp.data, 0)));
+            }
+        }
+        0x03 => {
+            reply.status = RdmStatus::AckOverflow;
+            reply.value = parse_value(resp.pid, &resp.data).unwrap_or(None);
+        }
+        other => return Err(anyhow!("Unknown RDM response type 0x{:02x}", other)),
+    }
+    Ok(reply)
+}
+
+fn get_request(dest: Uid, param: RdmParam) -> RdmRequest {
+    let (pid, data) = match param {
+        RdmParam::DeviceInfo => (PID_DEVICE_INFO, Vec::new()),
+        RdmParam::DeviceLabel => (PID_DEVICE_LABEL, Vec::new()),
+        RdmParam::DmxStartAddress => (PID_DMX_START_ADDRESS, Vec::new()),
+        RdmParam::DmxPersonality => (PID_DMX_PERSONALITY, Vec::new()),
+        RdmParam::IdentifyDevice => (PID_IDENTIFY_DEVICE, Vec::new()),
+        RdmParam::SensorValue(sensor) => (PID_SENSOR_VALUE, vec![sensor]),
+    };
+    RdmRequest {
+        dest,
+        tn: TRANSACTION.fetch_add(1, Ordering::Relaxed),
+        cc: CC_GET,
+        pid,
+        data,
+    }
+}
+
+fn set_request(dest: Uid, value: &RdmSetValue) -> Result<RdmRequest> {
+    let (pid, data) = match value {
+        RdmSetValue::DeviceLabel(label) => {
+            if label.len() > 32 {
+                return Err(anyhow!("Device label is longer than 32 bytes"));
+            }
+            (PID_DEVICE_LABEL, label.as_bytes().to_vec())
+        }
+        RdmSetValue::DmxStartAddress(addr) => {
+            if !(1..=512).contains(addr) {
+                return Err(anyhow!("DMX start address {} out of range 1..=512", addr));
+            }
+            (PID_DMX_START_ADDRESS, addr.to_be_bytes().to_vec())
+        }
+        RdmSetValue::DmxPersonality(p) => (PID_DMX_PERSONALITY, vec![*p]),
+        RdmSetValue::IdentifyDevice(on) => (PID_IDENTIFY_DEVICE, vec![*on as u8]),
+        RdmSetValue::SensorValue(sensor) => (PID_SENSOR_VALUE, vec![*sensor]),
+    };
+    Ok(RdmRequest {
+        dest,
+        tn: TRANSACTION.fetch_add(1, Ordering::Relaxed),
+        cc: CC_SET,
+        pid,
+        data,
+    })
+}
+
+/// Devices found on one node port.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodEntry {
+    pub node_ip: String,
+    pub bind_index: u8,
+    pub port: u8,
+    pub port_address: PortAddress,
+    pub uids: Vec<Uid>,
+    /// False when the node sent fewer UIDs than it announced, or a NAK.
+    pub complete: bool,
+}
+
+#[derive(Default)]
+struct TodAccumulator {
+    entry: Option<TodEntry>,
+    total: u16,
+    nak: bool,
+}
+
+/// Reads the table of devices of each Port-Address from a node, with a
+/// full RDM discovery first when `flush` is set.
+pub async fn read_tod(
+    node_ip: Ipv4Addr,
+    port: u16,
+    addresses: &[PortAddress],
+    flush: bool,
+    relay: &broadcast::Sender<(SocketAddr, Vec<u8>)>,
+    timeout: Duration,
+) -> Result<Vec<TodEntry>> {
+    let dest = SocketAddr::new(IpAddr::V4(node_ip), port);
+    let mut found: BTreeMap<PortAddress, TodAccumulator> = addresses
+        .iter()
+        .map(|a| (*a, TodAccumulator::default()))
+        .collect();
+
+    let mut by_net: BTreeMap<u8, Vec<u8>> = BTreeMap::new();
+    for addr in addresses {
+        by_net.entry(addr.net()).or_default().push(addr.sub_uni());
+    }
+    let mut requests: Vec<(Vec<u8>, Vec<PortAddress>)> = Vec::new();
+    if flush {
+        for addr in addresses {
+            requests.push((encode_tod_flush(*addr), vec![*addr]));
+        }
+    } else {
+        for (net, subs) in by_net {
+            for chunk in subs.chunks(MAX_TOD_ADDRESSES) {
+                let wanted = chunk
+                    .iter()
+                    .filter_map(|s| PortAddress::from_wire(*s, net).ok())
+                    .collect();
+                requests.push((encode_tod_request(net, chunk), wanted));
+            }
+        }
+    }
+
+    for (pkt, wanted) in requests {
+        remote::exchange(
+            dest,
+            &pkt,
+            Some(relay.subscribe()),
+            timeout,
+            // A repeated flush would restart discovery on the node.
+            (!flush).then_some(RDM_RESEND),
+            |src, data| {
+                if src.ip() != IpAddr::V4(node_ip) {
+                    return false;
+                }
+                let Ok(tod) = parse_tod_data(data) else {
+                    return false;
+                };
+                if let Some(acc) = found.get_mut(&tod.port_address) {
+                    let entry = acc.entry.get_or_insert_with(|| TodEntry {
+                        node_ip: node_ip.to_string(),
+                        bind_index: tod.bind_index,
+                        port: tod.port,
+                        port_address: tod.port_address,
+                        uids: Vec::new(),
+                        complete: false,
+                    });
+                    if tod.nak {
+                        acc.nak = true;
+                    } else {
+                        acc.total = tod.uid_total;
+                        for uid in tod.uids {
+                            if !entry.uids.contains(&uid) {
+                                entry.uids.push(uid);
+                            }
+                        }
+                        entry.complete = entry.uids.len() >= acc.total as usize;
+                    }
+                }
+                wanted.iter().all(|a| {
+                    found.get(a).is_some_and(|acc| {
+                        acc.nak || acc.entry.as_ref().is_some_and(|e| e.complete)
+                    })
+                })
+            },
+        )
+        .await?;
+    }
+
+    Ok(found
+        .into_values()
+        .filter_map(|acc| acc.entry)
+        .map(|mut e| {
+            e.uids.sort();
+            e
+        })
+        .collect())
+}
+
+/// Sends one RDM request through ArtRdm and waits for the matching response.
+async fn transact(
+    node_ip: Ipv4Addr,
+    port: u16,
+    addr: PortAddress,
+    req: RdmRequest,
+    relay: &broadcast::Sender<(SocketAddr, Vec<u8>)>,
+    timeout: Duration,
+) -> Result<RdmReply> {
+    let pkt = encode_art_rdm(addr, &encode_rdm(&req));
+    let mut response = None;
+    remote::exchange(
+        SocketAddr::new(IpAddr::V4(node_ip), port),
+        &pkt,
+        Some(relay.subscribe()),
+        timeout,
+        Some(RDM_RESEND),
+        |src, data| {
+            if src.ip() != IpAddr::V4(node_ip) {
+                return false;
+            }
+            let Ok((from_addr, msg)) = parse_art_rdm(data) else {
+                return false;
+            };
+            let Ok(resp) = parse_rdm(msg) else {
+                return false;
+            };
+            let matches = from_addr == addr
+                && resp.src == req.dest
+                && resp.tn == req.tn
+                && resp.pid == req.pid
+                && resp.cc == req.cc + 1;
+            if matches {
+                response = Some(resp);
+            }
+            matches
+        },
+    )
+    .await?;
+    let resp = response.ok_or_else(|| anyhow!("No RDM response from {}", req.dest))?;
+    reply_from(&resp)
+}
+
+pub async fn get(
+    node_ip: Ipv4Addr,
+    port: u16,
+    addr: PortAddress,
+    uid: Uid,
+    param: RdmParam,
+    relay: &broadcast::Sender<(SocketAddr, Vec<u8>)>,
+    timeout: Duration,
+) -> Result<RdmReply> {
+    transact(node_ip, port, addr, get_request(uid, param), relay, timeout).await
+}
+
+pub async fn set(
+    node_ip: Ipv4Addr,
+    port: u16,
+    addr: PortAddress,
+    uid: Uid,
+    value: &RdmSetValue,
+    relay: &broadcast::Sender<(SocketAddr, Vec<u8>)>,
+    timeout: Duration,
+) -> Result<RdmReply> {
+    transact(
+        node_ip,
+        port,
+        addr,
+        set_request(uid, value)?,
+        relay,
+        timeout,
+    )
+    .await
+}
+
+/// Devices per node port from the latest TOD of each.
+#[derive(Default)]
+pub struct TodTable {
+    entries: Vec<TodEntry>,
+}
+
+impl TodTable {
+    pub fn list(&self) -> Vec<TodEntry> {
+        self.entries.clone()
+    }
+
+    pub fn update(&mut self, entries: Vec<TodEntry>) {
+        for entry in entries {
+            self.entries
+                .retain(|e| !(e.node_ip == entry.node_ip && e.port_address == entry.port_address));
+            self.entries.push(entry);
+        }
+        self.entries
+            .sort_by(|a, b| (&a.node_ip, a.port_address).cmp(&(&b.node_ip, b.port_address)));
+    }
+}
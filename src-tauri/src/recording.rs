@@ -0,0 +1,705 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::artnet::{NzsFrame, PortAddress};
+use crate::state::{normalize_channels, NzsRecord, PlayFrame, RecordData, RecordTrack};
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct WavRecordingData {
+    pub timestamps: Vec<u64>,
+    pub channels: Vec<Vec<u8>>,
+    #[serde(default)]
+    pub dmx_channels: Option<Vec<u16>>,
+    /// Universe the recording was taken from, when known.
+    #[serde(default)]
+    pub port_address: Option<PortAddress>,
+}
+
+pub fn write_jsonl(path: &str, data: &RecordData) -> Result<()> {
+    use std::io::Write;
+
+    let mut file = std::fs::File::create(path)?;
+    // Each line's values follow the channel list of its universe in
+    // `tracks`; `channels` is only the union of them.
+    let tracks: Vec<serde_json::Value> = data
+        .tracks
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "net": t.port_address.net(),
+                "subnet": t.port_address.subnet(),
+                "universe": t.port_address.universe(),
+                "channels": t.channel_numbers(),
+            })
+        })
+        .collect();
+    let header = serde_json::json!({
+        "format": "artnet-jsonl",
+        "version": 2,
+        "channels": data.channel_numbers(),
+        "tracks": tracks,
+    });
+    writeln!(file, "{}", header)?;
+
+    let order = data.frames_in_order();
+    let base = order
+        .first()
+        .map(|(t, i)| data.tracks[*t].timestamps[*i])
+        .unwrap_or(0);
+    let mut nzs = data.nzs.iter().peekable();
+
+    for (track, idx) in order {
+        let track = &data.tracks[track];
+        let timestamp = track.timestamps[idx].saturating_sub(base);
+        while let Some(rec) = nzs.next_if(|r| r.t_ms.saturating_sub(base) <= timestamp) {
+            writeln!(file, "{}", nzs_line(rec, base))?;
+        }
+        let addr = track.port_address;
+        let values = track.frame_values(idx);
+        let line = serde_json::json!({
+            "t_ms": timestamp,
+            "net": addr.net(),
+            "subnet": addr.subnet(),
+            "universe": addr.universe(),
+            "length": values.len(),
+            "values": values,
+        });
+        writeln!(file, "{}", line)?;
+    }
+    for rec in nzs {
+        writeln!(file, "{}", nzs_line(rec, base))?;
+    }
+
+    Ok(())
+}
+
+/// JSONL line of an alternate start code frame; readers that only know DMX
+/// lines skip it by its `start_code`.
+fn nzs_line(rec: &NzsRecord, base: u64) -> serde_json::Value {
+    let addr = rec.frame.port_address;
+    serde_json::json!({
+        "t_ms": rec.t_ms.saturating_sub(base),
+        "net": addr.net(),
+        "subnet": addr.subnet(),
+        "universe": addr.universe(),
+        "start_code": rec.frame.start_code,
+        "length": rec.frame.values.len(),
+        "values": rec.frame.values,
+    })
+}
+
+/// A recorded frame with its time, as streamed from or to a recording file.
+pub enum TimedFrame {
+    Dmx {
+        t_ms: u64,
+        port_address: PortAddress,
+        values: Vec<u8>,
+    },
+    Nzs(NzsRecord),
+}
+
+impl TimedFrame {
+    pub fn t_ms(&self) -> u64 {
+        match self {
+            Self::Dmx { t_ms, .. } => *t_ms,
+            Self::Nzs(rec) => rec.t_ms,
+        }
+    }
+
+    /// DMX frame padded to a full universe, for playback.
+    pub fn play_frame(&self) -> Option<PlayFrame> {
+        let Self::Dmx {
+            t_ms,
+            port_address,
+            values,
+        } = self
+        else {
+            return None;
+        };
+        let mut data = [0u8; 512];
+        let n = values.len().min(512);
+        data[..n].copy_from_slice(&values[..n]);
+        Some(PlayFrame {
+            t_ms: *t_ms,
+            port_address: *port_address,
+            data,
+        })
+    }
+
+    /// JSONL line in the format of the live recorder.
+    pub fn jsonl_line(&self) -> serde_json::Value {
+        match self {
+            Self::Dmx {
+                t_ms,
+                port_address,
+                values,
+            } => serde_json::json!({
+                "t_ms": t_ms,
+                "net": port_address.net(),
+                "subnet": port_address.subnet(),
+                "universe": port_address.universe(),
+                "length": values.len(),
+                "values": values,
+            }),
+            Self::Nzs(rec) => nzs_line(rec, 0),
+        }
+    }
+}
+
+/// Channel lists of a JSONL header: one for every line (version 1) and one
+/// per universe (version 2).
+#[derive(Clone, Default)]
+struct JsonlLayout {
+    channels: Option<Vec<usize>>,
+    tracks: HashMap<PortAddress, Vec<usize>>,
+}
+
+impl JsonlLayout {
+    /// Zero-based channels the values of a line of `addr` follow, if listed.
+    fn channels(&self, addr: PortAddress) -> Option<&Vec<usize>> {
+        self.tracks.get(&addr).or(self.channels.as_ref())
+    }
+}
+
+/// Streams a JSONL recording, spreading each line's values over the channels
+/// its header lists for that universe. Lines of headerless files are taken
+/// as whole frames.
+pub fn read_jsonl(path: &str) -> Result<impl Iterator<Item = Result<TimedFrame>>> {
+    Ok(read_jsonl_layout(path)?.1)
+}
+
+fn read_jsonl_layout(
+    path: &str,
+) -> Result<(JsonlLayout, impl Iterator<Item = Result<TimedFrame>>)> {
+    use std::io::{BufRead, BufReader};
+
+    let file = std::fs::File::open(path)?;
+    let mut lines = BufReader::new(file).lines().peekable();
+    let header = match lines.peek() {
+        Some(Ok(line)) => serde_json::from_str::<JsonlHeader>(line)
+            .ok()
+            .filter(|h| h.format.is_some()),
+        _ => None,
+    };
+    let mut layout = JsonlLayout::default();
+    if let Some(header) = header {
+        if !header.channels.is_empty() {
+            layout.channels = Some(
+                header
+                    .channels
+                    .iter()
+                    .map(|n| n.saturating_sub(1) as usize)
+                    .collect(),
+            );
+        } else if let Some(ch) = header.channel {
+            layout.channels = Some(vec![ch.saturating_sub(1) as usize]);
+        }
+        for track in &header.tracks {
+            layout
+                .tracks
+                .insert(track.port_address()?, track.channel_indices());
+        }
+        lines.next();
+    }
+    let lookup = layout.clone();
+    let frames = lines
+        .filter(|line| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(move |line| -> Result<TimedFrame> {
+            let rec: JsonlRecord = serde_json::from_str(&line?)?;
+            let port_address = PortAddress::from_parts(rec.net, rec.subnet, rec.universe)?;
+            if rec.start_code != 0 {
+                return Ok(TimedFrame::Nzs(NzsRecord {
+                    t_ms: rec.t_ms,
+                    frame: NzsFrame {
+                        port_address,
+                        start_code: rec.start_code,
+                        length: rec.values.len() as u16,
+                        sequence: rec.sequence,
+                        values: rec.values,
+                    },
+                }));
+            }
+            let values = match lookup.channels(port_address) {
+                Some(list) => spread(list, &rec.values),
+                None => rec.values,
+            };
+            Ok(TimedFrame::Dmx {
+                t_ms: rec.t_ms,
+                port_address,
+                values,
+            })
+        });
+    Ok((layout, frames))
+}
+
+/// Places `values` at the zero-based `channels`, up to the highest one.
+fn spread(channels: &[usize], values: &[u8]) -> Vec<u8> {
+    let mut dmx = vec![0u8; 512];
+    let mut len = 0;
+    for (value, ch) in values.iter().zip(channels) {
+        if *ch < 512 {
+            dmx[*ch] = *value;
+            len = len.max(ch + 1);
+        }
+    }
+    dmx.truncate(len);
+    dmx
+}
+
+/// Writes one universe of a recording as WAV, one audio channel per DMX
+/// channel. `port_address` may be left out when there is a single track.
+pub fn write_wav(path: &str, data: &RecordData, port_address: Option<PortAddress>) -> Result<()> {
+    let track = data.single_track(port_address)?;
+    let frames = track.frame_count();
+    if frames == 0 {
+        return Err(anyhow!("No recorded frames"));
+    }
+    let base = track.timestamps.first().copied().unwrap_or(0);
+    let duration = track.duration_ms().saturating_sub(base).max(1);
+    let sample_rate = ((frames as u64 * 1000) / duration).max(1) as u32;
+    let timestamps: Vec<u64> = track
+        .timestamps
+        .iter()
+        .map(|t| t.saturating_sub(base))
+        .collect();
+    let wav = WavRecordingData {
+        timestamps,
+        channels: track.values.clone(),
+        dmx_channels: Some(track.channel_numbers()),
+        port_address: Some(track.port_address),
+    };
+    save_wav(path, sample_rate, &wav)
+}
+
+#[derive(Deserialize, Default)]
+struct JsonlHeader {
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    channels: Vec<u16>,
+    #[serde(default)]
+    channel: Option<u16>,
+    #[serde(default)]
+    tracks: Vec<JsonlTrack>,
+}
+
+/// Channels of one universe in a version 2 header.
+#[derive(Deserialize)]
+pub struct JsonlTrack {
+    #[serde(default)]
+    net: u8,
+    #[serde(default)]
+    subnet: u8,
+    #[serde(default)]
+    universe: u8,
+    channels: Vec<u16>,
+}
+
+impl JsonlTrack {
+    pub fn port_address(&self) -> Result<PortAddress> {
+        PortAddress::from_parts(self.net, self.subnet, self.universe)
+    }
+
+    /// Zero-based channel indices.
+    pub fn channel_indices(&self) -> Vec<usize> {
+        self.channels
+            .iter()
+            .map(|n| n.saturating_sub(1) as usize)
+            .filter(|n| *n < 512)
+            .collect()
+    }
+}
+
+/// Per-universe channel lists of a JSONL header line (empty for version 1).
+pub fn jsonl_header_tracks(header: &serde_json::Value) -> Vec<JsonlTrack> {
+    header
+        .get("tracks")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+struct JsonlRecord {
+    t_ms: u64,
+    #[serde(default)]
+    net: u8,
+    #[serde(default)]
+    subnet: u8,
+    #[serde(default)]
+    universe: u8,
+    #[serde(default)]
+    start_code: u8,
+    #[serde(default)]
+    sequence: u8,
+    values: Vec<u8>,
+}
+
+/// Loads a whole JSONL recording, each universe keeping the channels its
+/// header lists (all of them in headerless files).
+pub fn load_jsonl(path: &str) -> Result<RecordData> {
+    let (layout, frames) = read_jsonl_layout(path)?;
+    let mut data = RecordData {
+        tracks: Vec::new(),
+        nzs: Vec::new(),
+    };
+    for frame in frames {
+        match frame? {
+            TimedFrame::Dmx {
+                t_ms,
+                port_address,
+                values,
+            } => {
+                let channels = match layout.channels(port_address) {
+                    Some(list) => normalize_channels(list.clone()),
+                    None => (0..512).collect(),
+                };
+                data.track_mut(port_address, &channels).push(t_ms, &values);
+            }
+            TimedFrame::Nzs(rec) => data.nzs.push(rec),
+        }
+    }
+    Ok(data)
+}
+
+pub fn record_data_from_wav(data: WavRecordingData) -> RecordData {
+    let channels = data.channels.len();
+    let dmx_channels = data
+        .dmx_channels
+        .unwrap_or_else(|| (1..=channels as u16).collect());
+    RecordData {
+        tracks: vec![RecordTrack {
+            port_address: data.port_address.unwrap_or_default(),
+            timestamps: data.timestamps,
+            channels: dmx_channels
+                .into_iter()
+                .map(|ch| ch.saturating_sub(1) as usize)
+                .filter(|ch| *ch < 512)
+                .take(channels)
+                .collect(),
+            values: data.channels,
+        }],
+        nzs: Vec::new(),
+    }
+}
+
+/// Builds a recording with a track per universe out of full frames, each
+/// track keeping the channels that are ever non-zero in it.
+pub fn record_data_from_frames(frames: &[PlayFrame], nzs: Vec<NzsRecord>) -> RecordData {
+    let mut used: HashMap<PortAddress, [bool; 512]> = HashMap::new();
+    for f in frames {
+        let used = used.entry(f.port_address).or_insert([false; 512]);
+        for (ch, v) in f.data.iter().enumerate() {
+            used[ch] |= *v != 0;
+        }
+    }
+    let mut data = RecordData {
+        tracks: Vec::new(),
+        nzs,
+    };
+    for f in frames {
+        let used = &used[&f.port_address];
+        let mut channels: Vec<usize> = (0..512).filter(|ch| used[*ch]).collect();
+        if channels.is_empty() {
+            channels.push(0);
+        }
+        data.track_mut(f.port_address, &channels)
+            .push(f.t_ms, &f.data);
+    }
+    data
+}
+
+/// Universes of a recording, by number of frames.
+pub fn universes(data: &RecordData) -> Vec<PortAddress> {
+    let mut tracks: Vec<&RecordTrack> = data.tracks.iter().collect();
+    tracks.sort_by(|a, b| {
+        b.frame_count()
+            .cmp(&a.frame_count())
+            .then(a.port_address.cmp(&b.port_address))
+    });
+    tracks.into_iter().map(|t| t.port_address).collect()
+}
+
+/// One universe of a capture, show or JSONL file in the WAV recording shape,
+/// for preview.
+#[derive(serde::Serialize)]
+pub struct CaptureRecording {
+    #[serde(flatten)]
+    pub recording: WavRecordingData,
+    pub port_address: PortAddress,
+    /// Every universe in the recording, busiest first.
+    pub universes: Vec<PortAddress>,
+    /// Alternate start code frames of every universe, on the same timeline.
+    pub nzs: Vec<NzsRecord>,
+}
+
+/// The track of `port_address`, or of the busiest universe, as a preview.
+pub fn universe_recording(
+    data: &RecordData,
+    port_address: Option<PortAddress>,
+) -> Result<CaptureRecording> {
+    let universes = universes(data);
+    let port_address = port_address
+        .or(universes.first().copied())
+        .ok_or_else(|| anyhow!("No recorded frames"))?;
+    let track = data
+        .track(port_address)
+        .ok_or_else(|| anyhow!("Universe {} is not in the recording", port_address))?;
+    let base = track.timestamps.first().copied().unwrap_or(0);
+    Ok(CaptureRecording {
+        recording: WavRecordingData {
+            timestamps: track
+                .timestamps
+                .iter()
+                .map(|t| t.saturating_sub(base))
+                .collect(),
+            channels: track.values.clone(),
+            dmx_channels: Some(track.channel_numbers()),
+            port_address: Some(port_address),
+        },
+        port_address,
+        universes,
+        nzs: data
+            .nzs
+            .iter()
+            .map(|rec| NzsRecord {
+                t_ms: rec.t_ms.saturating_sub(base),
+                frame: rec.frame.clone(),
+            })
+            .collect(),
+    })
+}
+
+pub fn save_wav(path: &str, sample_rate: u32, data: &WavRecordingData) -> Result<()> {
+    use std::io::Write;
+
+    let mut file = std::fs::File::create(path)?;
+
+    // WAV header
+    let num_channels = data.channels.len() as u16;
+    let bits_per_sample = 8u16;
+    let bytes_per_sample = bits_per_sample / 8;
+    let block_align = num_channels * bytes_per_sample;
+    let byte_rate = sample_rate * block_align as u32;
+    let data_size = (data.timestamps.len() as u32) * block_align as u32;
+    let metadata = data
+        .dmx_channels
+        .as_ref()
+        .map(|channels| {
+            let mut meta = serde_json::json!({ "dmx_channels": channels });
+            if let Some(addr) = data.port_address {
+                meta["port_address"] = serde_json::json!(addr);
+            }
+            meta.to_string().into_bytes()
+        })
+        .unwrap_or_default();
+    let metadata_chunk_size = if metadata.is_empty() {
+        0
+    } else {
+        8 + metadata.len() as u32 + (metadata.len() as u32 % 2)
+    };
+    let file_size = 36 + metadata_chunk_size + data_size;
+
+    // RIFF header
+    file.write_all(b"RIFF")?;
+    file.write_all(&file_size.to_le_bytes())?;
+    file.write_all(b"WAVE")?;
+
+    // fmt chunk
+    file.write_all(b"fmt ")?;
+    file.write_all(&16u32.to_le_bytes())?; // chunk size
+    file.write_all(&1u16.to_le_bytes())?; // PCM format
+    file.write_all(&num_channels.to_le_bytes())?;
+    file.write_all(&sample_rate.to_le_bytes())?;
+    file.write_all(&byte_rate.to_le_bytes())?;
+    file.write_all(&block_align.to_le_bytes())?;
+    file.write_all(&bits_per_sample.to_le_bytes())?;
+
+    if !metadata.is_empty() {
+        file.write_all(b"anlc")?;
+        file.write_all(&(metadata.len() as u32).to_le_bytes())?;
+        file.write_all(&metadata)?;
+        if metadata.len() % 2 == 1 {
+            file.write_all(&[0])?;
+        }
+    }
+
+    // data chunk
+    file.write_all(b"data")?;
+    file.write_all(&data_size.to_le_bytes())?;
+
+    // Write sample data (interleaved channels)
+    for frame_idx in 0..data.timestamps.len() {
+        for ch in 0..num_channels as usize {
+            let value = if ch < data.channels.len() && frame_idx < data.channels[ch].len() {
+                data.channels[ch][frame_idx]
+            } else {
+                0
+            };
+            file.write_all(&[value])?;
+        }
+    }
+    Ok(())
+}
+
+pub fn load_wav(path: &str) -> Result<WavRecordingData> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+
+    if buffer.len() < 44 {
+        return Err(anyhow!("File too small to be a valid WAV file"));
+    }
+
+    // Parse WAV header
+    let mut pos = 0;
+
+    // Check RIFF header
+    if &buffer[pos..pos + 4] != b"RIFF" {
+        return Err(anyhow!("Invalid RIFF header"));
+    }
+    pos += 4;
+
+    let _file_size = u32::from_le_bytes([
+        buffer[pos],
+        buffer[pos + 1],
+        buffer[pos + 2],
+        buffer[pos + 3],
+    ]);
+    pos += 4;
+
+    if &buffer[pos..pos + 4] != b"WAVE" {
+        return Err(anyhow!("Invalid WAVE header"));
+    }
+    pos += 4;
+
+    // Find fmt chunk
+    let mut sample_rate = 44100u32; // Default sample rate
+    let mut num_channels = 0u16;
+    let mut dmx_channels: Option<Vec<u16>> = None;
+    let mut port_address: Option<PortAddress> = None;
+    while pos < buffer.len() - 8 {
+        let chunk_id = &buffer[pos..pos + 4];
+        let chunk_size = u32::from_le_bytes([
+            buffer[pos + 4],
+            buffer[pos + 5],
+            buffer[pos + 6],
+            buffer[pos + 7],
+        ]);
+        pos += 8;
+
+        if chunk_id == b"fmt " {
+            if chunk_size < 16 {
+                return Err(anyhow!("Invalid fmt chunk size"));
+            }
+
+            let _format = u16::from_le_bytes([buffer[pos], buffer[pos + 1]]);
+            num_channels = u16::from_le_bytes([buffer[pos + 2], buffer[pos + 3]]);
+            sample_rate = u32::from_le_bytes([
+                buffer[pos + 4],
+                buffer[pos + 5],
+                buffer[pos + 6],
+                buffer[pos + 7],
+            ]);
+            let _byte_rate = u32::from_le_bytes([
+                buffer[pos + 8],
+                buffer[pos + 9],
+                buffer[pos + 10],
+                buffer[pos + 11],
+            ]);
+            let _block_align = u16::from_le_bytes([buffer[pos + 12], buffer[pos + 13]]);
+            let bits_per_sample = u16::from_le_bytes([buffer[pos + 14], buffer[pos + 15]]);
+
+            if bits_per_sample != 8 {
+                return Err(anyhow!(
+                    "Expected 8 bits per sample, got {}",
+                    bits_per_sample
+                ));
+            }
+            pos += chunk_size as usize;
+            break;
+        } else {
+            pos += chunk_size as usize + (chunk_size as usize % 2);
+        }
+    }
+
+    // Find data chunk
+    while pos < buffer.len() - 8 {
+        let chunk_id = &buffer[pos..pos + 4];
+        let chunk_size = u32::from_le_bytes([
+            buffer[pos + 4],
+            buffer[pos + 5],
+            buffer[pos + 6],
+            buffer[pos + 7],
+        ]);
+        pos += 8;
+        let chunk_end = pos.saturating_add(chunk_size as usize).min(buffer.len());
+
+        if chunk_id == b"anlc" {
+            if let Ok(text) = std::str::from_utf8(&buffer[pos..chunk_end]) {
+                if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+                    dmx_channels =
+                        value
+                            .get("dmx_channels")
+                            .and_then(|v| v.as_array())
+                            .map(|channels| {
+                                channels
+                                    .iter()
+                                    .filter_map(|ch| ch.as_u64().map(|n| n as u16))
+                                    .collect()
+                            });
+                    port_address = value
+                        .get("port_address")
+                        .and_then(|v| serde_json::from_value(v.clone()).ok());
+                }
+            }
+            pos = chunk_end + (chunk_size as usize % 2);
+        } else if chunk_id == b"data" {
+            // Read sample data
+            let num_frames = chunk_size as usize / num_channels as usize;
+            let mut timestamps = Vec::new();
+            let mut channels = vec![Vec::new(); num_channels as usize];
+
+            for frame_idx in 0..num_frames {
+                timestamps.push((frame_idx as u64 * 1000) / sample_rate as u64); // Convert to milliseconds
+
+                for ch in 0..num_channels as usize {
+                    if pos < buffer.len() {
+                        channels[ch].push(buffer[pos]);
+                        pos += 1;
+                    } else {
+                        channels[ch].push(0);
+                    }
+                }
+            }
+            return Ok(WavRecordingData {
+                timestamps,
+                channels,
+                dmx_channels,
+                port_address,
+            });
+        } else {
+            pos = chunk_end + (chunk_size as usize % 2);
+        }
+    }
+
+    Err(anyhow!("No data chunk found in WAV file"))
+}
+
+/// Loads a `.wav`, binary show, pcap / pcapng or JSONL recording; returns the
+/// data and its format name.
+pub fn load(path: &str) -> Result<(RecordData, &'static str)> {
+    if path.to_lowercase().ends_with(".wav") {
+        Ok((record_data_from_wav(load_wav(path)?), "wav"))
+    } else if crate::show::is_show_file(path) {
+        Ok((crate::show::load_record_data(path)?, "artrec"))
+    } else if crate::pcap::is_capture_file(path) {
+        Ok((crate::pcap::load_record_data(path)?, "pcap"))
+    } else {
+        Ok((load_jsonl(path)?, "jsonl"))
+    }
+}
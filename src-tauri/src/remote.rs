@@ -0,0 +1,621 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use tokio::net::UdpSocket;
+use tokio::sync::broadcast;
+
+use crate::artnet::{self, ARTNET_PORT};
+use crate::discovery::ArtNetDiscoveredNode;
+use crate::merge::MergeMode;
+use crate::node::{FailsafeState, IndicatorState, PollReply};
+use crate::sacn::OutputProtocol;
+
+pub const ADDRESS_LEN: usize = 107;
+const PROGRAM: u8 = 0x80; // Set on a switch value to program it
+const NO_CHANGE: u8 = 0x7f;
+const ACN_PRIORITY_NO_CHANGE: u8 = 0xff;
+
+/// ArtAddress Command field. Port numbers are 0..=3 within the addressed
+/// BindIndex page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AddressCommand {
+    #[default]
+    None,
+    CancelMerge,
+    LedNormal,
+    LedMute,
+    LedLocate,
+    ResetRxFlags,
+    FailHold,
+    FailZero,
+    FailFull,
+    FailScene,
+    MergeLtp(u8),
+    MergeHtp(u8),
+    ArtNetSel(u8),
+    AcnSel(u8),
+    ClearOutput(u8),
+}
+
+impl AddressCommand {
+    fn code(self) -> u8 {
+        match self {
+            Self::None => 0x00,
+            Self::CancelMerge => 0x01,
+            Self::LedNormal => 0x02,
+            Self::LedMute => 0x03,
+            Self::LedLocate => 0x04,
+            Self::ResetRxFlags => 0x05,
+            Self::FailHold => 0x08,
+            Self::FailZero => 0x09,
+            Self::FailFull => 0x0a,
+            Self::FailScene => 0x0b,
+            Self::MergeLtp(p) => 0x10 | (p & 0x03),
+            Self::MergeHtp(p) => 0x50 | (p & 0x03),
+            Self::ArtNetSel(p) => 0x60 | (p & 0x03),
+            Self::AcnSel(p) => 0x70 | (p & 0x03),
+            Self::ClearOutput(p) => 0x90 | (p & 0x03),
+        }
+    }
+
+    fn port(self) -> Option<u8> {
+        match self {
+            Self::MergeLtp(p)
+            | Self::MergeHtp(p)
+            | Self::ArtNetSel(p)
+            | Self::AcnSel(p)
+            | Self::ClearOutput(p) => Some(p),
+            _ => None,
+        }
+    }
+}
+
+/// Changes to program on a node. `None` fields are left as they are.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct AddressRequest {
+    pub ip: String,
+    pub port: u16,
+    pub bind_index: u8,
+    pub short_name: Option<String>, // up to 17 characters
+    pub long_name: Option<String>,  // up to 63 characters
+    pub net_switch: Option<u8>,     // 0..=127
+    pub sub_switch: Option<u8>,     // 0..=15
+    /// Universe (low 4 bits of the Port-Address) per input port.
+    pub sw_in: [Option<u8>; 4],
+    /// Universe per output port.
+    pub sw_out: [Option<u8>; 4],
+    pub acn_priority: Option<u8>, // 0..=200
+    pub command: AddressCommand,
+}
+
+impl Default for AddressRequest {
+    fn default() -> Self {
+        Self {
+            ip: String::new(),
+            port: ARTNET_PORT,
+            bind_index: 1,
+            short_name: None,
+            long_name: None,
+            net_switch: None,
+            sub_switch: None,
+            sw_in: [None; 4],
+            sw_out: [None; 4],
+            acn_priority: None,
+            command: AddressCommand::None,
+        }
+    }
+}
+
+/// Writes a name without its surrounding whitespace, which nodes do not
+/// report back in ArtPollReply.
+fn put_name(buf: &mut [u8], name: &Option<String>, field: &str) -> Result<()> {
+    if let Some(s) = name {
+        let s = s.trim();
+        if s.len() >= buf.len() {
+            return Err(anyhow!("{} is longer than {} bytes", field, buf.len() - 1));
+        }
+        buf[..s.len()].copy_from_slice(s.as_bytes());
+    }
+    Ok(())
+}
+
+fn switch(value: Option<u8>, max: u8, field: &str) -> Result<u8> {
+    match value {
+        None => Ok(NO_CHANGE),
+        Some(v) if v <= max => Ok(PROGRAM | v),
+        Some(v) => Err(anyhow!("{} {} out of range 0..={}", field, v, max)),
+    }
+}
+
+pub fn encode_address(req: &AddressRequest) -> Result<Vec<u8>> {
+    if let Some(p) = req.command.port().filter(|p| *p > 3) {
+        return Err(anyhow!("port {} out of range 0..=3", p));
+    }
+    let mut pkt = vec![0u8; ADDRESS_LEN];
+    pkt[0..8].copy_from_slice(artnet::ARTNET_ID);
+    pkt[8..10].copy_from_slice(&artnet::OP_ADDRESS.to_le_bytes());
+    pkt[10..12].copy_from_slice(&artnet::PROT_VER.to_be_bytes());
+    pkt[12] = switch(req.net_switch, 0x7f, "net")?;
+    pkt[13] = req.bind_index;
+    put_name(&mut pkt[14..32], &req.short_name, "short name")?;
+    put_name(&mut pkt[32..96], &req.long_name, "long name")?;
+    for i in 0..4 {
+        pkt[96 + i] = switch(req.sw_in[i], 0x0f, "input universe")?;
+        pkt[100 + i] = switch(req.sw_out[i], 0x0f, "output universe")?;
+    }
+    pkt[104] = switch(req.sub_switch, 0x0f, "subnet")?;
+    pkt[105] = match req.acn_priority {
+        None => ACN_PRIORITY_NO_CHANGE,
+        Some(p) if p <= 200 => p,
+        Some(p) => return Err(anyhow!("sACN priority {} out of range 0..=200", p)),
+    };
+    pkt[106] = req.command.code();
+    Ok(pkt)
+}
+
+/// Sends the ArtAddress unicast to the node.
+pub async fn send_address(req: &AddressRequest) -> Result<()> {
+    let ip: Ipv4Addr = req
+        .ip
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("invalid node address: {}", req.ip))?;
+    let pkt = encode_address(req)?;
+    let sock = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).await?;
+    sock.send_to(&pkt, SocketAddr::new(IpAddr::V4(ip), req.port))
+        .await?;
+    Ok(())
+}
+
+/// Result of programming a node: its ArtPollReply afterwards and the
+/// requested values it does not report.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressOutcome {
+    pub reply: Option<ArtNetDiscoveredNode>,
+    pub mismatches: Vec<String>,
+    pub verified: bool,
+}
+
+/// BindIndex 0 and 1 both mean the root device.
+fn same_page(a: u8, b: u8) -> bool {
+    a.max(1) == b.max(1)
+}
+
+fn check<T: PartialEq + std::fmt::Debug>(out: &mut Vec<String>, field: &str, want: T, got: T) {
+    if want != got {
+        out.push(format!(
+            "{}: expected {:?}, node reports {:?}",
+            field, want, got
+        ));
+    }
+}
+
+fn mismatches(req: &AddressRequest, reply: &PollReply) -> Vec<String> {
+    let mut out = Vec::new();
+    // Names are compared trimmed, as `put_name` sends them.
+    if let Some(name) = &req.short_name {
+        check(
+            &mut out,
+            "short name",
+            name.trim(),
+            reply.short_name.as_str(),
+        );
+    }
+    if let Some(name) = &req.long_name {
+        check(&mut out, "long name", name.trim(), reply.long_name.as_str());
+    }
+    if let Some(net) = req.net_switch {
+        check(&mut out, "net", net, reply.net_switch & 0x7f);
+    }
+    if let Some(sub) = req.sub_switch {
+        check(&mut out, "subnet", sub, reply.sub_switch & 0x0f);
+    }
+    let port = |i: usize| reply.ports.iter().find(|p| p.index as usize == i);
+    for i in 0..4 {
+        if let Some(uni) = req.sw_in[i] {
+            let got = port(i)
+                .and_then(|p| p.input.as_ref())
+                .map(|p| p.port_address.universe());
+            check(&mut out, &format!("input {} universe", i), Some(uni), got);
+        }
+        if let Some(uni) = req.sw_out[i] {
+            let got = port(i)
+                .and_then(|p| p.output.as_ref())
+                .map(|p| p.port_address.universe());
+            check(&mut out, &format!("output {} universe", i), Some(uni), got);
+        }
+    }
+    if let Some(prio) = req.acn_priority {
+        check(&mut out, "sACN priority", prio, reply.acn_priority);
+    }
+    let output = |p: u8| port(p as usize).and_then(|p| p.output.as_ref());
+    match req.command {
+        AddressCommand::LedNormal => check(
+            &mut out,
+            "indicators",
+            IndicatorState::Normal,
+            reply.status1.indicators,
+        ),
+        AddressCommand::LedMute => check(
+            &mut out,
+            "indicators",
+            IndicatorState::Mute,
+            reply.status1.indicators,
+        ),
+        AddressCommand::LedLocate => check(
+            &mut out,
+            "indicators",
+            IndicatorState::Locate,
+            reply.status1.indicators,
+        ),
+        AddressCommand::FailHold => check(
+            &mut out,
+            "failsafe",
+            FailsafeState::HoldLast,
+            reply.status3.failsafe,
+        ),
+        AddressCommand::FailZero => check(
+            &mut out,
+            "failsafe",
+            FailsafeState::AllZero,
+            reply.status3.failsafe,
+        ),
+        AddressCommand::FailFull => check(
+            &mut out,
+            "failsafe",
+            FailsafeState::AllFull,
+            reply.status3.failsafe,
+        ),
+        AddressCommand::FailScene => check(
+            &mut out,
+            "failsafe",
+            FailsafeState::Scene,
+            reply.status3.failsafe,
+        ),
+        AddressCommand::MergeLtp(p) => check(
+            &mut out,
+            &format!("output {} merge mode", p),
+            Some(MergeMode::Ltp),
+            output(p).map(|o| o.merge_mode),
+        ),
+        AddressCommand::MergeHtp(p) => check(
+            &mut out,
+            &format!("output {} merge mode", p),
+            Some(MergeMode::Htp),
+            output(p).map(|o| o.merge_mode),
+        ),
+        AddressCommand::ArtNetSel(p) => check(
+            &mut out,
+            &format!("output {} protocol", p),
+            Some(OutputProtocol::ArtNet),
+            output(p).map(|o| o.source),
+        ),
+        AddressCommand::AcnSel(p) => check(
+            &mut out,
+            &format!("output {} protocol", p),
+            Some(OutputProtocol::Sacn),
+            output(p).map(|o| o.source),
+        ),
+        // Cancelling a merge, clearing a buffer or resetting flags leaves
+        // nothing in the reply to compare against.
+        AddressCommand::None
+        | AddressCommand::CancelMerge
+        | AddressCommand::ResetRxFlags
+        | AddressCommand::ClearOutput(_) => {}
+    }
+    out
+}
+
+/// Picks the addressed node from scan results and compares it to the request.
+pub fn verify(req: &AddressRequest, nodes: &[ArtNetDiscoveredNode]) -> AddressOutcome {
+    let target = req.ip.trim();
+    let reply = nodes
+        .iter()
+        .find(|n| {
+            let from_target = n.reply.ip == target
+                || n.udp_source
+                    .parse::<SocketAddr>()
+                    .is_ok_and(|s| s.ip().to_string() == target);
+            from_target && same_page(n.reply.bind_index, req.bind_index)
+        })
+        .cloned();
+    match reply {
+        Some(node) => {
+            let mismatches = mismatches(req, &node.reply);
+            AddressOutcome {
+                verified: mismatches.is_empty(),
+                reply: Some(node),
+                mismatches,
+            }
+        }
+        None => AddressOutcome {
+            reply: None,
+            mismatches: vec!["no ArtPollReply from the node".into()],
+            verified: false,
+        },
+    }
+}
+
+pub const IP_PROG_LEN: usize = 34;
+const IP_PROG_ENABLE: u8 = 0x80;
+const IP_PROG_DHCP: u8 = 0x40;
+const IP_PROG_GATEWAY: u8 = 0x10;
+const IP_PROG_RESET: u8 = 0x08;
+const IP_PROG_IP: u8 = 0x04;
+const IP_PROG_MASK: u8 = 0x02;
+const IP_PROG_PORT: u8 = 0x01;
+const IP_PROG_REPLY_DHCP: u8 = 0x40;
+const IP_PROG_RESEND: Duration = Duration::from_millis(500);
+
+/// ArtIpProg to a node. With nothing set it only queries the current
+/// settings; `dhcp` overrides the static fields.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct IpProgRequest {
+    pub ip: String,
+    pub port: u16,
+    pub new_ip: Option<String>,
+    pub subnet_mask: Option<String>,
+    pub gateway: Option<String>,
+    pub prog_port: Option<u16>, // deprecated by Art-Net 4, still sent
+    pub dhcp: bool,
+    pub reset_defaults: bool,
+}
+
+impl Default for IpProgRequest {
+    fn default() -> Self {
+        Self {
+            ip: String::new(),
+            port: ARTNET_PORT,
+            new_ip: None,
+            subnet_mask: None,
+            gateway: None,
+            prog_port: None,
+            dhcp: false,
+            reset_defaults: false,
+        }
+    }
+}
+
+impl IpProgRequest {
+    pub fn is_query(&self) -> bool {
+        self.new_ip.is_none()
+            && self.subnet_mask.is_none()
+            && self.gateway.is_none()
+            && self.prog_port.is_none()
+            && !self.dhcp
+            && !self.reset_defaults
+    }
+}
+
+/// Network settings reported in an ArtIpProgReply.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpSettings {
+    pub ip: String,
+    pub subnet_mask: String,
+    pub gateway: String,
+    pub port: u16,
+    pub dhcp: bool,
+}
+
+fn parse_ipv4(s: &Option<String>, field: &str) -> Result<Option<Ipv4Addr>> {
+    s.as_deref()
+        .map(|v| {
+            v.trim()
+                .parse()
+                .map_err(|_| anyhow!("invalid {}: {}", field, v))
+        })
+        .transpose()
+}
+
+pub fn encode_ip_prog(req: &IpProgRequest) -> Result<Vec<u8>> {
+    let mut pkt = vec![0u8; IP_PROG_LEN];
+    pkt[0..8].copy_from_slice(artnet::ARTNET_ID);
+    pkt[8..10].copy_from_slice(&artnet::OP_IP_PROG.to_le_bytes());
+    pkt[10..12].copy_from_slice(&artnet::PROT_VER.to_be_bytes());
+    let mut command = 0u8;
+    if req.dhcp {
+        command |= IP_PROG_DHCP;
+    }
+    if req.reset_defaults {
+        command |= IP_PROG_RESET;
+    }
+    if let Some(ip) = parse_ipv4(&req.new_ip, "IP address")? {
+        command |= IP_PROG_IP;
+        pkt[16..20].copy_from_slice(&ip.octets());
+    }
+    if let Some(mask) = parse_ipv4(&req.subnet_mask, "subnet mask")? {
+        command |= IP_PROG_MASK;
+        pkt[20..24].copy_from_slice(&mask.octets());
+    }
+    if let Some(port) = req.prog_port {
+        command |= IP_PROG_PORT;
+        pkt[24..26].copy_from_slice(&port.to_be_bytes());
+    }
+    if let Some(gw) = parse_ipv4(&req.gateway, "gateway")? {
+        command |= IP_PROG_GATEWAY;
+        pkt[26..30].copy_from_slice(&gw.octets());
+    }
+    if command != 0 {
+        command |= IP_PROG_ENABLE;
+    }
+    pkt[14] = command;
+    Ok(pkt)
+}
+
+pub fn parse_ip_prog_reply(buf: &[u8]) -> Result<IpSettings> {
+    // Gateway (bytes 28..32) was added in Art-Net 4; older nodes stop at 28.
+    if buf.len() < 28 || artnet::opcode(buf) != Some(artnet::OP_IP_PROG_REPLY) {
+        return Err(anyhow!("Not an ArtIpProgReply"));
+    }
+    let ip4 = |b: &[u8]| Ipv4Addr::new(b[0], b[1], b[2], b[3]).to_string();
+    Ok(IpSettings {
+        ip: ip4(&buf[16..20]),
+        subnet_mask: ip4(&buf[20..24]),
+        port: u16::from_be_bytes([buf[24], buf[25]]),
+        dhcp: buf[26] & IP_PROG_REPLY_DHCP != 0,
+        gateway: buf.get(28..32).map(ip4).unwrap_or_default(),
+    })
+}
+
+/// Settings read back from the node and the requested values it does not
+/// report.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpProgOutcome {
+    pub reply: Option<IpSettings>,
+    pub reply_from: Option<String>,
+    pub mismatches: Vec<String>,
+    pub verified: bool,
+}
+
+fn ip_prog_mismatches(req: &IpProgRequest, reply: &IpSettings) -> Vec<String> {
+    let mut out = Vec::new();
+    if req.dhcp {
+        check(&mut out, "DHCP", true, reply.dhcp);
+        return out;
+    }
+    if req.reset_defaults {
+        return out;
+    }
+    let fields = [
+        ("IP", &req.new_ip, &reply.ip),
+        ("subnet mask", &req.subnet_mask, &reply.subnet_mask),
+        ("gateway", &req.gateway, &reply.gateway),
+    ];
+    for (field, want, got) in fields {
+        if let Some(want) = want {
+            check(&mut out, field, want.trim(), got.as_str());
+        }
+    }
+    if let Some(port) = req.prog_port {
+        check(&mut out, "port", port, reply.port);
+    }
+    out
+}
+
+/// Sends `pkt` to `dest`, repeated every `resend` if given, and feeds the
+/// packets arriving on the sending socket or through the receiver relay to
+/// `accept` until it reports the exchange complete or `timeout` passes.
+/// Returns whether it completed.
+pub async fn exchange(
+    dest: SocketAddr,
+    pkt: &[u8],
+    mut relay: Option<broadcast::Receiver<(SocketAddr, Vec<u8>)>>,
+    timeout: Duration,
+    resend: Option<Duration>,
+    mut accept: impl FnMut(SocketAddr, &[u8]) -> bool,
+) -> Result<bool> {
+    enum Recv {
+        Packet(SocketAddr, Vec<u8>),
+        Nothing,
+        RelayClosed,
+    }
+
+    let sock = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).await?;
+    let deadline = Instant::now() + timeout;
+    let mut buf = [0u8; 2048];
+    let mut last_send: Option<Instant> = None;
+    while Instant::now() < deadline {
+        let due = match (last_send, resend) {
+            (None, _) => true,
+            (Some(t), Some(every)) => t.elapsed() >= every,
+            (Some(_), None) => false,
+        };
+        if due {
+            sock.send_to(pkt, dest).await?;
+            last_send = Some(Instant::now());
+        }
+        let slice = deadline
+            .saturating_duration_since(Instant::now())
+            .min(Duration::from_millis(100));
+        let recv = async {
+            match relay.as_mut() {
+                Some(rx) => tokio::select! {
+                    r = sock.recv_from(&mut buf) => match r {
+                        Ok((n, src)) => Recv::Packet(src, buf[..n].to_vec()),
+                        Err(_) => Recv::Nothing,
+                    },
+                    r = rx.recv() => match r {
+                        Ok((src, data)) => Recv::Packet(src, data),
+                        Err(broadcast::error::RecvError::Lagged(_)) => Recv::Nothing,
+                        Err(broadcast::error::RecvError::Closed) => Recv::RelayClosed,
+                    },
+                },
+                None => match sock.recv_from(&mut buf).await {
+                    Ok((n, src)) => Recv::Packet(src, buf[..n].to_vec()),
+                    Err(_) => Recv::Nothing,
+                },
+            }
+        };
+        match tokio::time::timeout(slice, recv).await {
+            Ok(Recv::Packet(src, data)) if accept(src, &data) => return Ok(true),
+            Ok(Recv::RelayClosed) => relay = None,
+            _ => {}
+        }
+    }
+    Ok(false)
+}
+
+/// Sends ArtIpProg to the node and waits for its ArtIpProgReply. The reply
+/// may come from the new address when the IP was changed.
+pub async fn ip_prog(
+    req: &IpProgRequest,
+    relay: Option<broadcast::Receiver<(SocketAddr, Vec<u8>)>>,
+    timeout_ms: u64,
+) -> Result<IpProgOutcome> {
+    let target: Ipv4Addr = req
+        .ip
+        .trim()
+        .parse()
+        .map_err(|_| anyhow!("invalid node address: {}", req.ip))?;
+    let new_ip = parse_ipv4(&req.new_ip, "IP address")?;
+    let pkt = encode_ip_prog(req)?;
+    let dest = SocketAddr::new(IpAddr::V4(target), req.port);
+    let from_node = |src: &SocketAddr| {
+        src.ip() == IpAddr::V4(target) || new_ip.is_some_and(|ip| src.ip() == IpAddr::V4(ip))
+    };
+
+    let mut found = None;
+    // Programming the same values twice is harmless, so keep repeating.
+    exchange(
+        dest,
+        &pkt,
+        relay,
+        Duration::from_millis(timeout_ms.max(200)),
+        Some(IP_PROG_RESEND),
+        |src, data| {
+            if from_node(&src) {
+                if let Ok(reply) = parse_ip_prog_reply(data) {
+                    found = Some((src, reply));
+                    return true;
+                }
+            }
+            false
+        },
+    )
+    .await?;
+
+    Ok(match found {
+        Some((src, reply)) => {
+            let mismatches = ip_prog_mismatches(req, &reply);
+            IpProgOutcome {
+                verified: mismatches.is_empty(),
+                reply: Some(reply),
+                reply_from: Some(src.ip().to_string()),
+                mismatches,
+            }
+        }
+        None => IpProgOutcome {
+            reply: None,
+            reply_from: None,
+            mismatches: vec!["no ArtIpProgReply from the node".into()],
+            verified: false,
+        },
+    })
+}
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use anyhow::{anyhow, Result};
+use tokio::net::UdpSocket;
+
+use crate::artnet::PortAddress;
+
+pub const SACN_PORT: u16 = 5568;
+const ACN_PACKET_ID: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const VECTOR_ROOT_E131_DATA: u32 = 0x0000_0004;
+const VECTOR_E131_DATA_PACKET: u32 = 0x0000_0002;
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+const DMP_ADDRESS_DATA_TYPE: u8 = 0xa1;
+const HEADER_LEN: usize = 126; // up to and including the start code
+pub const OPTION_PREVIEW: u8 = 0x80;
+pub const OPTION_STREAM_TERMINATED: u8 = 0x40;
+pub const DEFAULT_PRIORITY: u8 = 100;
+
+/// E1.31 Component Identifier, a UUID identifying this source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cid(pub [u8; 16]);
+
+impl Cid {
+    /// Random (version 4) UUID.
+    pub fn random() -> Self {
+        let mut bytes = [0u8; 16];
+        for chunk in bytes.chunks_mut(8) {
+            let mut h = std::collections::hash_map::RandomState::new().build_hasher();
+            h.write_u128(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0),
+            );
+            chunk.copy_from_slice(&h.finish().to_be_bytes());
+        }
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Self(bytes)
+    }
+}
+
+impl fmt::Display for Cid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, b) in self.0.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                write!(f, "-")?;
+            }
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Cid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let hex: String = s.chars().filter(|c| *c != '-').collect();
+        if hex.len() != 32 {
+            return Err(anyhow!("Invalid CID: {:?}", s));
+        }
+        let mut bytes = [0u8; 16];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                .map_err(|_| anyhow!("Invalid CID: {:?}", s))?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+impl serde::Serialize for Cid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Cid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Source identity used for all sACN output, persisted with the settings.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SacnConfig {
+    pub source_name: String, // up to 63 characters
+    pub cid: Cid,
+    pub priority: u8, // 0..=200
+}
+
+impl Default for SacnConfig {
+    fn default() -> Self {
+        Self {
+            source_name: "ArtNetLab".into(),
+            cid: Cid::random(),
+            priority: DEFAULT_PRIORITY,
+        }
+    }
+}
+
+/// sACN receiver settings, persisted with the settings.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SacnReceiverConfig {
+    pub enabled: bool,
+    pub bind_ip: String,
+    /// sACN universes (1..=63999) whose multicast groups are joined.
+    pub universes: Vec<u16>,
+    /// Also accept unicast streams for universes outside `universes`.
+    pub unicast: bool,
+}
+
+impl Default for SacnReceiverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_ip: "0.0.0.0".into(),
+            universes: vec![1],
+            unicast: true,
+        }
+    }
+}
+
+/// Decoded E1.31 data packet.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SacnPacket {
+    pub cid: Cid,
+    pub source_name: String,
+    pub priority: u8,
+    pub sync_address: u16,
+    pub sequence: u8,
+    pub preview: bool,
+    pub stream_terminated: bool,
+    pub universe: u16,
+    pub start_code: u8,
+    pub values: Vec<u8>,
+}
+
+/// Output protocol of a sender universe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputProtocol {
+    #[default]
+    ArtNet,
+    Sacn,
+}
+
+/// sACN universe carrying a given Port-Address. sACN universes start at 1,
+/// so Art-Net 0:0:0 maps to sACN universe 1.
+pub fn sacn_universe(addr: PortAddress) -> u16 {
+    addr.value() + 1
+}
+
+/// Inverse of `sacn_universe`; `None` for universes beyond the Port-Address range.
+pub fn port_address_for(universe: u16) -> Option<PortAddress> {
+    universe
+        .checked_sub(1)
+        .and_then(|v| PortAddress::new(v).ok())
+}
+
+/// Multicast group 239.255.{hi}.{lo} of an sACN universe.
+pub fn multicast_addr(universe: u16) -> Ipv4Addr {
+    let [hi, lo] = universe.to_be_bytes();
+    Ipv4Addr::new(239, 255, hi, lo)
+}
+
+fn flags_and_length(len: usize) -> [u8; 2] {
+    (0x7000 | (len as u16 & 0x0fff)).to_be_bytes()
+}
+
+pub fn encode_data_into(
+    pkt: &mut Vec<u8>,
+    cfg: &SacnConfig,
+    universe: u16,
+    start_code: u8,
+    data: &[u8],
+    sequence: u8,
+    options: u8,
+) {
+    let slots = data.len().min(512);
+    let total = HEADER_LEN + slots;
+    pkt.clear();
+    pkt.resize(total, 0);
+    // Root layer
+    pkt[0..2].copy_from_slice(&0x0010u16.to_be_bytes()); // Preamble size
+    pkt[4..16].copy_from_slice(ACN_PACKET_ID);
+    pkt[16..18].copy_from_slice(&flags_and_length(total - 16));
+    pkt[18..22].copy_from_slice(&VECTOR_ROOT_E131_DATA.to_be_bytes());
+    pkt[22..38].copy_from_slice(&cfg.cid.0);
+    // Framing layer
+    pkt[38..40].copy_from_slice(&flags_and_length(total - 38));
+    pkt[40..44].copy_from_slice(&VECTOR_E131_DATA_PACKET.to_be_bytes());
+    let name = cfg.source_name.as_bytes();
+    let n = name.len().min(63);
+    pkt[44..44 + n].copy_from_slice(&name[..n]);
+    pkt[108] = cfg.priority.min(200);
+    pkt[111] = sequence;
+    pkt[112] = options;
+    pkt[113..115].copy_from_slice(&universe.to_be_bytes());
+    // DMP layer
+    pkt[115..117].copy_from_slice(&flags_and_length(total - 115));
+    pkt[117] = VECTOR_DMP_SET_PROPERTY;
+    pkt[118] = DMP_ADDRESS_DATA_TYPE;
+    pkt[121..123].copy_from_slice(&1u16.to_be_bytes()); // Address increment
+    pkt[123..125].copy_from_slice(&(slots as u16 + 1).to_be_bytes());
+    pkt[125] = start_code; // 0 for DMX
+    pkt[HEADER_LEN..].copy_from_slice(&data[..slots]);
+}
+
+/// Destination of an sACN universe: unicast when a target IP is given,
+/// otherwise the universe's multicast group.
+pub fn destination(universe: u16, target_ip: Option<&str>) -> Result<SocketAddr> {
+    let ip = match target_ip {
+        Some(t) => t.trim().parse::<IpAddr>()?,
+        None => IpAddr::V4(multicast_addr(universe)),
+    };
+    Ok(SocketAddr::new(ip, SACN_PORT))
+}
+
+pub async fn send_data_with_buffer(
+    sock: &UdpSocket,
+    cfg: &SacnConfig,
+    addr: PortAddress,
+    target_ip: Option<&str>,
+    data: &[u8; 512],
+    sequence: u8,
+    pkt: &mut Vec<u8>,
+) -> Result<()> {
+    let universe = sacn_universe(addr);
+    encode_data_into(pkt, cfg, universe, 0, data, sequence, 0);
+    sock.send_to(pkt, destination(universe, target_ip)?).await?;
+    Ok(())
+}
+
+pub fn parse_data(buf: &[u8]) -> Result<SacnPacket> {
+    if buf.len() < HEADER_LEN {
+        return Err(anyhow!("Packet too short"));
+    }
+    if &buf[4..16] != ACN_PACKET_ID {
+        return Err(anyhow!("Not ACN"));
+    }
+    let root_vector = u32::from_be_bytes([buf[18], buf[19], buf[20], buf[21]]);
+    if root_vector != VECTOR_ROOT_E131_DATA {
+        return Err(anyhow!("Unsupported root vector"));
+    }
+    let framing_vector = u32::from_be_bytes([buf[40], buf[41], buf[42], buf[43]]);
+    if framing_vector != VECTOR_E131_DATA_PACKET {
+        return Err(anyhow!("Unsupported framing vector"));
+    }
+    if buf[117] != VECTOR_DMP_SET_PROPERTY || buf[118] != DMP_ADDRESS_DATA_TYPE {
+        return Err(anyhow!("Unsupported DMP layer"));
+    }
+    let count = u16::from_be_bytes([buf[123], buf[124]]) as usize;
+    if count == 0 || count > 513 || buf.len() < 125 + count {
+        return Err(anyhow!("Length mismatch"));
+    }
+    let universe = u16::from_be_bytes([buf[113], buf[114]]);
+    if universe == 0 || universe > 63999 {
+        return Err(anyhow!("Invalid universe {}", universe));
+    }
+    let mut cid = [0u8; 16];
+    cid.copy_from_slice(&buf[22..38]);
+    let name = &buf[44..108];
+    let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+    let options = buf[112];
+    Ok(SacnPacket {
+        cid: Cid(cid),
+        source_name: String::from_utf8_lossy(&name[..end]).trim().to_string(),
+        priority: buf[108],
+        sync_address: u16::from_be_bytes([buf[109], buf[110]]),
+        sequence: buf[111],
+        preview: options & OPTION_PREVIEW != 0,
+        stream_terminated: options & OPTION_STREAM_TERMINATED != 0,
+        universe,
+        start_code: buf[125],
+        values: buf[126..125 + count].to_vec(),
+    })
+}
+
+pub async fn bind_receiver_socket(cfg: &SacnReceiverConfig) -> Result<UdpSocket> {
+    let sock = crate::artnet::bind_reuse_socket(&cfg.bind_ip, SACN_PORT)?;
+    let iface = match cfg.bind_ip.parse::<IpAddr>()? {
+        IpAddr::V4(v4) => v4,
+        IpAddr::V6(_) => return Err(anyhow!("sACN receiver needs an IPv4 bind address")),
+    };
+    for universe in &cfg.universes {
+        if *universe == 0 || *universe > 63999 {
+            continue;
+        }
+        sock.join_multicast_v4(multicast_addr(*universe), iface)?;
+    }
+    Ok(sock)
+}
@@ -0,0 +1,893 @@
+//! Binary show recordings (`.artrec`): per-universe delta frames in
+//! independently compressed segments, each opening with a keyframe, and an
+//! index of the segments so playback can seek without reading from the start.
+//!
+//! Layout, little endian:
+//!
+//! ```text
+//! header   "ARTREC\0\0", version u16, compression u8, 0u8, keyframe ms u32
+//! segment  stored length u32, raw length u32, start ms u64, payload
+//! index    segment count u32, then start ms u64 and file offset u64 each
+//! trailer  index offset u64, "ARTRIDX\0"
+//! ```
+//!
+//! A segment payload is a run of records: a tag byte, the milliseconds since
+//! the previous record (varint), the Port-Address (u16), then
+//!
+//! ```text
+//! STATE, FULL  length varint, values
+//! DELTA        length varint, run count varint, and per run the channels
+//!              skipped since the previous run and its length (varints)
+//!              followed by its values
+//! NZS          start code u8, length varint, values
+//! ```
+//!
+//! STATE records open a segment with the values of every universe seen so
+//! far; they restore the look when seeking and are not frames themselves.
+//! Deltas are against the previous values of the universe in the segment.
+//! A file whose trailer is missing, as after a crash, is read by walking its
+//! segments.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::artnet::{NzsFrame, PortAddress};
+use crate::recording::{self, TimedFrame};
+use crate::state::{NzsRecord, PlayFrame, RecordData};
+
+const MAGIC: &[u8; 8] = b"ARTREC\0\0";
+const INDEX_MAGIC: &[u8; 8] = b"ARTRIDX\0";
+const VERSION: u16 = 1;
+const HEADER_LEN: u64 = 16;
+const SEGMENT_HEADER_LEN: u64 = 16;
+const TRAILER_LEN: u64 = 16;
+
+const TAG_STATE: u8 = 1;
+const TAG_FULL: u8 = 2;
+const TAG_DELTA: u8 = 3;
+const TAG_NZS: u8 = 4;
+
+/// Time between keyframes unless chosen otherwise; seeking decodes at most
+/// this much of the show.
+pub const DEFAULT_KEYFRAME_MS: u32 = 1000;
+
+/// Raw size after which the writer starts a new segment before the next
+/// keyframe is due, and the largest segment a reader accepts.
+const SEGMENT_SPLIT_LEN: usize = 16 << 20;
+const MAX_SEGMENT_LEN: usize = 64 << 20;
+
+/// Unchanged channels bridged inside a delta run rather than starting a new
+/// one, which costs two varints.
+const MAX_RUN_GAP: usize = 2;
+
+/// Whether `path` names a binary show file.
+pub fn is_show_file(path: &str) -> bool {
+    path.to_lowercase().ends_with(".artrec")
+}
+
+/// Compression of the segments of a show file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    #[default]
+    Zstd,
+    Lz4,
+}
+
+impl std::str::FromStr for Compression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "zstd" => Ok(Self::Zstd),
+            "lz4" => Ok(Self::Lz4),
+            _ => Err(anyhow!("Compression must be none, zstd or lz4")),
+        }
+    }
+}
+
+impl Compression {
+    fn code(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Zstd => 1,
+            Self::Lz4 => 2,
+        }
+    }
+
+    fn from_code(code: u8) -> Result<Self> {
+        match code {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Zstd),
+            2 => Ok(Self::Lz4),
+            _ => Err(anyhow!("Unknown show file compression {}", code)),
+        }
+    }
+
+    fn compress(self, raw: &[u8]) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::None => raw.to_vec(),
+            Self::Zstd => zstd::bulk::compress(raw, 3)?,
+            Self::Lz4 => lz4_flex::block::compress(raw),
+        })
+    }
+
+    fn decompress(self, stored: Vec<u8>, raw_len: usize) -> Result<Vec<u8>> {
+        let raw = match self {
+            Self::None => stored,
+            Self::Zstd => zstd::bulk::decompress(&stored, raw_len)?,
+            Self::Lz4 => lz4_flex::block::decompress(&stored, raw_len)?,
+        };
+        if raw.len() != raw_len {
+            return Err(anyhow!("Corrupt show file segment"));
+        }
+        Ok(raw)
+    }
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+/// Runs of channels whose value differs from `prev` (channels past its end
+/// count as zero), as `start..end` ranges.
+fn changed_runs(prev: &[u8], values: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for (ch, v) in values.iter().enumerate() {
+        if prev.get(ch).copied().unwrap_or(0) == *v {
+            continue;
+        }
+        match runs.last_mut() {
+            Some((_, end)) if ch - *end <= MAX_RUN_GAP => *end = ch + 1,
+            _ => runs.push((ch, ch + 1)),
+        }
+    }
+    runs
+}
+
+/// Writes a show file frame by frame. Dropping the writer completes the
+/// file, so an aborted recorder still leaves it indexed.
+pub struct ShowWriter {
+    file: BufWriter<File>,
+    compression: Compression,
+    keyframe_ms: u64,
+    /// Raw records of the open segment.
+    segment: Vec<u8>,
+    segment_start: u64,
+    last_t: u64,
+    offset: u64,
+    /// Latest values of each universe, the base of its deltas.
+    universes: BTreeMap<PortAddress, Vec<u8>>,
+    index: Vec<(u64, u64)>,
+    finished: bool,
+}
+
+impl ShowWriter {
+    pub fn create(path: &str, compression: Compression, keyframe_ms: u32) -> Result<Self> {
+        let keyframe_ms = keyframe_ms.max(1);
+        let mut file = BufWriter::new(File::create(path)?);
+        file.write_all(MAGIC)?;
+        file.write_all(&VERSION.to_le_bytes())?;
+        file.write_all(&[compression.code(), 0])?;
+        file.write_all(&keyframe_ms.to_le_bytes())?;
+        Ok(Self {
+            file,
+            compression,
+            keyframe_ms: keyframe_ms as u64,
+            segment: Vec::new(),
+            segment_start: 0,
+            last_t: 0,
+            offset: HEADER_LEN,
+            universes: BTreeMap::new(),
+            index: Vec::new(),
+            finished: false,
+        })
+    }
+
+    pub fn write(&mut self, frame: &TimedFrame) -> Result<()> {
+        match frame {
+            TimedFrame::Dmx {
+                t_ms,
+                port_address,
+                values,
+            } => self.write_dmx(*t_ms, *port_address, values),
+            TimedFrame::Nzs(rec) => self.write_nzs(rec.t_ms, &rec.frame),
+        }
+    }
+
+    pub fn write_dmx(&mut self, t_ms: u64, addr: PortAddress, values: &[u8]) -> Result<()> {
+        self.record_header(t_ms)?;
+        let prev = self.universes.entry(addr).or_default();
+        let runs = changed_runs(prev, values);
+        let mut delta = Vec::new();
+        put_varint(&mut delta, runs.len() as u64);
+        let mut at = 0;
+        for (start, end) in runs {
+            put_varint(&mut delta, (start - at) as u64);
+            put_varint(&mut delta, (end - start) as u64);
+            delta.extend_from_slice(&values[start..end]);
+            at = end;
+        }
+        let seg = &mut self.segment;
+        if delta.len() < values.len() {
+            seg.push(TAG_DELTA);
+            put_varint(seg, t_ms.saturating_sub(self.last_t));
+            seg.extend_from_slice(&u16::from(addr).to_le_bytes());
+            put_varint(seg, values.len() as u64);
+            seg.extend_from_slice(&delta);
+        } else {
+            seg.push(TAG_FULL);
+            put_varint(seg, t_ms.saturating_sub(self.last_t));
+            seg.extend_from_slice(&u16::from(addr).to_le_bytes());
+            put_varint(seg, values.len() as u64);
+            seg.extend_from_slice(values);
+        }
+        prev.clear();
+        prev.extend_from_slice(values);
+        self.last_t = self.last_t.max(t_ms);
+        Ok(())
+    }
+
+    pub fn write_nzs(&mut self, t_ms: u64, frame: &NzsFrame) -> Result<()> {
+        self.record_header(t_ms)?;
+        let seg = &mut self.segment;
+        seg.push(TAG_NZS);
+        put_varint(seg, t_ms.saturating_sub(self.last_t));
+        seg.extend_from_slice(&u16::from(frame.port_address).to_le_bytes());
+        seg.push(frame.start_code);
+        put_varint(seg, frame.values.len() as u64);
+        seg.extend_from_slice(&frame.values);
+        self.last_t = self.last_t.max(t_ms);
+        Ok(())
+    }
+
+    /// Starts a new segment, opened by a keyframe, once the current one
+    /// spans the keyframe interval or grows too large.
+    fn record_header(&mut self, t_ms: u64) -> Result<()> {
+        if !self.segment.is_empty()
+            && t_ms.saturating_sub(self.segment_start) < self.keyframe_ms
+            && self.segment.len() < SEGMENT_SPLIT_LEN
+        {
+            return Ok(());
+        }
+        self.flush_segment()?;
+        self.segment_start = t_ms;
+        self.last_t = t_ms;
+        for (addr, values) in &self.universes {
+            self.segment.push(TAG_STATE);
+            put_varint(&mut self.segment, 0);
+            self.segment
+                .extend_from_slice(&u16::from(*addr).to_le_bytes());
+            put_varint(&mut self.segment, values.len() as u64);
+            self.segment.extend_from_slice(values);
+        }
+        Ok(())
+    }
+
+    fn flush_segment(&mut self) -> Result<()> {
+        if self.segment.is_empty() {
+            return Ok(());
+        }
+        let stored = self.compression.compress(&self.segment)?;
+        self.file.write_all(&(stored.len() as u32).to_le_bytes())?;
+        self.file
+            .write_all(&(self.segment.len() as u32).to_le_bytes())?;
+        self.file.write_all(&self.segment_start.to_le_bytes())?;
+        self.file.write_all(&stored)?;
+        self.index.push((self.segment_start, self.offset));
+        self.offset += SEGMENT_HEADER_LEN + stored.len() as u64;
+        self.segment.clear();
+        Ok(())
+    }
+
+    /// Writes the open segment and the index.
+    pub fn finish(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        self.flush_segment()?;
+        let index_offset = self.offset;
+        self.file
+            .write_all(&(self.index.len() as u32).to_le_bytes())?;
+        for (t_ms, offset) in &self.index {
+            self.file.write_all(&t_ms.to_le_bytes())?;
+            self.file.write_all(&offset.to_le_bytes())?;
+        }
+        self.file.write_all(&index_offset.to_le_bytes())?;
+        self.file.write_all(INDEX_MAGIC)?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for ShowWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            eprintln!("show file error: {e:?}");
+        }
+    }
+}
+
+/// Reads records out of a decompressed segment.
+struct SegmentReader<'a> {
+    buf: &'a [u8],
+    at: usize,
+}
+
+impl<'a> SegmentReader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        let out = self
+            .buf
+            .get(self.at..self.at + n)
+            .ok_or_else(|| anyhow!("Truncated show file record"))?;
+        self.at += n;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.u8()?;
+            v |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(anyhow!("Bad varint in show file"))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let n = self.varint()? as usize;
+        if n > 512 {
+            return Err(anyhow!("Bad frame length {} in show file", n));
+        }
+        Ok(n)
+    }
+}
+
+/// A decoded record; `state` marks keyframe values that are not frames.
+struct Decoded {
+    state: bool,
+    frame: TimedFrame,
+}
+
+fn decode_segment(start_ms: u64, raw: &[u8]) -> Result<Vec<Decoded>> {
+    let mut r = SegmentReader { buf: raw, at: 0 };
+    let mut universes: BTreeMap<PortAddress, Vec<u8>> = BTreeMap::new();
+    let mut out = Vec::new();
+    let mut t_ms = start_ms;
+    while r.at < raw.len() {
+        let tag = r.u8()?;
+        t_ms = t_ms
+            .checked_add(r.varint()?)
+            .ok_or_else(|| anyhow!("Corrupt show file record time"))?;
+        let port_address = PortAddress::try_from(r.u16()?)?;
+        let values = match tag {
+            TAG_STATE | TAG_FULL => {
+                let n = r.len()?;
+                r.bytes(n)?.to_vec()
+            }
+            TAG_DELTA => {
+                let n = r.len()?;
+                let mut values = universes.get(&port_address).cloned().unwrap_or_default();
+                values.resize(n, 0);
+                let mut at = 0usize;
+                for _ in 0..r.varint()? {
+                    let bad_run = || anyhow!("Bad delta run in show file");
+                    let start = usize::try_from(r.varint()?)
+                        .ok()
+                        .and_then(|skip| at.checked_add(skip))
+                        .ok_or_else(bad_run)?;
+                    let end = usize::try_from(r.varint()?)
+                        .ok()
+                        .and_then(|len| start.checked_add(len))
+                        .ok_or_else(bad_run)?;
+                    values
+                        .get_mut(start..end)
+                        .ok_or_else(bad_run)?
+                        .copy_from_slice(r.bytes(end - start)?);
+                    at = end;
+                }
+                values
+            }
+            TAG_NZS => {
+                let start_code = r.u8()?;
+                let n = r.len()?;
+                let values = r.bytes(n)?.to_vec();
+                out.push(Decoded {
+                    state: false,
+                    frame: TimedFrame::Nzs(NzsRecord {
+                        t_ms,
+                        frame: NzsFrame {
+                            port_address,
+                            start_code,
+                            length: n as u16,
+                            sequence: 0,
+                            values,
+                        },
+                    }),
+                });
+                continue;
+            }
+            _ => return Err(anyhow!("Unknown show file record {}", tag)),
+        };
+        universes.insert(port_address, values.clone());
+        out.push(Decoded {
+            state: tag == TAG_STATE,
+            frame: TimedFrame::Dmx {
+                t_ms,
+                port_address,
+                values,
+            },
+        });
+    }
+    Ok(out)
+}
+
+/// An open show file with its segment index.
+pub struct ShowReader {
+    file: BufReader<File>,
+    compression: Compression,
+    /// Start time and file offset of each segment.
+    index: Vec<(u64, u64)>,
+}
+
+impl ShowReader {
+    pub fn open(path: &str) -> Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut header = [0u8; HEADER_LEN as usize];
+        file.read_exact(&mut header)
+            .map_err(|_| anyhow!("{} is not a show file", path))?;
+        if &header[0..8] != MAGIC {
+            return Err(anyhow!("{} is not a show file", path));
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version != VERSION {
+            return Err(anyhow!("Unsupported show file version {}", version));
+        }
+        let compression = Compression::from_code(header[10])?;
+        let index = match read_index(&mut file)? {
+            Some(index) => index,
+            None => scan_segments(&mut file)?,
+        };
+        Ok(Self {
+            file,
+            compression,
+            index,
+        })
+    }
+
+    fn segment(&mut self, i: usize) -> Result<Vec<Decoded>> {
+        let (start_ms, offset) = self.index[i];
+        self.file.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; SEGMENT_HEADER_LEN as usize];
+        self.file.read_exact(&mut header)?;
+        let stored_len = u32::from_le_bytes(header[0..4].try_into()?) as usize;
+        let raw_len = u32::from_le_bytes(header[4..8].try_into()?) as usize;
+        if stored_len > MAX_SEGMENT_LEN || raw_len > MAX_SEGMENT_LEN {
+            return Err(anyhow!("Corrupt show file segment"));
+        }
+        let mut stored = vec![0u8; stored_len];
+        self.file.read_exact(&mut stored)?;
+        let raw = self.compression.decompress(stored, raw_len)?;
+        decode_segment(start_ms, &raw)
+    }
+
+    /// Frames from `start_ms` on. Only the segment holding `start_ms` is
+    /// decoded to get there, and the values every universe has at that time
+    /// come first, so output resumes with the look of the show at `start_ms`.
+    pub fn frames_from(self, start_ms: u64) -> ShowFrames {
+        let first = self
+            .index
+            .partition_point(|(t, _)| *t <= start_ms)
+            .saturating_sub(1);
+        ShowFrames {
+            reader: self,
+            next_segment: first,
+            start_ms,
+            seeking: true,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// Reads the index through the trailer; `None` when it is missing or does
+/// not fit the file.
+fn read_index(file: &mut BufReader<File>) -> Result<Option<Vec<(u64, u64)>>> {
+    let len = file.seek(SeekFrom::End(0))?;
+    if len < HEADER_LEN + TRAILER_LEN + 4 {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::Start(len - TRAILER_LEN))?;
+    let mut trailer = [0u8; TRAILER_LEN as usize];
+    file.read_exact(&mut trailer)?;
+    if &trailer[8..16] != INDEX_MAGIC {
+        return Ok(None);
+    }
+    let offset = u64::from_le_bytes(trailer[0..8].try_into()?);
+    if offset < HEADER_LEN || offset > len - TRAILER_LEN - 4 {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::Start(offset))?;
+    let mut count = [0u8; 4];
+    file.read_exact(&mut count)?;
+    let count = u32::from_le_bytes(count) as u64;
+    if offset + 4 + count * 16 + TRAILER_LEN != len {
+        return Ok(None);
+    }
+    let mut index = Vec::with_capacity(count as usize);
+    let mut entry = [0u8; 16];
+    for _ in 0..count {
+        file.read_exact(&mut entry)?;
+        index.push((
+            u64::from_le_bytes(entry[0..8].try_into()?),
+            u64::from_le_bytes(entry[8..16].try_into()?),
+        ));
+    }
+    Ok(Some(index))
+}
+
+/// Rebuilds the index of an unfinished file, stopping at a truncated
+/// segment.
+fn scan_segments(file: &mut BufReader<File>) -> Result<Vec<(u64, u64)>> {
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut index = Vec::new();
+    let mut offset = HEADER_LEN;
+    let mut header = [0u8; SEGMENT_HEADER_LEN as usize];
+    while offset + SEGMENT_HEADER_LEN <= len {
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let stored_len = u32::from_le_bytes(header[0..4].try_into()?) as u64;
+        let end = offset + SEGMENT_HEADER_LEN + stored_len;
+        if end > len {
+            break;
+        }
+        index.push((u64::from_le_bytes(header[8..16].try_into()?), offset));
+        offset = end;
+    }
+    Ok(index)
+}
+
+/// Frames of a show file in time order, as returned by
+/// [`ShowReader::frames_from`].
+pub struct ShowFrames {
+    reader: ShowReader,
+    next_segment: usize,
+    start_ms: u64,
+    seeking: bool,
+    pending: VecDeque<TimedFrame>,
+}
+
+impl ShowFrames {
+    fn load_segment(&mut self) -> Result<()> {
+        let records = self.reader.segment(self.next_segment)?;
+        self.next_segment += 1;
+        if !std::mem::take(&mut self.seeking) {
+            self.pending
+                .extend(records.into_iter().filter(|d| !d.state).map(|d| d.frame));
+            return Ok(());
+        }
+        // Values each universe has reached by `start_ms`.
+        let mut look: BTreeMap<PortAddress, Vec<u8>> = BTreeMap::new();
+        let mut rest = Vec::new();
+        for d in records {
+            if d.frame.t_ms() >= self.start_ms && !d.state {
+                rest.push(d.frame);
+            } else if let TimedFrame::Dmx {
+                port_address,
+                values,
+                ..
+            } = d.frame
+            {
+                look.insert(port_address, values);
+            }
+        }
+        self.pending.extend(
+            look.into_iter()
+                .map(|(port_address, values)| TimedFrame::Dmx {
+                    t_ms: self.start_ms,
+                    port_address,
+                    values,
+                }),
+        );
+        self.pending.extend(rest);
+        Ok(())
+    }
+}
+
+impl Iterator for ShowFrames {
+    type Item = Result<TimedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if self.next_segment >= self.reader.index.len() {
+                return None;
+            }
+            if let Err(e) = self.load_segment() {
+                self.next_segment = self.reader.index.len();
+                return Some(Err(e));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// DMX frames of a show file from `start_ms` on, for playback.
+pub fn play_frames(path: &str, start_ms: u64) -> Result<impl Iterator<Item = Result<PlayFrame>>> {
+    Ok(ShowReader::open(path)?
+        .frames_from(start_ms)
+        .filter_map(|frame| match frame {
+            Ok(frame) => frame.play_frame().map(Ok),
+            Err(e) => Some(Err(e)),
+        }))
+}
+
+/// Loads a whole show file as a recording with a track per universe.
+pub fn load_record_data(path: &str) -> Result<RecordData> {
+    let mut frames = Vec::new();
+    let mut nzs = Vec::new();
+    for frame in ShowReader::open(path)?.frames_from(0) {
+        match frame? {
+            TimedFrame::Nzs(rec) => nzs.push(rec),
+            frame => frames.extend(frame.play_frame()),
+        }
+    }
+    if frames.is_empty() {
+        return Err(anyhow!("No DMX frames in {}", path));
+    }
+    Ok(recording::record_data_from_frames(&frames, nzs))
+}
+
+/// Writes a recording's tracks and alternate start code frames as a show
+/// file, in time order.
+pub fn write_record_data(
+    path: &str,
+    data: &RecordData,
+    compression: Compression,
+    keyframe_ms: u32,
+) -> Result<()> {
+    let mut writer = ShowWriter::create(path, compression, keyframe_ms)?;
+    let mut nzs = data.nzs.iter().peekable();
+    for (track, idx) in data.frames_in_order() {
+        let track = &data.tracks[track];
+        let t_ms = track.timestamps[idx];
+        while let Some(rec) = nzs.next_if(|r| r.t_ms <= t_ms) {
+            writer.write_nzs(rec.t_ms, &rec.frame)?;
+        }
+        let len = track.channels.iter().max().map_or(0, |ch| ch + 1);
+        let mut values = vec![0u8; len];
+        for (ch, value) in track.channels.iter().zip(track.frame_values(idx)) {
+            values[*ch] = value;
+        }
+        writer.write_dmx(t_ms, track.port_address, &values)?;
+    }
+    for rec in nzs {
+        writer.write_nzs(rec.t_ms, &rec.frame)?;
+    }
+    writer.finish()
+}
+
+/// Converts between JSONL and show files (or recompresses a show file),
+/// going by the extensions. Returns the number of frames written.
+pub fn convert(src: &str, dst: &str, compression: Compression, keyframe_ms: u32) -> Result<usize> {
+    let frames: Box<dyn Iterator<Item = Result<TimedFrame>>> = if is_show_file(src) {
+        Box::new(ShowReader::open(src)?.frames_from(0))
+    } else {
+        Box::new(recording::read_jsonl(src)?)
+    };
+    let mut count = 0;
+    if is_show_file(dst) {
+        let mut writer = ShowWriter::create(dst, compression, keyframe_ms)?;
+        for frame in frames {
+            writer.write(&frame?)?;
+            count += 1;
+        }
+        writer.finish()?;
+    } else {
+        let mut file = BufWriter::new(File::create(dst)?);
+        writeln!(
+            file,
+            "{}",
+            serde_json::json!({"format": "artnet-jsonl", "version": 1})
+        )?;
+        for frame in frames {
+            writeln!(file, "{}", frame?.jsonl_line())?;
+            count += 1;
+        }
+        file.flush()?;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Key = (u64, u16, Option<u8>, Vec<u8>);
+
+    fn key(frame: &TimedFrame) -> Key {
+        match frame {
+            TimedFrame::Dmx {
+                t_ms,
+                port_address,
+                values,
+            } => (*t_ms, u16::from(*port_address), None, values.clone()),
+            TimedFrame::Nzs(rec) => (
+                rec.t_ms,
+                u16::from(rec.frame.port_address),
+                Some(rec.frame.start_code),
+                rec.frame.values.clone(),
+            ),
+        }
+    }
+
+    /// Two universes over many segments: one whose length shrinks and grows
+    /// with a few changing channels, one mostly constant, plus NZS frames.
+    fn show_frames() -> Vec<TimedFrame> {
+        let a = PortAddress::try_from(1).unwrap();
+        let b = PortAddress::try_from(0x123).unwrap();
+        let mut frames = Vec::new();
+        for i in 0..150u64 {
+            let t_ms = i * 20;
+            let len = [512, 24, 300, 1, 512, 100][(i / 7) as usize % 6];
+            let values = (0..len)
+                .map(|ch| {
+                    if ch % 50 == (i % 50) as usize {
+                        i as u8
+                    } else {
+                        ch as u8
+                    }
+                })
+                .collect();
+            frames.push(TimedFrame::Dmx {
+                t_ms,
+                port_address: a,
+                values,
+            });
+            let mut values = vec![7u8; 512];
+            values[(i as usize * 13) % 512] = 200;
+            frames.push(TimedFrame::Dmx {
+                t_ms,
+                port_address: b,
+                values,
+            });
+            if i % 9 == 0 {
+                frames.push(TimedFrame::Nzs(NzsRecord {
+                    t_ms,
+                    frame: NzsFrame {
+                        port_address: a,
+                        start_code: 0x17,
+                        length: 3,
+                        sequence: 0,
+                        values: vec![b'h', b'i', i as u8],
+                    },
+                }));
+            }
+        }
+        frames
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("artnetlab-{}-{}.artrec", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn write_show(path: &str, compression: Compression, frames: &[TimedFrame]) {
+        let mut writer = ShowWriter::create(path, compression, 250).unwrap();
+        for frame in frames {
+            writer.write(frame).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn read_from(path: &str, start_ms: u64) -> Vec<Key> {
+        ShowReader::open(path)
+            .unwrap()
+            .frames_from(start_ms)
+            .map(|f| key(&f.unwrap()))
+            .collect()
+    }
+
+    /// What seeking to `start_ms` should give: each universe's values just
+    /// before it, then every frame from it on.
+    fn expected_from(frames: &[TimedFrame], start_ms: u64) -> Vec<Key> {
+        let mut look: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+        for frame in frames.iter().filter(|f| f.t_ms() < start_ms) {
+            if let TimedFrame::Dmx {
+                port_address,
+                values,
+                ..
+            } = frame
+            {
+                look.insert(u16::from(*port_address), values.clone());
+            }
+        }
+        look.into_iter()
+            .map(|(addr, values)| (start_ms, addr, None, values))
+            .chain(frames.iter().filter(|f| f.t_ms() >= start_ms).map(key))
+            .collect()
+    }
+
+    fn check(path: &str, frames: &[TimedFrame]) {
+        assert!(ShowReader::open(path).unwrap().index.len() > 4);
+        let all: Vec<Key> = frames.iter().map(key).collect();
+        assert_eq!(read_from(path, 0), all);
+        for start_ms in [1130, 1500, 2990] {
+            assert_eq!(read_from(path, start_ms), expected_from(frames, start_ms));
+        }
+    }
+
+    #[test]
+    fn round_trip_and_seek() {
+        let frames = show_frames();
+        for compression in [Compression::None, Compression::Zstd, Compression::Lz4] {
+            let path = temp_path(&format!("roundtrip-{:?}", compression));
+            write_show(&path, compression, &frames);
+            check(&path, &frames);
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn reads_without_trailer() {
+        let frames = show_frames();
+        let path = temp_path("no-trailer");
+        write_show(&path, Compression::Zstd, &frames);
+        let bytes = std::fs::read(&path).unwrap();
+        let trailer = &bytes[bytes.len() - TRAILER_LEN as usize..];
+        let index_offset = u64::from_le_bytes(trailer[0..8].try_into().unwrap()) as usize;
+
+        // As left by a crash before the index was written.
+        std::fs::write(&path, &bytes[..index_offset]).unwrap();
+        check(&path, &frames);
+
+        // A segment cut short is dropped along with what follows it.
+        std::fs::write(&path, &bytes[..index_offset - 5]).unwrap();
+        let read = read_from(&path, 0);
+        let all: Vec<Key> = frames.iter().map(key).collect();
+        assert!(!read.is_empty() && read.len() < all.len());
+        assert_eq!(read[..], all[..read.len()]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_corrupt_delta_runs() {
+        let mut raw = vec![TAG_DELTA];
+        put_varint(&mut raw, 0);
+        raw.extend_from_slice(&1u16.to_le_bytes());
+        put_varint(&mut raw, 4);
+        put_varint(&mut raw, 1);
+        put_varint(&mut raw, u64::MAX);
+        put_varint(&mut raw, 1);
+        raw.push(9);
+        assert!(decode_segment(0, &raw).is_err());
+
+        let mut raw = vec![TAG_FULL];
+        put_varint(&mut raw, u64::MAX);
+        raw.extend_from_slice(&1u16.to_le_bytes());
+        put_varint(&mut raw, 0);
+        assert!(decode_segment(1, &raw).is_err());
+    }
+}
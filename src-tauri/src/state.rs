@@ -1,19 +1,34 @@
-use std::net::SocketAddr;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::{Arc, Mutex};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use tokio::{
     net::UdpSocket,
-    sync::mpsc,
+    sync::{mpsc, watch},
     task::JoinHandle,
     time::{sleep, Duration, Instant},
 };
 
-use crate::artnet::{self, ReceiverConfig, SenderConfig};
-use serde::Serialize;
-use tauri::{AppHandle, Emitter};
+use crate::artnet::{self, PortAddress, ReceiverConfig, SenderConfig};
+use crate::discovery::{self, NodeTable, TrackedNode};
+use crate::events::EventSink;
+use crate::inspector::{self, CaptureStatus, CapturedPacket, InspectedPacket, PacketCapture};
+use crate::merge::{MergeOutcome, Merger, SequenceFilter, SourceKey};
+use crate::node::{self, NodeConfig};
+use crate::preroll::{PreRoll, PreRollCapture, PreRollCaptureInfo, PreRollConfig, PreRollStatus};
+use crate::rdm;
+use crate::recording::TimedFrame;
+use crate::remote::{self, AddressOutcome, AddressRequest, IpProgOutcome, IpProgRequest};
+use crate::sacn::{self, OutputProtocol, SacnConfig, SacnReceiverConfig};
+use crate::stats::{ReceiveStats, UniverseStatsInfo};
+use crate::timecode::{self, Timecode, TimecodeConfig, TimecodeEvent};
+use crate::trigger::{self, TriggerAction, TriggerActionEvent, TriggerMapping};
+use serde::{Deserialize, Serialize};
 
 const MAX_RECORD_FRAMES: usize = 200_000;
+const STATS_EVENT_INTERVAL: Duration = Duration::from_secs(1);
+const PREROLL_TICK: Duration = Duration::from_millis(50);
 
 #[derive(Clone, Copy, Default, PartialEq)]
 pub enum AnimKind {
@@ -123,13 +138,56 @@ pub fn sanitize_animation_modes(
 
 #[derive(Clone)]
 pub struct RecordData {
+    /// One track per universe, in the order they were first seen.
+    pub tracks: Vec<RecordTrack>,
+    /// Alternate start code frames, kept apart from the DMX channels.
+    pub nzs: Vec<NzsRecord>,
+}
+
+/// Frames of one universe in a recording, with its own timeline and channel
+/// selection. Timestamps of every track share the recording's start.
+#[derive(Clone)]
+pub struct RecordTrack {
+    pub port_address: PortAddress,
     pub timestamps: Vec<u64>,
-    pub addresses: Vec<(u8, u8, u8)>,
     pub channels: Vec<usize>,
     pub values: Vec<Vec<u8>>,
 }
 
-impl RecordData {
+/// Summary of a recording track, as reported to the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordTrackInfo {
+    pub port_address: PortAddress,
+    pub channels: Vec<u16>,
+    pub frames: usize,
+    pub duration_ms: u64,
+}
+
+/// An ArtNzs (or sACN alternate start code) frame in a recording.
+#[derive(Debug, Clone, Serialize)]
+pub struct NzsRecord {
+    pub t_ms: u64,
+    #[serde(flatten)]
+    pub frame: artnet::NzsFrame,
+}
+
+/// What the receiver hands to a running file recorder.
+pub enum RecordedFrame {
+    Dmx(artnet::DmxFrame),
+    Nzs(artnet::NzsFrame),
+}
+
+impl RecordTrack {
+    pub fn new(port_address: PortAddress, channels: Vec<usize>) -> Self {
+        let values = channels.iter().map(|_| Vec::new()).collect();
+        Self {
+            port_address,
+            timestamps: Vec::new(),
+            channels,
+            values,
+        }
+    }
+
     pub fn frame_count(&self) -> usize {
         self.timestamps.len()
     }
@@ -142,55 +200,31 @@ impl RecordData {
         self.channels.iter().map(|c| (*c + 1) as u16).collect()
     }
 
-    pub fn last_address(&self) -> Option<(u8, u8, u8)> {
-        self.addresses.last().copied()
+    pub fn info(&self) -> RecordTrackInfo {
+        RecordTrackInfo {
+            port_address: self.port_address,
+            channels: self.channel_numbers(),
+            frames: self.frame_count(),
+            duration_ms: self.duration_ms(),
+        }
     }
-}
-
-#[derive(Clone, Serialize)]
-pub struct PreviewPoint {
-    pub t_ms: u64,
-    pub value: u8,
-}
-
-#[derive(Clone, Serialize)]
-pub struct PreviewResponse {
-    pub points: Vec<PreviewPoint>,
-    pub frame_count: usize,
-    pub duration_ms: u64,
-}
-
-struct RecordBuffer {
-    channels: Vec<usize>,
-    timestamps: Vec<u64>,
-    values: Vec<Vec<u8>>,
-    addresses: Vec<(u8, u8, u8)>,
-    start: Instant,
-    active: bool,
-}
 
-impl RecordBuffer {
-    fn new(channels: Vec<usize>, active: bool) -> Self {
-        let values = channels.iter().map(|_| Vec::new()).collect();
-        Self {
-            channels,
-            timestamps: Vec::new(),
-            values,
-            addresses: Vec::new(),
-            start: Instant::now(),
-            active,
+    /// Appends a frame, taking the selected channels out of a full universe.
+    pub fn push(&mut self, t_ms: u64, dmx: &[u8]) {
+        self.timestamps.push(t_ms);
+        for (idx, ch) in self.channels.iter().enumerate() {
+            if let Some(vec) = self.values.get_mut(idx) {
+                vec.push(dmx.get(*ch).copied().unwrap_or(0));
+            }
         }
     }
 
-    fn from_data(data: RecordData, active: bool) -> Self {
-        Self {
-            channels: data.channels,
-            timestamps: data.timestamps,
-            values: data.values,
-            addresses: data.addresses,
-            start: Instant::now(),
-            active,
-        }
+    /// Value of every selected channel in frame `idx`.
+    pub fn frame_values(&self, idx: usize) -> Vec<u8> {
+        self.values
+            .iter()
+            .map(|channel| channel.get(idx).copied().unwrap_or(0))
+            .collect()
     }
 
     fn set_channels(&mut self, channels: Vec<usize>) {
@@ -207,27 +241,6 @@ impl RecordBuffer {
         self.values = new_values;
     }
 
-    fn append(&mut self, frame: &artnet::DmxFrame) {
-        if !self.active {
-            return;
-        }
-        let elapsed = self.start.elapsed().as_millis() as u64;
-        self.timestamps.push(elapsed);
-        self.addresses
-            .push((frame.net, frame.subnet, frame.universe));
-        for (idx, ch) in self.channels.iter().enumerate() {
-            let value = if *ch < frame.values.len() {
-                frame.values[*ch]
-            } else {
-                0
-            };
-            if let Some(vec) = self.values.get_mut(idx) {
-                vec.push(value);
-            }
-        }
-        self.enforce_limit();
-    }
-
     fn preview(&self, channel: usize, max_points: usize) -> Option<PreviewResponse> {
         let idx = self.channels.iter().position(|c| *c == channel)?;
         let values = self.values.get(idx)?;
@@ -283,37 +296,389 @@ impl RecordBuffer {
         })
     }
 
-    fn to_record_data(&self) -> RecordData {
-        RecordData {
-            timestamps: self.timestamps.clone(),
-            addresses: self.addresses.clone(),
-            channels: self.channels.clone(),
-            values: self.values.clone(),
-        }
+    /// Drops the oldest frames beyond the memory limit; returns how many.
+    fn enforce_limit(&mut self) -> usize {
+        let excess = self.timestamps.len().saturating_sub(MAX_RECORD_FRAMES);
+        self.drop_oldest(excess);
+        excess
     }
 
-    fn duration_ms(&self) -> u64 {
-        self.timestamps.last().copied().unwrap_or(0)
+    /// Drops frames more than `window_ms` older than the latest. Waits until
+    /// a tenth of a window has piled up so the drain runs only now and then.
+    fn trim_window(&mut self, window_ms: u64) {
+        let (Some(&first), Some(&last)) = (self.timestamps.first(), self.timestamps.last()) else {
+            return;
+        };
+        if last - first <= window_ms + window_ms / 10 {
+            return;
+        }
+        let cutoff = last - window_ms;
+        self.drop_oldest(self.timestamps.partition_point(|t| *t < cutoff));
     }
 
-    fn enforce_limit(&mut self) {
-        if self.timestamps.len() <= MAX_RECORD_FRAMES {
+    fn drop_oldest(&mut self, n: usize) {
+        if n == 0 {
             return;
         }
-        let drop = self.timestamps.len() - MAX_RECORD_FRAMES;
-        self.timestamps.drain(0..drop);
-        self.addresses.drain(0..drop);
+        self.timestamps.drain(0..n);
         for values in self.values.iter_mut() {
-            if values.len() > drop {
-                values.drain(0..drop);
-            } else {
-                values.clear();
+            values.drain(0..n.min(values.len()));
+        }
+    }
+}
+
+impl RecordData {
+    pub fn frame_count(&self) -> usize {
+        self.tracks.iter().map(RecordTrack::frame_count).sum()
+    }
+
+    pub fn duration_ms(&self) -> u64 {
+        self.tracks
+            .iter()
+            .map(RecordTrack::duration_ms)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Every channel recorded in any track.
+    pub fn channel_numbers(&self) -> Vec<u16> {
+        let mut numbers: Vec<u16> = self
+            .tracks
+            .iter()
+            .flat_map(RecordTrack::channel_numbers)
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers
+    }
+
+    /// Universe of the most recent frame.
+    pub fn last_address(&self) -> Option<PortAddress> {
+        self.tracks
+            .iter()
+            .filter(|t| t.frame_count() > 0)
+            .max_by_key(|t| t.duration_ms())
+            .map(|t| t.port_address)
+    }
+
+    pub fn track(&self, addr: PortAddress) -> Option<&RecordTrack> {
+        self.tracks.iter().find(|t| t.port_address == addr)
+    }
+
+    /// Track of `addr`, created with `channels` when the universe is new.
+    pub fn track_mut(&mut self, addr: PortAddress, channels: &[usize]) -> &mut RecordTrack {
+        let idx = match self.tracks.iter().position(|t| t.port_address == addr) {
+            Some(idx) => idx,
+            None => {
+                self.tracks.push(RecordTrack::new(addr, channels.to_vec()));
+                self.tracks.len() - 1
+            }
+        };
+        &mut self.tracks[idx]
+    }
+
+    pub fn track_infos(&self) -> Vec<RecordTrackInfo> {
+        self.tracks.iter().map(RecordTrack::info).collect()
+    }
+
+    /// `(track, frame)` indices of every frame, merged into time order.
+    pub fn frames_in_order(&self) -> Vec<(usize, usize)> {
+        let mut order: Vec<(u64, usize, usize)> = self
+            .tracks
+            .iter()
+            .enumerate()
+            .flat_map(|(t, track)| {
+                track
+                    .timestamps
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, ts)| (*ts, t, i))
+            })
+            .collect();
+        order.sort_by_key(|(ts, _, _)| *ts);
+        order.into_iter().map(|(_, t, i)| (t, i)).collect()
+    }
+
+    /// Track to export on its own, e.g. as WAV: `addr`, or the only one.
+    pub fn single_track(&self, addr: Option<PortAddress>) -> Result<&RecordTrack> {
+        match addr {
+            Some(addr) => self
+                .track(addr)
+                .ok_or_else(|| anyhow!("Universe {} is not in the recording", addr)),
+            None => match self.tracks.as_slice() {
+                [track] => Ok(track),
+                [] => Err(anyhow!("No recorded frames")),
+                tracks => Err(anyhow!(
+                    "Recording has {} universes ({}); choose one",
+                    tracks.len(),
+                    tracks
+                        .iter()
+                        .map(|t| t.port_address.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct PreviewPoint {
+    pub t_ms: u64,
+    pub value: u8,
+}
+
+#[derive(Clone, Serialize)]
+pub struct PreviewResponse {
+    pub points: Vec<PreviewPoint>,
+    pub frame_count: usize,
+    pub duration_ms: u64,
+}
+
+/// Streams the buffered recording to a show file as it comes in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpillConfig {
+    pub path: String,
+    #[serde(default)]
+    pub compression: crate::show::Compression,
+    /// Seconds of recent frames kept in memory for previews.
+    #[serde(default = "default_spill_window_sec")]
+    pub window_sec: u64,
+}
+
+/// Seconds of a spilled recording kept in memory unless chosen otherwise.
+pub const DEFAULT_SPILL_WINDOW_SEC: u64 = 300;
+
+fn default_spill_window_sec() -> u64 {
+    DEFAULT_SPILL_WINDOW_SEC
+}
+
+struct Spill {
+    path: String,
+    /// Frames for the writer task; `None` once the recording stopped or
+    /// the writer failed.
+    tx: Option<mpsc::UnboundedSender<TimedFrame>>,
+    task: Option<JoinHandle<()>>,
+    window_ms: u64,
+    progress: Arc<Mutex<SpillProgress>>,
+}
+
+/// What the spill writer task reports back.
+#[derive(Default)]
+struct SpillProgress {
+    frames: usize,
+    error: Option<String>,
+}
+
+/// State of the buffered recorder, as reported to the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordStatus {
+    pub active: bool,
+    /// Frames held in memory.
+    pub frames: usize,
+    pub duration_ms: u64,
+    /// Frames lost to the memory limit with no copy on disk.
+    pub dropped_frames: usize,
+    pub spill_path: Option<String>,
+    pub spilled_frames: usize,
+    pub spill_error: Option<String>,
+}
+
+struct RecordBuffer {
+    /// Universes to capture; empty captures every universe.
+    universes: Vec<PortAddress>,
+    /// Channels of tracks created from now on.
+    channels: Vec<usize>,
+    data: RecordData,
+    start: Instant,
+    active: bool,
+    /// With a spill file memory only holds the recent window; without one
+    /// the oldest frames are dropped at `MAX_RECORD_FRAMES`.
+    spill: Option<Spill>,
+    dropped: usize,
+}
+
+impl RecordBuffer {
+    fn new(universes: Vec<PortAddress>, channels: Vec<usize>, active: bool) -> Self {
+        Self {
+            universes,
+            channels,
+            data: RecordData {
+                tracks: Vec::new(),
+                nzs: Vec::new(),
+            },
+            start: Instant::now(),
+            active,
+            spill: None,
+            dropped: 0,
+        }
+    }
+
+    fn from_data(data: RecordData, active: bool) -> Self {
+        Self {
+            universes: Vec::new(),
+            channels: data
+                .tracks
+                .first()
+                .map(|t| t.channels.clone())
+                .unwrap_or_else(|| (0..512).collect()),
+            data,
+            start: Instant::now(),
+            active,
+            spill: None,
+            dropped: 0,
+        }
+    }
+
+    fn with_spill(mut self, cfg: SpillConfig) -> Result<Self> {
+        if !crate::show::is_show_file(&cfg.path) {
+            return Err(anyhow!("Spill file must be an .artrec show file"));
+        }
+        let writer = crate::show::ShowWriter::create(
+            &cfg.path,
+            cfg.compression,
+            crate::show::DEFAULT_KEYFRAME_MS,
+        )?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let progress = Arc::new(Mutex::new(SpillProgress::default()));
+        let task = tokio::spawn(run_spill_task(writer, rx, progress.clone()));
+        self.spill = Some(Spill {
+            path: cfg.path,
+            tx: Some(tx),
+            task: Some(task),
+            window_ms: cfg.window_sec.max(1) * 1000,
+            progress,
+        });
+        Ok(self)
+    }
+
+    fn captures(&self, addr: PortAddress) -> bool {
+        self.active && (self.universes.is_empty() || self.universes.contains(&addr))
+    }
+
+    /// Selects the channels of one track, or of every track (and of tracks
+    /// yet to come) when `addr` is `None`.
+    fn set_channels(&mut self, addr: Option<PortAddress>, channels: Vec<usize>) {
+        match addr {
+            Some(addr) => self.data.track_mut(addr, &channels).set_channels(channels),
+            None => {
+                for track in &mut self.data.tracks {
+                    track.set_channels(channels.clone());
+                }
+                self.channels = channels;
+            }
+        }
+    }
+
+    /// Hands a frame to the spill writer. Returns the memory window while
+    /// the writer takes frames; once it failed memory keeps everything again.
+    fn spill(&mut self, frame: impl FnOnce() -> TimedFrame) -> Option<u64> {
+        let spill = self.spill.as_mut()?;
+        if spill.tx.as_ref()?.send(frame()).is_err() {
+            spill.tx = None;
+            return None;
+        }
+        Some(spill.window_ms)
+    }
+
+    /// Records a frame; true when it is the first time the memory limit
+    /// dropped data that is not on disk.
+    fn append(&mut self, frame: &artnet::DmxFrame) -> bool {
+        if !self.captures(frame.port_address) {
+            return false;
+        }
+        let elapsed = self.start.elapsed().as_millis() as u64;
+        let window = self.spill(|| TimedFrame::Dmx {
+            t_ms: elapsed,
+            port_address: frame.port_address,
+            values: frame.values.clone(),
+        });
+        let track = self.data.track_mut(frame.port_address, &self.channels);
+        track.push(elapsed, &frame.values);
+        if let Some(window_ms) = window {
+            track.trim_window(window_ms);
+            track.enforce_limit();
+            return false;
+        }
+        let dropped = track.enforce_limit();
+        self.count_dropped(dropped)
+    }
+
+    fn append_nzs(&mut self, frame: &artnet::NzsFrame) -> bool {
+        if !self.captures(frame.port_address) {
+            return false;
+        }
+        let t_ms = self.start.elapsed().as_millis() as u64;
+        let window = self.spill(|| {
+            TimedFrame::Nzs(NzsRecord {
+                t_ms,
+                frame: frame.clone(),
+            })
+        });
+        let nzs = &mut self.data.nzs;
+        nzs.push(NzsRecord {
+            t_ms,
+            frame: frame.clone(),
+        });
+        let mut dropped = nzs.len().saturating_sub(MAX_RECORD_FRAMES);
+        if let Some(window_ms) = window {
+            let cutoff = t_ms.saturating_sub(window_ms);
+            dropped = dropped.max(nzs.partition_point(|r| r.t_ms < cutoff));
+        }
+        nzs.drain(0..dropped);
+        window.is_none() && self.count_dropped(dropped)
+    }
+
+    fn count_dropped(&mut self, dropped: usize) -> bool {
+        let first = self.dropped == 0 && dropped > 0;
+        self.dropped += dropped;
+        first
+    }
+
+    /// Stops capturing. Returns the spill writer task, which completes the
+    /// file once it has written the frames still queued.
+    fn stop(&mut self) -> Option<JoinHandle<()>> {
+        self.active = false;
+        let spill = self.spill.as_mut()?;
+        spill.tx = None;
+        spill.task.take()
+    }
+
+    fn status(&self) -> RecordStatus {
+        let (spilled_frames, spill_error) = match &self.spill {
+            Some(spill) => {
+                let progress = spill.progress.lock().unwrap();
+                (progress.frames, progress.error.clone())
             }
+            None => (0, None),
+        };
+        RecordStatus {
+            active: self.active,
+            frames: self.data.frame_count(),
+            duration_ms: self.data.duration_ms(),
+            dropped_frames: self.dropped,
+            spill_path: self.spill.as_ref().map(|s| s.path.clone()),
+            spilled_frames,
+            spill_error,
         }
     }
+
+    /// Preview of a channel in the track of `addr`, or in the first track.
+    fn preview(
+        &self,
+        addr: Option<PortAddress>,
+        channel: usize,
+        max_points: usize,
+    ) -> Option<PreviewResponse> {
+        let track = match addr {
+            Some(addr) => self.data.track(addr)?,
+            None => self.data.tracks.first()?,
+        };
+        track.preview(channel, max_points)
+    }
 }
 
-fn normalize_channels(channels: Vec<usize>) -> Vec<usize> {
+pub fn normalize_channels(channels: Vec<usize>) -> Vec<usize> {
     let mut seen = [false; 512];
     let mut result = Vec::with_capacity(channels.len().min(512));
     for ch in channels {
@@ -325,34 +690,178 @@ fn normalize_channels(channels: Vec<usize>) -> Vec<usize> {
     result
 }
 
+/// One output universe of the sender with its own buffer and sequence counter.
+#[derive(Clone)]
+struct UniverseOutput {
+    channels: [u8; 512],
+    target_ip: Option<String>,
+    enabled: bool,
+    protocol: OutputProtocol,
+    /// Non-zero sends ArtNzs (or sACN with this start code) instead of DMX.
+    start_code: u8,
+    sequence: u8,
+}
+
+impl UniverseOutput {
+    fn new(target_ip: Option<String>) -> Self {
+        Self {
+            channels: [0; 512],
+            target_ip,
+            enabled: true,
+            protocol: OutputProtocol::ArtNet,
+            start_code: 0,
+            sequence: 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct UniverseInfo {
+    pub port_address: PortAddress,
+    pub label: String,
+    pub target_ip: Option<String>,
+    pub enabled: bool,
+    pub protocol: OutputProtocol,
+    pub start_code: u8,
+    pub selected: bool,
+    /// Nodes receiving the universe by unicast; empty while broadcasting.
+    pub subscribers: Vec<String>,
+}
+
+/// Channel values of one universe, as kept in a snapshot.
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct UniverseValues {
+    pub port_address: PortAddress,
+    pub values: Vec<u8>,
+}
+
+/// A universe buffer captured for one sender tick.
+pub struct UniverseFrame {
+    pub port_address: PortAddress,
+    pub target_ip: Option<String>,
+    pub protocol: OutputProtocol,
+    pub start_code: u8,
+    pub data: [u8; 512],
+    pub sequence: u8,
+    /// Nodes subscribed to the universe when unicast sending is on.
+    pub subscribers: Vec<String>,
+}
+
+impl UniverseFrame {
+    /// Sender configuration addressed to this universe, falling back to the
+    /// global target IP when the universe has none of its own.
+    pub fn sender_config(&self, base: &SenderConfig) -> SenderConfig {
+        let mut cfg = base.clone();
+        cfg.port_address = self.port_address;
+        if let Some(ip) = &self.target_ip {
+            cfg.target_ip = ip.clone();
+        }
+        cfg
+    }
+
+    /// One sender configuration per subscribed node, or the broadcast one
+    /// from `sender_config` while no subscriber is known.
+    pub fn sender_configs(&self, base: &SenderConfig) -> Vec<SenderConfig> {
+        let cfg = self.sender_config(base);
+        if self.subscribers.is_empty() {
+            return vec![cfg];
+        }
+        self.subscribers
+            .iter()
+            .map(|ip| SenderConfig {
+                target_ip: ip.clone(),
+                ..cfg.clone()
+            })
+            .collect()
+    }
+}
+
+/// Subscribers of an Art-Net universe that has no target IP of its own,
+/// when the sender is in unicast mode.
+fn unicast_subscribers(
+    cfg: &SenderConfig,
+    nodes: &NodeTable,
+    addr: PortAddress,
+    out: &UniverseOutput,
+) -> Vec<String> {
+    if cfg.unicast_subscribers && out.target_ip.is_none() && out.protocol == OutputProtocol::ArtNet
+    {
+        nodes.subscribers(addr)
+    } else {
+        Vec::new()
+    }
+}
+
 #[derive(Clone)]
 pub struct AppState {
     inner: Arc<Mutex<Inner>>,
     shared_udp: Arc<tokio::sync::Mutex<Option<Arc<UdpSocket>>>>,
-    discovery_poll_tx: Arc<tokio::sync::Mutex<Option<mpsc::Sender<(SocketAddr, Vec<u8>)>>>>,
+    reply_relay: discovery::ReplyRelay,
+    timecode_in: watch::Sender<Option<ReceivedTimecode>>,
+    capture: Arc<Mutex<PacketCapture>>,
+}
+
+/// Latest ArtTimeCode seen by the receiver.
+#[derive(Clone, Copy)]
+struct ReceivedTimecode {
+    timecode: Timecode,
+    at: Instant,
 }
 
 struct Inner {
     // Receiver
     recv_cfg: ReceiverConfig,
     recv_task: Option<JoinHandle<()>>,
+    sacn_recv_cfg: SacnReceiverConfig,
+    sacn_recv_task: Option<JoinHandle<()>>,
+    // Node identity (ArtPollReply)
+    node_cfg: NodeConfig,
     // Sender
     send_cfg: SenderConfig,
+    sacn_cfg: SacnConfig,
     send_task: Option<JoinHandle<()>>,
+    timecode_cfg: TimecodeConfig,
+    timecode_task: Option<JoinHandle<()>>,
     discovery_interval_sec: u64,
-    channels: [u8; 512],
-    sequence: u8,
+    universes: BTreeMap<PortAddress, UniverseOutput>,
+    selected_universe: PortAddress,
     // Recording
-    record_tx: Option<mpsc::UnboundedSender<crate::artnet::DmxFrame>>,
+    record_tx: Option<mpsc::UnboundedSender<RecordedFrame>>,
     record_task: Option<JoinHandle<()>>,
     record_buffer: Option<RecordBuffer>,
+    preroll: Option<PreRoll>,
+    preroll_task: Option<JoinHandle<()>>,
     // Playback
     play_task: Option<JoinHandle<()>>,
     // Animation
     animation_state: AnimationState,
     animation_task: Option<JoinHandle<()>>,
+    // Remote triggers and snapshots
+    trigger_mappings: Vec<TriggerMapping>,
+    snapshots: BTreeMap<String, Vec<UniverseValues>>,
     // Event filter
-    event_filter: Option<(u8, u8, u8)>,
+    event_filter: Option<PortAddress>,
+    sync_mode: SyncModeEvent,
+    // Discovery
+    discovery_task: Option<JoinHandle<()>>,
+    nodes: NodeTable,
+    rdm_devices: rdm::TodTable,
+    receive_stats: ReceiveStats,
+}
+
+impl Inner {
+    fn selected_mut(&mut self) -> &mut UniverseOutput {
+        let addr = self.selected_universe;
+        self.universes
+            .entry(addr)
+            .or_insert_with(|| UniverseOutput::new(None))
+    }
+
+    /// Selects a universe and mirrors its address into the sender config.
+    fn select(&mut self, addr: PortAddress) {
+        self.selected_universe = addr;
+        self.send_cfg.port_address = addr;
+    }
 }
 
 impl Default for AppState {
@@ -361,21 +870,38 @@ impl Default for AppState {
             inner: Arc::new(Mutex::new(Inner {
                 recv_cfg: ReceiverConfig::default(),
                 recv_task: None,
+                sacn_recv_cfg: SacnReceiverConfig::default(),
+                sacn_recv_task: None,
+                node_cfg: NodeConfig::default(),
                 send_cfg: SenderConfig::default(),
+                sacn_cfg: SacnConfig::default(),
                 send_task: None,
+                timecode_cfg: TimecodeConfig::default(),
+                timecode_task: None,
                 discovery_interval_sec: 10,
-                channels: [0; 512],
-                sequence: 0,
+                universes: BTreeMap::from([(PortAddress::default(), UniverseOutput::new(None))]),
+                selected_universe: PortAddress::default(),
                 record_tx: None,
                 record_task: None,
                 record_buffer: None,
+                preroll: None,
+                preroll_task: None,
                 play_task: None,
                 animation_state: AnimationState::default(),
                 animation_task: None,
+                trigger_mappings: Vec::new(),
+                snapshots: BTreeMap::new(),
                 event_filter: None,
+                sync_mode: SyncModeEvent::default(),
+                discovery_task: None,
+                nodes: NodeTable::default(),
+                rdm_devices: rdm::TodTable::default(),
+                receive_stats: ReceiveStats::default(),
             })),
             shared_udp: Arc::new(tokio::sync::Mutex::new(None)),
-            discovery_poll_tx: Arc::new(tokio::sync::Mutex::new(None)),
+            reply_relay: tokio::sync::broadcast::channel(256).0,
+            timecode_in: watch::channel(None).0,
+            capture: Arc::new(Mutex::new(PacketCapture::default())),
         }
     }
 }
@@ -385,13 +911,6 @@ impl AppState {
         Self::default()
     }
 
-    pub async fn set_discovery_poll_reply_tx(
-        &self,
-        tx: Option<mpsc::Sender<(SocketAddr, Vec<u8>)>>,
-    ) {
-        *self.discovery_poll_tx.lock().await = tx;
-    }
-
     pub async fn udp_for_send(&self) -> Result<Arc<UdpSocket>> {
         let mut g = self.shared_udp.lock().await;
         if g.is_none() {
@@ -400,11 +919,74 @@ impl AppState {
         Ok(g.as_ref().unwrap().clone())
     }
 
-    pub fn snapshot_channels_tick_seq(&self) -> ([u8; 512], u8) {
+    /// Captures the selected universe and advances its sequence counter.
+    pub fn snapshot_channels_tick_seq(&self) -> UniverseFrame {
+        let mut g = self.inner.lock().unwrap();
+        let addr = g.selected_universe;
+        let subscribers = match g.universes.get(&addr) {
+            Some(out) => unicast_subscribers(&g.send_cfg, &g.nodes, addr, out),
+            None => Vec::new(),
+        };
+        let out = g.selected_mut();
+        out.sequence = artnet::next_sequence(out.sequence);
+        UniverseFrame {
+            port_address: addr,
+            target_ip: out.target_ip.clone(),
+            protocol: out.protocol,
+            start_code: out.start_code,
+            data: out.channels,
+            sequence: out.sequence,
+            subscribers,
+        }
+    }
+
+    /// Captures every enabled universe for one sender tick.
+    pub fn snapshot_universes_tick_seq(&self) -> Vec<UniverseFrame> {
+        let mut g = self.inner.lock().unwrap();
+        let g = &mut *g;
+        g.universes
+            .iter_mut()
+            .filter(|(_, out)| out.enabled)
+            .map(|(addr, out)| {
+                out.sequence = artnet::next_sequence(out.sequence);
+                UniverseFrame {
+                    port_address: *addr,
+                    target_ip: out.target_ip.clone(),
+                    protocol: out.protocol,
+                    start_code: out.start_code,
+                    data: out.channels,
+                    sequence: out.sequence,
+                    subscribers: unicast_subscribers(&g.send_cfg, &g.nodes, *addr, out),
+                }
+            })
+            .collect()
+    }
+
+    /// Enabled sACN universes as they stand, without advancing their
+    /// sequence numbers.
+    pub fn sacn_universes(&self) -> Vec<UniverseFrame> {
+        let g = self.inner.lock().unwrap();
+        g.universes
+            .iter()
+            .filter(|(_, out)| out.enabled && out.protocol == OutputProtocol::Sacn)
+            .map(|(addr, out)| UniverseFrame {
+                port_address: *addr,
+                target_ip: out.target_ip.clone(),
+                protocol: out.protocol,
+                start_code: out.start_code,
+                data: out.channels,
+                sequence: out.sequence,
+                subscribers: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Advances the sequence number of one universe, returning the new one.
+    pub fn next_universe_sequence(&self, addr: PortAddress) -> Option<u8> {
         let mut g = self.inner.lock().unwrap();
-        let data = g.channels;
-        g.sequence = g.sequence.wrapping_add(1);
-        (data, g.sequence)
+        let out = g.universes.get_mut(&addr)?;
+        out.sequence = artnet::next_sequence(out.sequence);
+        Some(out.sequence)
     }
 
     pub fn get_receiver_config(&self) -> ReceiverConfig {
@@ -414,11 +996,46 @@ impl AppState {
         self.inner.lock().unwrap().recv_cfg = cfg;
     }
 
+    pub fn get_sacn_receiver_config(&self) -> SacnReceiverConfig {
+        self.inner.lock().unwrap().sacn_recv_cfg.clone()
+    }
+    pub fn set_sacn_receiver_config(&self, cfg: SacnReceiverConfig) {
+        self.inner.lock().unwrap().sacn_recv_cfg = cfg;
+    }
+
     pub fn get_sender_config(&self) -> SenderConfig {
         self.inner.lock().unwrap().send_cfg.clone()
     }
+    /// Updates the sender configuration. The selected universe follows the
+    /// configured net/subnet/universe, keeping its channel buffer.
     pub fn set_sender_config(&self, cfg: SenderConfig) {
-        self.inner.lock().unwrap().send_cfg = cfg;
+        let mut g = self.inner.lock().unwrap();
+        let addr = cfg.port_address;
+        if addr != g.selected_universe && !g.universes.contains_key(&addr) {
+            let prev = g.selected_universe;
+            if let Some(out) = g.universes.remove(&prev) {
+                g.universes.insert(addr, out);
+            }
+        }
+        g.universes
+            .entry(addr)
+            .or_insert_with(|| UniverseOutput::new(None));
+        g.selected_universe = addr;
+        g.send_cfg = cfg;
+    }
+
+    pub fn get_node_config(&self) -> NodeConfig {
+        self.inner.lock().unwrap().node_cfg.clone()
+    }
+    pub fn set_node_config(&self, cfg: NodeConfig) {
+        self.inner.lock().unwrap().node_cfg = cfg;
+    }
+
+    pub fn get_sacn_config(&self) -> SacnConfig {
+        self.inner.lock().unwrap().sacn_cfg.clone()
+    }
+    pub fn set_sacn_config(&self, cfg: SacnConfig) {
+        self.inner.lock().unwrap().sacn_cfg = cfg;
     }
 
     pub fn get_discovery_interval_sec(&self) -> u64 {
@@ -430,74 +1047,429 @@ impl AppState {
     }
 
     pub fn snapshot_channels(&self) -> [u8; 512] {
-        self.inner.lock().unwrap().channels
+        let mut g = self.inner.lock().unwrap();
+        g.selected_mut().channels
     }
 
     pub fn set_channel(&self, index: usize, value: u8) {
-        self.inner.lock().unwrap().channels[index] = value;
+        self.inner.lock().unwrap().selected_mut().channels[index] = value;
     }
     pub fn set_channels(&self, values: &[u8]) {
-        self.inner.lock().unwrap().channels.copy_from_slice(values);
+        self.inner
+            .lock()
+            .unwrap()
+            .selected_mut()
+            .channels
+            .copy_from_slice(values);
+    }
+
+    // Sender universes
+    pub fn list_universes(&self) -> Vec<UniverseInfo> {
+        let g = self.inner.lock().unwrap();
+        g.universes
+            .iter()
+            .map(|(addr, out)| UniverseInfo {
+                port_address: *addr,
+                label: addr.to_string(),
+                target_ip: out.target_ip.clone(),
+                enabled: out.enabled,
+                protocol: out.protocol,
+                start_code: out.start_code,
+                selected: *addr == g.selected_universe,
+                subscribers: unicast_subscribers(&g.send_cfg, &g.nodes, *addr, out),
+            })
+            .collect()
+    }
+
+    pub fn add_universe(&self, addr: PortAddress, target_ip: Option<String>) -> Result<()> {
+        let mut g = self.inner.lock().unwrap();
+        if g.universes.contains_key(&addr) {
+            return Err(anyhow!("Universe {} is already configured", addr));
+        }
+        g.universes.insert(addr, UniverseOutput::new(target_ip));
+        Ok(())
     }
 
-    pub fn start_buffered_recording(&self, channels: Vec<usize>) -> Vec<usize> {
-        let normalized = normalize_channels(channels);
-        let mut guard = self.inner.lock().unwrap();
-        guard.record_buffer = Some(RecordBuffer::new(normalized.clone(), true));
-        normalized
+    pub fn remove_universe(&self, addr: PortAddress) -> Result<()> {
+        let mut g = self.inner.lock().unwrap();
+        if !g.universes.contains_key(&addr) {
+            return Err(anyhow!("Universe {} is not configured", addr));
+        }
+        if g.universes.len() == 1 {
+            return Err(anyhow!("Cannot remove the last universe"));
+        }
+        g.universes.remove(&addr);
+        if g.selected_universe == addr {
+            let first = *g.universes.keys().next().unwrap();
+            g.select(first);
+        }
+        Ok(())
     }
 
-    pub fn stop_buffered_recording(&self) {
-        if let Some(buffer) = self.inner.lock().unwrap().record_buffer.as_mut() {
-            buffer.active = false;
+    pub fn select_universe(&self, addr: PortAddress) -> Result<()> {
+        let mut g = self.inner.lock().unwrap();
+        if !g.universes.contains_key(&addr) {
+            return Err(anyhow!("Universe {} is not configured", addr));
         }
+        g.select(addr);
+        Ok(())
     }
 
-    pub fn clear_record_buffer(&self) {
-        self.inner.lock().unwrap().record_buffer = None;
+    pub fn set_universe_enabled(&self, addr: PortAddress, enabled: bool) -> Result<()> {
+        self.with_universe(addr, |out| out.enabled = enabled)
     }
 
-    pub fn set_record_channels(&self, channels: Vec<usize>) -> Vec<usize> {
-        let normalized = normalize_channels(channels);
-        let mut guard = self.inner.lock().unwrap();
-        if let Some(buffer) = guard.record_buffer.as_mut() {
-            buffer.set_channels(normalized.clone());
-        } else {
-            guard.record_buffer = Some(RecordBuffer::new(normalized.clone(), false));
-        }
-        normalized
+    pub fn set_universe_target(&self, addr: PortAddress, target_ip: Option<String>) -> Result<()> {
+        self.with_universe(addr, |out| out.target_ip = target_ip)
     }
 
-    pub fn record_preview(&self, channel: usize, max_points: usize) -> Option<PreviewResponse> {
-        self.inner
-            .lock()
-            .unwrap()
-            .record_buffer
-            .as_ref()
-            .and_then(|buffer| buffer.preview(channel, max_points))
+    pub fn set_universe_protocol(&self, addr: PortAddress, protocol: OutputProtocol) -> Result<()> {
+        self.with_universe(addr, |out| out.protocol = protocol)
     }
 
-    pub fn record_data_snapshot(&self) -> Option<RecordData> {
+    /// 0 sends ordinary DMX; any other value sends that alternate start code.
+    pub fn set_universe_start_code(&self, addr: PortAddress, start_code: u8) -> Result<()> {
+        self.with_universe(addr, |out| out.start_code = start_code)
+    }
+
+    pub fn set_universe_channel(&self, addr: PortAddress, index: usize, value: u8) -> Result<()> {
+        self.with_universe(addr, |out| out.channels[index] = value)
+    }
+
+    pub fn set_universe_channels(&self, addr: PortAddress, values: &[u8]) -> Result<()> {
+        self.with_universe(addr, |out| out.channels.copy_from_slice(values))
+    }
+
+    /// Zeroes every output universe.
+    pub fn blackout(&self) {
+        let mut g = self.inner.lock().unwrap();
+        for out in g.universes.values_mut() {
+            out.channels = [0; 512];
+        }
+    }
+
+    /// Turns the packet inspector on or off; `capacity` bounds its ring.
+    pub fn set_packet_capture(&self, enabled: bool, capacity: Option<usize>) {
+        self.capture.lock().unwrap().configure(enabled, capacity);
+    }
+    pub fn packet_capture_status(&self) -> CaptureStatus {
+        self.capture.lock().unwrap().status()
+    }
+    pub fn clear_packet_capture(&self) {
+        self.capture.lock().unwrap().clear();
+    }
+    /// Decodes up to `limit` captured packets with an id of at least `from_id`.
+    pub fn captured_packets(&self, from_id: u64, limit: usize) -> Vec<InspectedPacket> {
+        let packets = self.capture.lock().unwrap().query(from_id, limit);
+        packets.iter().map(inspector::inspect).collect()
+    }
+    /// Undecoded captured packets, for export.
+    pub fn raw_captured_packets(&self) -> Vec<CapturedPacket> {
+        self.capture.lock().unwrap().query(0, usize::MAX)
+    }
+
+    pub fn get_trigger_mappings(&self) -> Vec<TriggerMapping> {
+        self.inner.lock().unwrap().trigger_mappings.clone()
+    }
+    pub fn set_trigger_mappings(&self, mappings: Vec<TriggerMapping>) {
+        self.inner.lock().unwrap().trigger_mappings = mappings;
+    }
+
+    pub fn get_snapshots(&self) -> BTreeMap<String, Vec<UniverseValues>> {
+        self.inner.lock().unwrap().snapshots.clone()
+    }
+    pub fn set_snapshots(&self, snapshots: BTreeMap<String, Vec<UniverseValues>>) {
+        self.inner.lock().unwrap().snapshots = snapshots;
+    }
+
+    /// Stores the values of every output universe under `name`.
+    pub fn save_snapshot(&self, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Snapshot name is empty"));
+        }
+        let mut g = self.inner.lock().unwrap();
+        let values = g
+            .universes
+            .iter()
+            .map(|(addr, out)| UniverseValues {
+                port_address: *addr,
+                values: out.channels.to_vec(),
+            })
+            .collect();
+        g.snapshots.insert(name.to_string(), values);
+        Ok(())
+    }
+
+    pub fn delete_snapshot(&self, name: &str) -> Result<()> {
+        self.inner
+            .lock()
+            .unwrap()
+            .snapshots
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| anyhow!("No snapshot named {:?}", name))
+    }
+
+    /// Restores a snapshot into the universes that are still configured.
+    pub fn recall_snapshot(&self, name: &str) -> Result<()> {
+        let mut g = self.inner.lock().unwrap();
+        let snapshot = g
+            .snapshots
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("No snapshot named {:?}", name))?;
+        for uv in snapshot {
+            if let Some(out) = g.universes.get_mut(&uv.port_address) {
+                let n = uv.values.len().min(512);
+                out.channels = [0; 512];
+                out.channels[..n].copy_from_slice(&uv.values[..n]);
+            }
+        }
+        Ok(())
+    }
+
+    fn with_universe(&self, addr: PortAddress, f: impl FnOnce(&mut UniverseOutput)) -> Result<()> {
+        let mut g = self.inner.lock().unwrap();
+        let out = g
+            .universes
+            .get_mut(&addr)
+            .ok_or_else(|| anyhow!("Universe {} is not configured", addr))?;
+        f(out);
+        Ok(())
+    }
+
+    /// Starts a buffered recording of `universes` (every universe when
+    /// empty), one track per universe. With `spill` every frame also goes
+    /// to a show file and memory keeps only the recent window.
+    pub fn start_buffered_recording(
+        &self,
+        universes: Vec<PortAddress>,
+        channels: Vec<usize>,
+        spill: Option<SpillConfig>,
+    ) -> Result<Vec<usize>> {
+        let normalized = normalize_channels(channels);
+        let mut buffer = RecordBuffer::new(universes, normalized.clone(), true);
+        if let Some(cfg) = spill {
+            buffer = buffer.with_spill(cfg)?;
+        }
+        self.inner.lock().unwrap().record_buffer = Some(buffer);
+        Ok(normalized)
+    }
+
+    /// Stops the buffered recorder and waits for its spill file, if any, to
+    /// be completed.
+    pub async fn stop_buffered_recording(&self) {
+        let task = self
+            .inner
+            .lock()
+            .unwrap()
+            .record_buffer
+            .as_mut()
+            .and_then(RecordBuffer::stop);
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+    }
+
+    pub fn record_status(&self) -> Option<RecordStatus> {
+        self.inner
+            .lock()
+            .unwrap()
+            .record_buffer
+            .as_ref()
+            .map(RecordBuffer::status)
+    }
+
+    pub fn clear_record_buffer(&self) {
+        self.inner.lock().unwrap().record_buffer = None;
+    }
+
+    /// Selects the recorded channels of one universe's track, or of every
+    /// track when `addr` is `None`.
+    pub fn set_record_channels(
+        &self,
+        addr: Option<PortAddress>,
+        channels: Vec<usize>,
+    ) -> Vec<usize> {
+        let normalized = normalize_channels(channels);
+        let mut guard = self.inner.lock().unwrap();
+        guard
+            .record_buffer
+            .get_or_insert_with(|| RecordBuffer::new(Vec::new(), normalized.clone(), false))
+            .set_channels(addr, normalized.clone());
+        normalized
+    }
+
+    /// Universes the buffered recorder captures; empty captures every one.
+    pub fn set_record_universes(&self, universes: Vec<PortAddress>) {
+        let mut guard = self.inner.lock().unwrap();
+        match guard.record_buffer.as_mut() {
+            Some(buffer) => buffer.universes = universes,
+            None => {
+                guard.record_buffer = Some(RecordBuffer::new(universes, (0..512).collect(), false))
+            }
+        }
+    }
+
+    pub fn record_tracks(&self) -> Vec<RecordTrackInfo> {
         self.inner
             .lock()
             .unwrap()
             .record_buffer
             .as_ref()
-            .map(|buffer| buffer.to_record_data())
+            .map(|buffer| buffer.data.track_infos())
+            .unwrap_or_default()
+    }
+
+    pub fn record_preview(
+        &self,
+        addr: Option<PortAddress>,
+        channel: usize,
+        max_points: usize,
+    ) -> Option<PreviewResponse> {
+        self.inner
+            .lock()
+            .unwrap()
+            .record_buffer
+            .as_ref()
+            .and_then(|buffer| buffer.preview(addr, channel, max_points))
+    }
+
+    pub fn record_data_snapshot(&self) -> Option<RecordData> {
+        self.inner
+            .lock()
+            .unwrap()
+            .record_buffer
+            .as_ref()
+            .map(|buffer| buffer.data.clone())
     }
 
     pub fn load_record_data(&self, data: RecordData, active: bool) {
         self.inner.lock().unwrap().record_buffer = Some(RecordBuffer::from_data(data, active));
     }
 
+    pub fn stop_preroll(&self) {
+        let mut g = self.inner.lock().unwrap();
+        g.preroll = None;
+        if let Some(h) = g.preroll_task.take() {
+            h.abort();
+        }
+    }
+
+    pub fn preroll_status(&self) -> Option<PreRollStatus> {
+        self.inner
+            .lock()
+            .unwrap()
+            .preroll
+            .as_ref()
+            .map(PreRoll::status)
+    }
+
+    /// Fires the pre-roll by hand; false while a post-roll is running.
+    pub fn trigger_preroll(&self, reason: &str) -> Result<bool> {
+        let mut g = self.inner.lock().unwrap();
+        let preroll = g
+            .preroll
+            .as_mut()
+            .ok_or_else(|| anyhow!("Pre-roll is not armed"))?;
+        Ok(preroll.fire(reason.to_string()))
+    }
+
+    /// Puts the last pre-roll capture in the record buffer, for the save
+    /// commands. Refused while a buffered recording is running.
+    pub fn load_preroll_capture(&self) -> Result<PreRollCaptureInfo> {
+        let mut g = self.inner.lock().unwrap();
+        if g.record_buffer.as_ref().is_some_and(|b| b.active) {
+            return Err(anyhow!("A buffered recording is running"));
+        }
+        let capture = g
+            .preroll
+            .as_ref()
+            .and_then(PreRoll::last_capture)
+            .ok_or_else(|| anyhow!("No pre-roll has been captured"))?;
+        let info = capture.info();
+        let data = capture.data.clone();
+        g.record_buffer = Some(RecordBuffer::from_data(data, false));
+        Ok(info)
+    }
+
+    pub fn list_discovered_nodes(&self) -> Vec<TrackedNode> {
+        self.inner.lock().unwrap().nodes.list()
+    }
+
+    /// Reply pages of a discovered node, by its IP.
+    fn discovered_node_pages(&self, ip: &str) -> Vec<TrackedNode> {
+        let ip = ip.trim();
+        self.inner
+            .lock()
+            .unwrap()
+            .nodes
+            .list()
+            .into_iter()
+            .filter(|t| t.node.reply.ip == ip)
+            .collect()
+    }
+
+    pub fn list_rdm_devices(&self) -> Vec<rdm::TodEntry> {
+        self.inner.lock().unwrap().rdm_devices.list()
+    }
+
+    pub fn set_discovery_task(&self, task: JoinHandle<()>) {
+        if let Some(old) = self.inner.lock().unwrap().discovery_task.replace(task) {
+            old.abort();
+        }
+    }
+
     pub fn stop_receiver(&self) {
         if let Some(handle) = self.inner.lock().unwrap().recv_task.take() {
             handle.abort();
         }
     }
 
-    pub fn stop_sender(&self) {
-        if let Some(handle) = self.inner.lock().unwrap().send_task.take() {
+    pub fn stop_sacn_receiver(&self) {
+        if let Some(handle) = self.inner.lock().unwrap().sacn_recv_task.take() {
+            handle.abort();
+        }
+    }
+
+    /// Stops the sender. Its sACN universes are ended with stream
+    /// terminated packets, so receivers let go of them at once instead of
+    /// holding the last look until their timeout.
+    pub async fn stop_sender(&self) {
+        let Some(handle) = self.inner.lock().unwrap().send_task.take() else {
+            return;
+        };
+        handle.abort();
+        if let Err(e) = send_sacn_terminated(self).await {
+            eprintln!("sACN stream terminated error: {e:?}");
+        }
+    }
+
+    pub fn receiver_active(&self) -> bool {
+        self.inner
+            .lock()
+            .unwrap()
+            .recv_task
+            .as_ref()
+            .is_some_and(|h| !h.is_finished())
+    }
+
+    pub fn get_timecode_config(&self) -> TimecodeConfig {
+        self.inner.lock().unwrap().timecode_cfg.clone()
+    }
+    pub fn set_timecode_config(&self, cfg: TimecodeConfig) -> Result<()> {
+        cfg.start_timecode()?;
+        self.inner.lock().unwrap().timecode_cfg = cfg;
+        Ok(())
+    }
+    pub fn set_timecode_task(&self, task: JoinHandle<()>) {
+        if let Some(old) = self.inner.lock().unwrap().timecode_task.replace(task) {
+            old.abort();
+        }
+    }
+    pub fn stop_timecode(&self) {
+        if let Some(handle) = self.inner.lock().unwrap().timecode_task.take() {
             handle.abort();
         }
     }
@@ -514,16 +1486,15 @@ impl AppState {
     pub fn set_receiver_task(&self, task: JoinHandle<()>) {
         self.inner.lock().unwrap().recv_task = Some(task);
     }
+    pub fn set_sacn_receiver_task(&self, task: JoinHandle<()>) {
+        self.inner.lock().unwrap().sacn_recv_task = Some(task);
+    }
     pub fn set_sender_task(&self, task: JoinHandle<()>) {
         self.inner.lock().unwrap().send_task = Some(task);
     }
 
     // Recording controls
-    pub fn set_recording(
-        &self,
-        tx: mpsc::UnboundedSender<crate::artnet::DmxFrame>,
-        task: JoinHandle<()>,
-    ) {
+    pub fn set_recording(&self, tx: mpsc::UnboundedSender<RecordedFrame>, task: JoinHandle<()>) {
         let mut g = self.inner.lock().unwrap();
         g.record_tx = Some(tx);
         g.record_task = Some(task);
@@ -546,10 +1517,19 @@ impl AppState {
         }
     }
 
-    pub fn set_event_filter(&self, filter: Option<(u8, u8, u8)>) {
+    pub fn set_event_filter(&self, filter: Option<PortAddress>) {
         self.inner.lock().unwrap().event_filter = filter;
     }
 
+    pub fn get_sync_mode(&self) -> SyncModeEvent {
+        self.inner.lock().unwrap().sync_mode.clone()
+    }
+
+    pub fn get_receive_stats(&self) -> Vec<UniverseStatsInfo> {
+        let mut g = self.inner.lock().unwrap();
+        g.receive_stats.snapshot(std::time::Instant::now())
+    }
+
     // Animation controls
     pub fn set_animation_task(&self, task: JoinHandle<()>) {
         self.inner.lock().unwrap().animation_task = Some(task);
@@ -693,7 +1673,7 @@ fn generate_animation_scaled_frame(
 }
 
 // Animation task
-pub async fn run_animation_task(app_state: AppState, app: AppHandle) -> Result<()> {
+pub async fn run_animation_task<E: EventSink>(app_state: AppState, events: E) -> Result<()> {
     let mut interval = tokio::time::interval(Duration::from_millis(16));
     interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
     let t0 = Instant::now();
@@ -722,77 +1702,1018 @@ pub async fn run_animation_task(app_state: AppState, app: AppHandle) -> Result<(
         prev_frame = Some(frame);
         if let Some(ref f) = prev_frame {
             app_state.set_channels(f);
-            let _ = app.emit("sender:preview", f.as_slice());
+            events.emit("sender:preview", f.as_slice());
+        }
+    }
+}
+
+/// Art-Net 4: a node reverts to non-synchronous output when no ArtSync
+/// has been received for 4 seconds.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(4);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    #[default]
+    NonSynchronous,
+    Synchronous,
+}
+
+#[derive(Clone, Default, Serialize)]
+pub struct SyncModeEvent {
+    pub mode: SyncMode,
+    pub source: Option<String>,
+}
+
+/// Holds ArtDmx frames while the receiver is in synchronous mode and releases
+/// them when the matching ArtSync arrives.
+#[derive(Default)]
+struct SyncGate {
+    source: Option<IpAddr>,
+    last_sync: Option<Instant>,
+    pending: Vec<artnet::DmxFrame>,
+}
+
+impl SyncGate {
+    fn mode(&self) -> SyncMode {
+        if self.last_sync.is_some() {
+            SyncMode::Synchronous
+        } else {
+            SyncMode::NonSynchronous
+        }
+    }
+
+    /// Returns the frame back if it should be output immediately.
+    fn hold(&mut self, frame: artnet::DmxFrame, from: IpAddr) -> Option<artnet::DmxFrame> {
+        if self.mode() != SyncMode::Synchronous || self.source != Some(from) {
+            return Some(frame);
+        }
+        // Only the latest frame per universe is kept until the next ArtSync.
+        match self
+            .pending
+            .iter_mut()
+            .find(|p| p.port_address == frame.port_address)
+        {
+            Some(slot) => *slot = frame,
+            None => self.pending.push(frame),
+        }
+        None
+    }
+
+    /// Handles an ArtSync. Syncs from a different controller than the one we
+    /// are synchronised to are ignored, as required by the spec.
+    fn sync(&mut self, from: IpAddr) -> Option<Vec<artnet::DmxFrame>> {
+        if self.mode() == SyncMode::Synchronous && self.source != Some(from) {
+            return None;
+        }
+        self.source = Some(from);
+        self.last_sync = Some(Instant::now());
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    /// Falls back to non-synchronous mode after `SYNC_TIMEOUT`, flushing any
+    /// frames still on hold.
+    fn expire(&mut self) -> Option<Vec<artnet::DmxFrame>> {
+        match self.last_sync {
+            Some(t) if t.elapsed() >= SYNC_TIMEOUT => {
+                self.last_sync = None;
+                self.source = None;
+                Some(std::mem::take(&mut self.pending))
+            }
+            _ => None,
+        }
+    }
+
+    fn event(&self) -> SyncModeEvent {
+        SyncModeEvent {
+            mode: self.mode(),
+            source: self.source.map(|ip| ip.to_string()),
+        }
+    }
+}
+
+fn dispatch_frame<E: EventSink>(events: &E, app_state: &AppState, frame: artnet::DmxFrame) {
+    events.emit("artnet:dmx", &frame);
+
+    let (pass, recorder_tx, truncated, fired) = {
+        let mut g = app_state.inner.lock().unwrap();
+        let pass = match g.event_filter {
+            Some(addr) => frame.port_address == addr,
+            None => true,
+        };
+        // The buffer keeps a track per universe and selects its own.
+        let truncated = g
+            .record_buffer
+            .as_mut()
+            .and_then(|buffer| buffer.append(&frame).then(|| buffer.status()));
+        let fired = g.preroll.as_mut().and_then(|p| p.push_dmx(&frame));
+        (pass, g.record_tx.clone(), truncated, fired)
+    };
+    if let Some(reason) = fired {
+        events.emit("artnet:preroll_triggered", &reason);
+    }
+
+    if pass {
+        events.emit("artnet:dmx_filtered", &frame);
+    }
+    if let Some(status) = truncated {
+        events.emit("artnet:record_truncated", &status);
+    }
+    if let Some(tx) = recorder_tx {
+        let _ = tx.send(RecordedFrame::Dmx(frame));
+    }
+}
+
+/// Emits an alternate start code frame as `artnet:nzs` (and
+/// `artnet:nzs_filtered`) and records it apart from the DMX data.
+fn dispatch_nzs<E: EventSink>(events: &E, app_state: &AppState, event: NzsEvent) {
+    events.emit("artnet:nzs", &event);
+    let (pass, recorder_tx, truncated) = {
+        let mut g = app_state.inner.lock().unwrap();
+        let pass = g
+            .event_filter
+            .is_none_or(|addr| addr == event.frame.port_address);
+        let truncated = g
+            .record_buffer
+            .as_mut()
+            .and_then(|buffer| buffer.append_nzs(&event.frame).then(|| buffer.status()));
+        if let Some(preroll) = g.preroll.as_mut() {
+            preroll.push_nzs(&event.frame);
         }
+        (pass, g.record_tx.clone(), truncated)
+    };
+    if pass {
+        events.emit("artnet:nzs_filtered", &event);
     }
+    if let Some(status) = truncated {
+        events.emit("artnet:record_truncated", &status);
+    }
+    if let Some(tx) = recorder_tx {
+        let _ = tx.send(RecordedFrame::Nzs(event.frame));
+    }
+}
+
+fn set_sync_mode<E: EventSink>(events: &E, app_state: &AppState, gate: &SyncGate) {
+    let event = gate.event();
+    events.emit("artnet:sync_mode", &event);
+    app_state.inner.lock().unwrap().sync_mode = event;
 }
 
-pub async fn run_receiver_task(
+/// Replies to an ArtPoll with one ArtPollReply per page of node ports.
+async fn answer_poll(
+    sock: &UdpSocket,
+    poll: &[u8],
+    from: SocketAddr,
+    cfg: &ReceiverConfig,
+    app_state: &AppState,
+    merger: &Merger,
+    report_count: &mut u16,
+) -> Result<()> {
+    let identity = app_state.get_node_config();
+    if !identity.enabled {
+        return Ok(());
+    }
+    let inputs: Vec<PortAddress> = app_state
+        .list_universes()
+        .into_iter()
+        .filter(|u| u.enabled)
+        .map(|u| u.port_address)
+        .collect();
+    let mut ports = node::node_ports(&inputs, &identity.output_universes);
+    let now = std::time::Instant::now();
+    for port in ports.iter_mut().filter(|p| p.output) {
+        port.sources = merger.active_sources(port.port_address, now);
+    }
+    let target = node::poll_target_range(poll);
+    let (ip, iface_mac) = node::local_interface_for(from.ip(), &cfg.bind_ip);
+    let mac = identity
+        .mac
+        .as_deref()
+        .and_then(node::parse_mac)
+        .unwrap_or(iface_mac);
+    let sending = app_state.sender_stream_active();
+    if let Some((bottom, top)) = target {
+        ports.retain(|p| (bottom..=top).contains(&p.port_address.value()));
+        if ports.is_empty() {
+            return Ok(());
+        }
+    }
+    // Art-Net 4: replies are unicast to the poller on the Art-Net port.
+    let dest = SocketAddr::new(from.ip(), artnet::ARTNET_PORT);
+    for (idx, page) in node::reply_pages(&ports).iter().enumerate() {
+        // NodeReport counts the replies sent.
+        *report_count = report_count.wrapping_add(1);
+        let pkt = node::encode_poll_reply(&node::PollReplyInfo {
+            identity: &identity,
+            ip,
+            mac,
+            ports: page,
+            bind_index: (idx + 1).min(255) as u8,
+            report_count: *report_count,
+            sending,
+            merge_mode: merger.mode(),
+        });
+        sock.send_to(&pkt, dest).await?;
+    }
+    Ok(())
+}
+
+/// ArtDmx as received from one source, before merging.
+#[derive(Clone, Serialize)]
+pub struct SourceFrameEvent {
+    pub source_ip: String,
+    #[serde(flatten)]
+    pub frame: artnet::DmxFrame,
+}
+
+/// ArtNzs, or sACN with an alternate start code, as received.
+#[derive(Clone, Serialize)]
+pub struct NzsEvent {
+    pub source_ip: String,
+    #[serde(flatten)]
+    pub frame: artnet::NzsFrame,
+}
+
+/// A third source was refused on a universe already merging two sources.
+#[derive(Clone, Serialize)]
+pub struct MergeRejectedEvent {
+    pub port_address: PortAddress,
+    pub source_ip: String,
+    pub physical: u8,
+}
+
+pub async fn run_receiver_task<E: EventSink>(
     cfg: artnet::ReceiverConfig,
-    window: tauri::AppHandle,
+    events: E,
     app_state: AppState,
 ) -> Result<()> {
     let sock = artnet::bind_receiver_socket(&cfg).await?;
+    // Recorded as the destination of captured packets; unspecified unless a
+    // bind IP is set.
+    let local = sock.local_addr()?;
     let mut buf = [0u8; 2048];
+    let mut gate = SyncGate::default();
+    let mut merger = Merger::new(cfg.merge_mode);
+    let mut sequence_filter = SequenceFilter::default();
+    let mut report_count: u16 = 0;
+    let mut last_stats = Instant::now();
+    app_state.inner.lock().unwrap().receive_stats.clear();
+    set_sync_mode(&events, &app_state, &gate);
 
     loop {
-        let (n, from) = sock.recv_from(&mut buf).await?;
+        if last_stats.elapsed() >= STATS_EVENT_INTERVAL {
+            last_stats = Instant::now();
+            events.emit("artnet:stats", app_state.get_receive_stats());
+        }
+        if let Some(flushed) = gate.expire() {
+            for frame in flushed {
+                dispatch_frame(&events, &app_state, frame);
+            }
+            set_sync_mode(&events, &app_state, &gate);
+        }
 
-        if let Ok(frame) = artnet::parse_artdmx(&buf[..n]) {
-            let _ = window.emit("artnet:dmx", &frame);
+        let (n, from) = match tokio::time::timeout(
+            Duration::from_millis(250),
+            sock.recv_from(&mut buf),
+        )
+        .await
+        {
+            Ok(r) => r?,
+            Err(_) => continue,
+        };
+        app_state
+            .capture
+            .lock()
+            .unwrap()
+            .push(from, local, &buf[..n]);
 
-            let (pass, recorder_tx) = {
+        if let Ok(frame) = artnet::parse_artdmx(&buf[..n]) {
+            let key = SourceKey {
+                ip: from.ip(),
+                physical: frame.physical,
+            };
+            let now = std::time::Instant::now();
+            {
                 let mut g = app_state.inner.lock().unwrap();
-                let pass = match g.event_filter {
-                    Some((net, sub, uni)) => {
-                        frame.net == net && frame.subnet == sub && frame.universe == uni
-                    }
-                    None => true,
-                };
-                if pass {
-                    if let Some(buffer) = g.record_buffer.as_mut() {
-                        buffer.append(&frame);
-                    }
+                g.receive_stats.record(&frame, key, now);
+                if cfg.reject_out_of_order && !sequence_filter.accept(&frame, key, now) {
+                    g.receive_stats.record_discarded(frame.port_address, key);
+                    continue;
+                }
+            }
+            events.emit(
+                "artnet:dmx_source",
+                SourceFrameEvent {
+                    source_ip: from.ip().to_string(),
+                    frame: frame.clone(),
+                },
+            );
+            let port_address = frame.port_address;
+            let frame = match merger.push(frame, key, now) {
+                MergeOutcome::Output(frame) => frame,
+                MergeOutcome::Rejected => {
+                    events.emit(
+                        "artnet:merge_rejected",
+                        MergeRejectedEvent {
+                            port_address,
+                            source_ip: key.ip.to_string(),
+                            physical: key.physical,
+                        },
+                    );
+                    continue;
                 }
-                (pass, g.record_tx.clone())
+                MergeOutcome::Ignored => continue,
             };
+            if let Some(frame) = gate.hold(frame, from.ip()) {
+                dispatch_frame(&events, &app_state, frame);
+            }
+        } else if let Ok(frame) = artnet::parse_artnzs(&buf[..n]) {
+            let source_ip = from.ip().to_string();
+            dispatch_nzs(&events, &app_state, NzsEvent { source_ip, frame });
+        } else if artnet::is_artsync(&buf[..n]) {
+            let was = gate.mode();
+            if let Some(released) = gate.sync(from.ip()) {
+                for frame in released {
+                    dispatch_frame(&events, &app_state, frame);
+                }
+                if was != gate.mode() {
+                    set_sync_mode(&events, &app_state, &gate);
+                }
+            }
+        } else if let Ok((timecode, stream_id)) = timecode::parse_timecode(&buf[..n]) {
+            app_state.timecode_in.send_replace(Some(ReceivedTimecode {
+                timecode,
+                at: Instant::now(),
+            }));
+            events.emit(
+                "artnet:timecode",
+                TimecodeEvent {
+                    source_ip: from.ip().to_string(),
+                    stream_id,
+                    timecode,
+                    text: timecode.to_string(),
+                    ms: timecode.to_ms(),
+                },
+            );
+        } else if let Ok(t) = trigger::parse_trigger(&buf[..n]) {
+            let oem = app_state.get_node_config().oem;
+            if t.oem == trigger::ALL_DEVICES || t.oem == oem {
+                events.emit(
+                    "artnet:trigger",
+                    trigger::TriggerEvent {
+                        source_ip: from.ip().to_string(),
+                        key_name: trigger::key_name(t.key),
+                        trigger: t.clone(),
+                    },
+                );
+                fire_trigger_actions(&events, &app_state, trigger::Incoming::Trigger(&t));
+                preroll_packet(&events, &app_state, trigger::Incoming::Trigger(&t));
+            }
+        } else if let Ok(c) = trigger::parse_command(&buf[..n]) {
+            let esta = app_state.get_node_config().esta_code;
+            if c.esta == trigger::ALL_DEVICES || c.esta == esta {
+                events.emit(
+                    "artnet:command",
+                    trigger::CommandEvent {
+                        source_ip: from.ip().to_string(),
+                        command: c.clone(),
+                    },
+                );
+                fire_trigger_actions(&events, &app_state, trigger::Incoming::Command(&c));
+                preroll_packet(&events, &app_state, trigger::Incoming::Command(&c));
+            }
+        } else if artnet::opcode(&buf[..n]) == Some(artnet::OP_POLL) {
+            let _ = answer_poll(
+                &sock,
+                &buf[..n],
+                from,
+                &cfg,
+                &app_state,
+                &merger,
+                &mut report_count,
+            )
+            .await;
+        } else if matches!(
+            artnet::opcode(&buf[..n]),
+            Some(
+                artnet::OP_POLL_REPLY
+                    | artnet::OP_IP_PROG_REPLY
+                    | artnet::OP_TOD_DATA
+                    | artnet::OP_RDM
+            )
+        ) {
+            // Fails only when no scan is listening.
+            let _ = app_state.reply_relay.send((from, buf[..n].to_vec()));
+        }
+    }
+}
+
+fn preroll_packet<E: EventSink>(events: &E, app_state: &AppState, packet: trigger::Incoming) {
+    let fired = match app_state.inner.lock().unwrap().preroll.as_mut() {
+        Some(preroll) => preroll.on_packet(&packet),
+        None => return,
+    };
+    if let Some(reason) = fired {
+        events.emit("artnet:preroll_triggered", &reason);
+    }
+}
 
-            if pass {
-                let _ = window.emit("artnet:dmx_filtered", &frame);
+/// Arms the pre-roll buffer with `config`, replacing any running one, with
+/// a task running its timed parts whichever receivers feed it.
+pub fn start_preroll<E: EventSink>(app_state: &AppState, config: PreRollConfig, events: E) {
+    app_state.stop_preroll();
+    app_state.inner.lock().unwrap().preroll = Some(PreRoll::new(config));
+    let st = app_state.clone();
+    let handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PREROLL_TICK);
+        loop {
+            interval.tick().await;
+            if !preroll_tick(&events, &st) {
+                break;
             }
-            if let Some(tx) = recorder_tx {
-                let _ = tx.send(frame);
+        }
+    });
+    app_state.inner.lock().unwrap().preroll_task = Some(handle);
+}
+
+/// Runs the pre-roll's timed triggers, completes its post-roll and trims the
+/// ring. A capture goes to the record buffer unless a buffered recording is
+/// running. False once the pre-roll is off.
+fn preroll_tick<E: EventSink>(events: &E, app_state: &AppState) -> bool {
+    let (fired, captured) = {
+        let mut g = app_state.inner.lock().unwrap();
+        let Some(preroll) = g.preroll.as_mut() else {
+            return false;
+        };
+        let (fired, completed) = preroll.tick();
+        // The capture stays with the pre-roll until `load_preroll_capture`
+        // asks for it, so the record buffer is never replaced behind the
+        // user's back.
+        let captured = preroll
+            .last_capture()
+            .filter(|_| completed)
+            .map(PreRollCapture::info);
+        (fired, captured)
+    };
+    if let Some(reason) = fired {
+        events.emit("artnet:preroll_triggered", &reason);
+    }
+    if let Some(info) = captured {
+        events.emit("artnet:preroll_captured", &info);
+    }
+    true
+}
+
+fn fire_trigger_actions<E: EventSink>(events: &E, app_state: &AppState, packet: trigger::Incoming) {
+    let mappings = app_state.get_trigger_mappings();
+    for action in trigger::matching_actions(&mappings, &packet) {
+        let error = run_trigger_action(events, app_state, action)
+            .err()
+            .map(|e| e.to_string());
+        events.emit(
+            "artnet:trigger_action",
+            TriggerActionEvent {
+                action: action.clone(),
+                error,
+            },
+        );
+    }
+}
+
+/// Carries out an action mapped to an ArtTrigger or ArtCommand.
+pub fn run_trigger_action<E: EventSink>(
+    events: &E,
+    app_state: &AppState,
+    action: &TriggerAction,
+) -> Result<()> {
+    match action {
+        TriggerAction::PlayFile {
+            path,
+            loop_playback,
+        } => start_playback(app_state, path.clone(), 0, *loop_playback)?,
+        TriggerAction::StopPlayback => app_state.stop_playback(),
+        TriggerAction::StartAnimation {
+            mode,
+            frequency,
+            master_value,
+        } => {
+            let kind = anim_kind_from_cmd(mode);
+            if kind == AnimKind::Off {
+                return Err(anyhow!("Unknown animation mode {:?}", mode));
             }
-        } else if n >= 10 && &buf[..8] == b"Art-Net\0" {
-            let op = u16::from_le_bytes([buf[8], buf[9]]);
-            if op == 0x2100 {
-                let gate = app_state.discovery_poll_tx.lock().await;
-                if let Some(ref tx) = *gate {
-                    let _ = tx.try_send((from, buf[..n].to_vec()));
+            app_state.stop_animation();
+            let targets = [true; 512];
+            app_state.set_animation_state(AnimationState {
+                mode: kind,
+                frequency: if frequency.is_finite() {
+                    frequency.abs().max(1e-3)
+                } else {
+                    1.0
+                },
+                master_value: *master_value,
+                is_running: true,
+                chaser_from: 1,
+                chaser_to: 512,
+                animation_targets: targets,
+                animation_modes: sanitize_animation_modes(None, kind, targets),
+            });
+            let st = app_state.clone();
+            let ev = events.clone();
+            let handle = tokio::spawn(async move {
+                if let Err(e) = run_animation_task(st, ev).await {
+                    eprintln!("Animation task error: {e:?}");
                 }
+            });
+            app_state.set_animation_task(handle);
+        }
+        TriggerAction::StopAnimation => app_state.stop_animation(),
+        TriggerAction::Blackout => {
+            app_state.stop_animation();
+            app_state.blackout();
+            events.emit("sender:preview", app_state.snapshot_channels().as_slice());
+        }
+        TriggerAction::RecallSnapshot { name } => {
+            app_state.recall_snapshot(name)?;
+            events.emit("sender:preview", app_state.snapshot_channels().as_slice());
+        }
+    }
+    Ok(())
+}
+
+/// Sends a one-off packet to `target_ip`, or to the sender's target, on the
+/// sender's port.
+pub async fn send_to_target(
+    app_state: &AppState,
+    target_ip: Option<&str>,
+    pkt: &[u8],
+) -> Result<()> {
+    let cfg = app_state.get_sender_config();
+    let ip = target_ip.unwrap_or(&cfg.target_ip);
+    let target: SocketAddr = format!("{}:{}", ip, cfg.port).parse()?;
+    app_state.udp_for_send().await?.send_to(pkt, target).await?;
+    Ok(())
+}
+
+/// Scans for Art-Net nodes. Replies reaching the receiver socket on the
+/// Art-Net port are relayed into the scan while it runs.
+pub async fn discover_nodes(
+    app_state: &AppState,
+    hosts: &[String],
+    port: u16,
+    timeout_ms: u64,
+) -> Result<Vec<discovery::ArtNetDiscoveredNode>> {
+    let relay = app_state.reply_relay.subscribe();
+    discovery::scan_artnet(hosts, port, timeout_ms, Some(relay)).await
+}
+
+/// Merges scan results into the node table and emits
+/// `discovery:node_added`, `discovery:node_changed` and `discovery:node_lost`.
+pub fn apply_discovered_nodes<E: EventSink>(
+    events: &E,
+    app_state: &AppState,
+    nodes: Vec<discovery::ArtNetDiscoveredNode>,
+    complete: bool,
+) {
+    let changes = app_state
+        .inner
+        .lock()
+        .unwrap()
+        .nodes
+        .update(nodes, complete);
+    for node in changes.added {
+        events.emit("discovery:node_added", node);
+    }
+    for node in changes.changed {
+        events.emit("discovery:node_changed", node);
+    }
+    for node in changes.lost {
+        events.emit("discovery:node_lost", node);
+    }
+}
+
+/// Time a node gets to apply an ArtAddress before it is polled.
+const ADDRESS_SETTLE: Duration = Duration::from_millis(150);
+
+/// Programs a node with ArtAddress, then polls it and checks the reply
+/// against the request. The reply also refreshes the node table.
+pub async fn address_node<E: EventSink>(
+    events: &E,
+    app_state: &AppState,
+    req: &AddressRequest,
+    timeout_ms: u64,
+) -> Result<AddressOutcome> {
+    remote::send_address(req).await?;
+    sleep(ADDRESS_SETTLE).await;
+    let nodes = discover_nodes(
+        app_state,
+        &[req.ip.trim().to_string()],
+        req.port,
+        timeout_ms,
+    )
+    .await?;
+    let outcome = remote::verify(req, &nodes);
+    apply_discovered_nodes(events, app_state, nodes, false);
+    Ok(outcome)
+}
+
+/// Queries or reprograms a node's IP settings with ArtIpProg and reads the
+/// reply back.
+pub async fn ip_prog_node(
+    app_state: &AppState,
+    req: &IpProgRequest,
+    timeout_ms: u64,
+) -> Result<IpProgOutcome> {
+    let relay = app_state.reply_relay.subscribe();
+    remote::ip_prog(req, Some(relay), timeout_ms).await
+}
+
+/// Address and Art-Net port of a node in the discovery table.
+fn rdm_target(
+    app_state: &AppState,
+    node_ip: &str,
+) -> Result<(std::net::Ipv4Addr, u16, Vec<PortAddress>)> {
+    let pages = app_state.discovered_node_pages(node_ip);
+    let first = pages
+        .first()
+        .ok_or_else(|| anyhow!("Node {} is not in the discovery table", node_ip))?;
+    let ip = first
+        .node
+        .reply
+        .ip
+        .parse()
+        .map_err(|_| anyhow!("Invalid node address {}", first.node.reply.ip))?;
+    let port = match first.node.reply.artnet_port {
+        0 => artnet::ARTNET_PORT,
+        p => p,
+    };
+    // RDM runs on output (Art-Net -> DMX) ports.
+    let mut outputs: Vec<PortAddress> = pages
+        .iter()
+        .flat_map(|t| t.node.reply.ports.iter())
+        .filter_map(|p| p.output.as_ref().map(|o| o.port_address))
+        .collect();
+    outputs.sort();
+    outputs.dedup();
+    Ok((ip, port, outputs))
+}
+
+/// Reads the RDM devices on a discovered node's output ports (all of them
+/// unless `port_address` picks one), running full discovery first when
+/// `flush` is set, and keeps them for `list_rdm_devices`.
+pub async fn rdm_discover(
+    app_state: &AppState,
+    node_ip: &str,
+    port_address: Option<PortAddress>,
+    flush: bool,
+    timeout_ms: u64,
+) -> Result<Vec<rdm::TodEntry>> {
+    let (ip, port, outputs) = rdm_target(app_state, node_ip)?;
+    let addresses = match port_address {
+        Some(addr) => vec![addr],
+        None if outputs.is_empty() => {
+            return Err(anyhow!("Node {} reports no output ports", node_ip))
+        }
+        None => outputs,
+    };
+    let entries = rdm::read_tod(
+        ip,
+        port,
+        &addresses,
+        flush,
+        &app_state.reply_relay,
+        Duration::from_millis(timeout_ms),
+    )
+    .await?;
+    app_state
+        .inner
+        .lock()
+        .unwrap()
+        .rdm_devices
+        .update(entries.clone());
+    Ok(entries)
+}
+
+pub async fn rdm_get(
+    app_state: &AppState,
+    node_ip: &str,
+    port_address: PortAddress,
+    uid: rdm::Uid,
+    param: rdm::RdmParam,
+    timeout_ms: u64,
+) -> Result<rdm::RdmReply> {
+    let (ip, port, _) = rdm_target(app_state, node_ip)?;
+    let timeout = Duration::from_millis(timeout_ms);
+    rdm::get(
+        ip,
+        port,
+        port_address,
+        uid,
+        param,
+        &app_state.reply_relay,
+        timeout,
+    )
+    .await
+}
+
+pub async fn rdm_set(
+    app_state: &AppState,
+    node_ip: &str,
+    port_address: PortAddress,
+    uid: rdm::Uid,
+    value: &rdm::RdmSetValue,
+    timeout_ms: u64,
+) -> Result<rdm::RdmReply> {
+    let (ip, port, _) = rdm_target(app_state, node_ip)?;
+    let timeout = Duration::from_millis(timeout_ms);
+    rdm::set(
+        ip,
+        port,
+        port_address,
+        uid,
+        value,
+        &app_state.reply_relay,
+        timeout,
+    )
+    .await
+}
+
+const DISCOVERY_SCAN_MS: u64 = 1500;
+/// Poll interval that keeps unicast subscribers current while periodic
+/// discovery is paused.
+const UNICAST_DISCOVERY_SEC: u64 = 10;
+
+fn effective_discovery_interval_sec(app_state: &AppState) -> u64 {
+    let g = app_state.inner.lock().unwrap();
+    if g.discovery_interval_sec == 0 && g.send_cfg.unicast_subscribers {
+        UNICAST_DISCOVERY_SEC
+    } else {
+        g.discovery_interval_sec
+    }
+}
+
+/// Polls every `discovery_interval_sec` seconds (0 pauses discovery unless
+/// the sender is in unicast mode) and keeps the node table current.
+pub async fn run_discovery_task<E: EventSink>(events: E, app_state: AppState) -> Result<()> {
+    loop {
+        let interval = effective_discovery_interval_sec(&app_state);
+        if interval == 0 {
+            sleep(Duration::from_secs(1)).await;
+            continue;
+        }
+        let started = Instant::now();
+        let cfg = app_state.get_sender_config();
+        let hosts = discovery::broadcast_hosts(&cfg.target_ip, Vec::new());
+        let scan_ms = DISCOVERY_SCAN_MS.min(interval * 1000);
+        match discover_nodes(&app_state, &hosts, cfg.port, scan_ms).await {
+            Ok(nodes) => apply_discovered_nodes(&events, &app_state, nodes, true),
+            Err(e) => eprintln!("discovery scan error: {e:?}"),
+        }
+        // Re-read so a changed interval takes effect without a full wait.
+        while started.elapsed() < Duration::from_secs(effective_discovery_interval_sec(&app_state))
+        {
+            sleep(Duration::from_millis(250)).await;
+        }
+    }
+}
+
+/// Receives E1.31 data on the sACN port and feeds it through the same
+/// monitor and recording path as ArtDmx.
+pub async fn run_sacn_receiver_task<E: EventSink>(
+    cfg: SacnReceiverConfig,
+    events: E,
+    app_state: AppState,
+) -> Result<()> {
+    let sock = sacn::bind_receiver_socket(&cfg).await?;
+    let mut buf = [0u8; 1144];
+
+    loop {
+        let (n, from) = sock.recv_from(&mut buf).await?;
+        let Ok(packet) = sacn::parse_data(&buf[..n]) else {
+            continue;
+        };
+        if !cfg.unicast && !cfg.universes.contains(&packet.universe) {
+            continue;
+        }
+        events.emit("sacn:packet", SacnPacketEvent::new(&packet, from));
+        if packet.stream_terminated || packet.preview {
+            continue;
+        }
+        let Some(port_address) = sacn::port_address_for(packet.universe) else {
+            continue;
+        };
+        if packet.start_code != 0 {
+            let frame = artnet::NzsFrame {
+                port_address,
+                start_code: packet.start_code,
+                length: packet.values.len() as u16,
+                sequence: packet.sequence,
+                values: packet.values,
+            };
+            let source_ip = from.ip().to_string();
+            dispatch_nzs(&events, &app_state, NzsEvent { source_ip, frame });
+            continue;
+        }
+        let frame = artnet::DmxFrame {
+            port_address,
+            length: packet.values.len() as u16,
+            sequence: packet.sequence,
+            physical: 0,
+            values: packet.values,
+        };
+        dispatch_frame(&events, &app_state, frame);
+    }
+}
+
+/// Per-packet sACN source details; the DMX values go out as `artnet:dmx`.
+#[derive(Clone, Serialize)]
+pub struct SacnPacketEvent {
+    pub source_ip: String,
+    pub cid: sacn::Cid,
+    pub source_name: String,
+    pub universe: u16,
+    pub port_address: Option<PortAddress>,
+    pub priority: u8,
+    pub sequence: u8,
+    pub preview: bool,
+    pub stream_terminated: bool,
+    pub start_code: u8,
+}
+
+impl SacnPacketEvent {
+    fn new(packet: &sacn::SacnPacket, from: SocketAddr) -> Self {
+        Self {
+            source_ip: from.ip().to_string(),
+            cid: packet.cid,
+            source_name: packet.source_name.clone(),
+            universe: packet.universe,
+            port_address: sacn::port_address_for(packet.universe),
+            priority: packet.priority,
+            sequence: packet.sequence,
+            preview: packet.preview,
+            stream_terminated: packet.stream_terminated,
+            start_code: packet.start_code,
+        }
+    }
+}
+
+/// Sends one universe frame with the protocol configured for it.
+pub async fn send_universe_frame(
+    sock: &UdpSocket,
+    cfg: &SenderConfig,
+    sacn_cfg: &SacnConfig,
+    frame: &UniverseFrame,
+    pkt: &mut Vec<u8>,
+) -> Result<()> {
+    match frame.protocol {
+        OutputProtocol::ArtNet if frame.start_code != 0 => {
+            for ucfg in frame.sender_configs(cfg) {
+                artnet::send_artnzs_with_buffer(
+                    sock,
+                    &ucfg,
+                    frame.start_code,
+                    &frame.data,
+                    frame.sequence,
+                    pkt,
+                )
+                .await?;
             }
+            Ok(())
+        }
+        OutputProtocol::ArtNet => {
+            for ucfg in frame.sender_configs(cfg) {
+                artnet::send_artdmx_with_buffer(sock, &ucfg, &frame.data, frame.sequence, pkt)
+                    .await?;
+            }
+            Ok(())
+        }
+        OutputProtocol::Sacn if frame.start_code != 0 => {
+            let universe = sacn::sacn_universe(frame.port_address);
+            let data = &frame.data;
+            sacn::encode_data_into(
+                pkt,
+                sacn_cfg,
+                universe,
+                frame.start_code,
+                data,
+                frame.sequence,
+                0,
+            );
+            let dest = sacn::destination(universe, frame.target_ip.as_deref())?;
+            sock.send_to(pkt, dest).await?;
+            Ok(())
+        }
+        OutputProtocol::Sacn => {
+            sacn::send_data_with_buffer(
+                sock,
+                sacn_cfg,
+                frame.port_address,
+                frame.target_ip.as_deref(),
+                &frame.data,
+                frame.sequence,
+                pkt,
+            )
+            .await
         }
     }
 }
 
 pub async fn run_sender_task(cfg: SenderConfig, app_state: AppState) -> Result<()> {
     let sock = app_state.udp_for_send().await?;
-    let mut pkt = Vec::with_capacity(530);
+    let mut pkt = Vec::with_capacity(638);
     let mut interval = tokio::time::interval(Duration::from_millis(
         ((1000.0f32 / cfg.fps.max(1) as f32).round() as u64).max(1),
     ));
     interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
     loop {
         interval.tick().await;
-        let (last, seq) = app_state.snapshot_channels_tick_seq();
-        let _ = artnet::send_artdmx_with_buffer(sock.as_ref(), &cfg, &last, seq, &mut pkt).await;
+        let frames = app_state.snapshot_universes_tick_seq();
+        let sacn_cfg = app_state.get_sacn_config();
+        for frame in &frames {
+            let _ = send_universe_frame(sock.as_ref(), &cfg, &sacn_cfg, frame, &mut pkt).await;
+        }
+        if cfg.sync {
+            // Every node that got ArtDmx this tick needs the ArtSync, or it
+            // stays in non-synchronous mode.
+            let mut targets: Vec<String> = Vec::new();
+            for frame in &frames {
+                if frame.protocol != OutputProtocol::ArtNet || frame.start_code != 0 {
+                    continue;
+                }
+                for ucfg in frame.sender_configs(&cfg) {
+                    if !targets.contains(&ucfg.target_ip) {
+                        targets.push(ucfg.target_ip);
+                    }
+                }
+            }
+            for target_ip in targets {
+                let sync_cfg = SenderConfig {
+                    target_ip,
+                    ..cfg.clone()
+                };
+                let _ = artnet::send_artsync_with_buffer(sock.as_ref(), &sync_cfg, &mut pkt).await;
+            }
+        }
+    }
+}
+
+/// Sends the three stream terminated packets E1.31 ends a universe with,
+/// for every sACN universe of the sender.
+async fn send_sacn_terminated(app_state: &AppState) -> Result<()> {
+    let sock = app_state.udp_for_send().await?;
+    let sacn_cfg = app_state.get_sacn_config();
+    let mut pkt = Vec::with_capacity(638);
+    let frames = app_state.sacn_universes();
+    for _ in 0..3 {
+        for frame in &frames {
+            let Some(sequence) = app_state.next_universe_sequence(frame.port_address) else {
+                continue;
+            };
+            let universe = sacn::sacn_universe(frame.port_address);
+            sacn::encode_data_into(
+                &mut pkt,
+                &sacn_cfg,
+                universe,
+                frame.start_code,
+                &frame.data,
+                sequence,
+                sacn::OPTION_STREAM_TERMINATED,
+            );
+            let dest = sacn::destination(universe, frame.target_ip.as_deref())?;
+            sock.send_to(&pkt, dest).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Sends ArtTimeCode to the sender's target, counting up from `cfg.start` at
+/// the real frame rate of its type.
+pub async fn run_timecode_task(
+    cfg: TimecodeConfig,
+    sender: SenderConfig,
+    app_state: AppState,
+) -> Result<()> {
+    let start = cfg.start_timecode()?.frame_count();
+    let sock = app_state.udp_for_send().await?;
+    let target: SocketAddr = format!("{}:{}", sender.target_ip, sender.port).parse()?;
+    let frame = cfg.kind.frame_duration();
+    let mut interval = tokio::time::interval(frame);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let began = Instant::now();
+    loop {
+        interval.tick().await;
+        // Count frames from the clock so skipped ticks do not slow it down.
+        let elapsed = (began.elapsed().as_nanos() / frame.as_nanos()) as u64;
+        let tc = Timecode::from_frame_count(start + elapsed, cfg.kind);
+        let _ = sock
+            .send_to(&timecode::encode_timecode(tc, cfg.stream_id), target)
+            .await;
     }
 }
 
 // Recorder: writes JSON Lines
 pub async fn run_record_task(
     path: String,
-    mut rx: mpsc::UnboundedReceiver<crate::artnet::DmxFrame>,
+    mut rx: mpsc::UnboundedReceiver<RecordedFrame>,
 ) -> Result<()> {
     use std::io::Write;
     let mut file = std::fs::File::create(path)?;
@@ -807,83 +2728,151 @@ pub async fn run_record_task(
             net: u8,
             subnet: u8,
             universe: u8,
+            /// Only present on alternate start code frames.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            start_code: Option<u8>,
             length: u16,
             values: &'a [u8],
         }
+        let (port_address, start_code, length, values) = match &frame {
+            RecordedFrame::Dmx(f) => (f.port_address, None, f.length, &f.values),
+            RecordedFrame::Nzs(f) => (f.port_address, Some(f.start_code), f.length, &f.values),
+        };
         let line = Line {
             t_ms,
-            net: frame.net,
-            subnet: frame.subnet,
-            universe: frame.universe,
-            length: frame.length,
-            values: &frame.values,
+            net: port_address.net(),
+            subnet: port_address.subnet(),
+            universe: port_address.universe(),
+            start_code,
+            length,
+            values,
         };
         writeln!(file, "{}", serde_json::to_string(&line)?)?;
     }
     Ok(())
 }
 
+/// Recorder for binary show files. The writer completes the file when the
+/// task ends or is aborted.
+pub async fn run_show_record_task(
+    path: String,
+    compression: crate::show::Compression,
+    mut rx: mpsc::UnboundedReceiver<RecordedFrame>,
+) -> Result<()> {
+    let mut writer =
+        crate::show::ShowWriter::create(&path, compression, crate::show::DEFAULT_KEYFRAME_MS)?;
+    let start = Instant::now();
+    while let Some(frame) = rx.recv().await {
+        let t_ms = start.elapsed().as_millis() as u64;
+        match frame {
+            RecordedFrame::Dmx(f) => writer.write_dmx(t_ms, f.port_address, &f.values)?,
+            RecordedFrame::Nzs(f) => writer.write_nzs(t_ms, &f)?,
+        }
+    }
+    writer.finish()
+}
+
+/// Writes the frames of a buffered recording to its spill file, away from
+/// the state lock. A failed write ends the task and with it spilling.
+async fn run_spill_task(
+    mut writer: crate::show::ShowWriter,
+    mut rx: mpsc::UnboundedReceiver<TimedFrame>,
+    progress: Arc<Mutex<SpillProgress>>,
+) {
+    let mut result = Ok(());
+    while let Some(frame) = rx.recv().await {
+        result = writer.write(&frame);
+        if result.is_err() {
+            break;
+        }
+        progress.lock().unwrap().frames += 1;
+    }
+    if let Err(e) = result.and_then(|()| writer.finish()) {
+        eprintln!("record spill error: {e:?}");
+        progress.lock().unwrap().error = Some(e.to_string());
+    }
+}
+
+/// One universe of a recording, ready to send.
+pub struct PlayFrame {
+    pub t_ms: u64,
+    pub port_address: PortAddress,
+    pub data: [u8; 512],
+}
+
+/// Frames of a recording from `start_ms` on: show files seek through their
+/// index, JSON Lines recordings and pcap / pcapng captures are read from the
+/// start.
+fn play_frames(
+    path: &str,
+    start_ms: u64,
+) -> Result<Box<dyn Iterator<Item = Result<PlayFrame>> + Send>> {
+    if crate::show::is_show_file(path) {
+        return Ok(Box::new(crate::show::play_frames(path, start_ms)?));
+    }
+    let frames: Box<dyn Iterator<Item = Result<PlayFrame>> + Send> =
+        if crate::pcap::is_capture_file(path) {
+            Box::new(crate::pcap::load_frames(path)?.into_iter().map(Ok))
+        } else {
+            Box::new(
+                crate::recording::read_jsonl(path)?
+                    .filter_map(|frame| frame.map(|f| f.play_frame()).transpose()),
+            )
+        };
+    Ok(Box::new(frames.filter(move |frame| {
+        frame.as_ref().map_or(true, |f| f.t_ms >= start_ms)
+    })))
+}
+
+/// Loads a whole JSON Lines recording or capture for random access.
+pub fn load_play_frames(path: &str) -> Result<Vec<PlayFrame>> {
+    play_frames(path, 0)?.collect()
+}
+
+/// WAV recordings carry one universe, sent to `port_address`.
+pub fn wav_frames(
+    wav_data: &crate::recording::WavRecordingData,
+    port_address: PortAddress,
+) -> Vec<PlayFrame> {
+    (0..wav_data.timestamps.len())
+        .map(|frame_idx| {
+            let mut data = [0u8; 512];
+            for (value, channel) in data.iter_mut().zip(&wav_data.channels) {
+                if let Some(v) = channel.get(frame_idx) {
+                    *value = *v;
+                }
+            }
+            PlayFrame {
+                t_ms: wav_data.timestamps[frame_idx],
+                port_address,
+                data,
+            }
+        })
+        .collect()
+}
+
 pub async fn run_play_task(
     path: String,
     cfg: SenderConfig,
     start_ms: u64,
     loop_playback: bool,
 ) -> Result<()> {
-    use std::io::{BufRead, BufReader};
     let sock = artnet::sender_socket().await?;
     let mut active_start_ms = start_ms;
     loop {
-        let file = std::fs::File::open(&path)?;
-        let mut lines = BufReader::new(file).lines();
-        let mut first = true;
-        let mut channels: Vec<usize> = (1..=512).collect();
         let mut last_t: Option<u64> = None;
-        while let Some(line) = lines.next() {
-            let line = line?;
-            if first {
-                first = false;
-                if let Ok(val) = serde_json::from_str::<serde_json::Value>(&line) {
-                    if val.get("format").is_some() {
-                        if let Some(arr) = val.get("channels").and_then(|v| v.as_array()) {
-                            channels = arr
-                                .iter()
-                                .filter_map(|n| n.as_u64().map(|x| x as usize))
-                                .collect();
-                        }
-                        continue;
-                    }
-                }
-            }
-            #[derive(serde::Deserialize)]
-            struct Line {
-                t_ms: u64,
-                net: u8,
-                subnet: u8,
-                universe: u8,
-                values: Vec<u8>,
-            }
-            let rec: Line = serde_json::from_str(&line)?;
-            if rec.t_ms < active_start_ms {
-                continue;
-            }
+        for frame in play_frames(&path, active_start_ms)? {
+            let frame = frame?;
             if let Some(prev) = last_t {
-                let delta = rec.t_ms.saturating_sub(prev);
+                let delta = frame.t_ms.saturating_sub(prev);
                 if delta > 0 {
                     sleep(Duration::from_millis(delta)).await;
                 }
             }
-            last_t = Some(rec.t_ms);
+            last_t = Some(frame.t_ms);
             let mut send_cfg = cfg.clone();
-            send_cfg.net = rec.net;
-            send_cfg.subnet = rec.subnet;
-            send_cfg.universe = rec.universe;
-            let mut arr = [0u8; 512];
-            for (idx, ch) in channels.iter().enumerate() {
-                if idx < rec.values.len() && *ch >= 1 && *ch <= 512 {
-                    arr[*ch - 1] = rec.values[idx];
-                }
-            }
-            let _ = crate::artnet::send_artdmx(&sock, &send_cfg, &arr, 0).await;
+            send_cfg.port_address = frame.port_address;
+            let _ = crate::artnet::send_artdmx(&sock, &send_cfg, &frame.data, 0).await;
         }
         if !loop_playback {
             break;
@@ -893,9 +2882,37 @@ pub async fn run_play_task(
     Ok(())
 }
 
+/// Plays a .jsonl, .wav, .artrec or pcap recording with the sender config, replacing any
+/// running playback.
+pub fn start_playback(
+    app_state: &AppState,
+    path: String,
+    start_ms: u64,
+    loop_playback: bool,
+) -> Result<()> {
+    app_state.stop_playback();
+    let cfg = app_state.get_sender_config();
+    let handle = if path.to_lowercase().ends_with(".wav") {
+        let wav_data = crate::recording::load_wav(&path)?;
+        tokio::spawn(async move {
+            if let Err(e) = run_wav_play_task(wav_data, cfg, start_ms, loop_playback).await {
+                eprintln!("WAV playback error: {e:?}");
+            }
+        })
+    } else {
+        tokio::spawn(async move {
+            if let Err(e) = run_play_task(path, cfg, start_ms, loop_playback).await {
+                eprintln!("playback error: {e:?}");
+            }
+        })
+    };
+    app_state.set_play_task(handle);
+    Ok(())
+}
+
 // WAV playback task
 pub async fn run_wav_play_task(
-    wav_data: crate::WavRecordingData,
+    wav_data: crate::recording::WavRecordingData,
     cfg: SenderConfig,
     start_ms: u64,
     loop_playback: bool,
@@ -934,3 +2951,111 @@ pub async fn run_wav_play_task(
     }
     Ok(())
 }
+
+const CHASE_TICK: Duration = Duration::from_millis(5);
+/// Without new timecode for this long, playback holds its position.
+const CHASE_STOP_AFTER: Duration = Duration::from_millis(250);
+/// Moves further than this are jumps: only the latest frame of each universe
+/// is sent instead of every frame in between.
+const CHASE_JUMP_MS: u64 = 1000;
+const CHASE_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChaseEvent {
+    pub timecode: String,
+    /// `None` while the timecode is before `origin`.
+    pub position_ms: Option<u64>,
+    pub running: bool,
+}
+
+/// Plays `frames` at the position given by received ArtTimeCode, where
+/// `origin` is the timecode of the recording's start. Needs the receiver.
+pub async fn run_chase_task<E: EventSink>(
+    frames: Vec<PlayFrame>,
+    cfg: SenderConfig,
+    origin: Timecode,
+    events: E,
+    app_state: AppState,
+) -> Result<()> {
+    let sock = artnet::sender_socket().await?;
+    let mut rx = app_state.timecode_in.subscribe();
+    let started = Instant::now();
+    let universes = frames
+        .iter()
+        .map(|f| f.port_address)
+        .collect::<std::collections::BTreeSet<_>>()
+        .len();
+    let mut tick = tokio::time::interval(CHASE_TICK);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    // Index of the next unsent frame and the position it was computed for.
+    let mut last: Option<(usize, u64)> = None;
+    let mut last_event: Option<Instant> = None;
+    let mut send_cfg = cfg.clone();
+    loop {
+        tick.tick().await;
+        let Some(rt) = *rx.borrow_and_update() else {
+            continue;
+        };
+        if rt.at < started {
+            continue;
+        }
+        let age = rt.at.elapsed();
+        let running = age < CHASE_STOP_AFTER;
+        // The origin is read with the incoming frame type.
+        let origin_ms = Timecode {
+            kind: rt.timecode.kind,
+            ..origin
+        }
+        .to_ms();
+        // Between timecode frames run on the local clock, for one frame at most.
+        let ahead = age.min(rt.timecode.kind.frame_duration()).as_millis() as u64;
+        let position = (rt.timecode.to_ms() + ahead).checked_sub(origin_ms);
+
+        if last_event.is_none_or(|t| t.elapsed() >= CHASE_EVENT_INTERVAL) {
+            last_event = Some(Instant::now());
+            events.emit(
+                "artnet:chase",
+                ChaseEvent {
+                    timecode: rt.timecode.to_string(),
+                    position_ms: position,
+                    running,
+                },
+            );
+        }
+        if !running {
+            continue;
+        }
+        let Some(pos) = position else {
+            last = None;
+            continue;
+        };
+        let next = frames.partition_point(|f| f.t_ms <= pos);
+        let to_send: Vec<&PlayFrame> = match last {
+            Some((prev, prev_pos))
+                if next >= prev && pos.saturating_sub(prev_pos) <= CHASE_JUMP_MS =>
+            {
+                frames[prev..next].iter().collect()
+            }
+            _ => {
+                // Jumped or just started: the latest state of every universe.
+                let mut seen = std::collections::BTreeSet::new();
+                let mut latest: Vec<&PlayFrame> = Vec::new();
+                for f in frames[..next].iter().rev() {
+                    if seen.insert(f.port_address) {
+                        latest.push(f);
+                        if seen.len() == universes {
+                            break;
+                        }
+                    }
+                }
+                latest
+            }
+        };
+        last = Some((next, pos));
+        for f in to_send {
+            send_cfg.port_address = f.port_address;
+            let _ = artnet::send_artdmx(&sock, &send_cfg, &f.data, 0).await;
+        }
+    }
+}
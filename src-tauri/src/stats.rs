@@ -0,0 +1,215 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::artnet::{self, DmxFrame, PortAddress};
+use crate::merge::{SourceKey, SEQUENCE_TIMEOUT};
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// Entries silent for longer than this are dropped, like the monitor's universes.
+const STATS_EXPIRY: Duration = Duration::from_secs(10);
+
+/// Health of one stream, either a whole universe or a single source on it.
+#[derive(Default)]
+struct StreamStats {
+    arrivals: VecDeque<Instant>,
+    last_interval: Option<Duration>,
+    jitter_ms: f64,
+    frames: u64,
+    length: u16,
+    last_seen: Option<Instant>,
+}
+
+impl StreamStats {
+    fn record(&mut self, length: u16, now: Instant) {
+        if let Some(prev) = self.last_seen {
+            let interval = now.duration_since(prev);
+            if let Some(last) = self.last_interval {
+                // RFC 3550 style smoothed inter-arrival jitter.
+                let d = (interval.as_secs_f64() - last.as_secs_f64()).abs() * 1000.0;
+                self.jitter_ms += (d - self.jitter_ms) / 16.0;
+            }
+            self.last_interval = Some(interval);
+        }
+        self.arrivals.push_back(now);
+        while self
+            .arrivals
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > RATE_WINDOW)
+        {
+            self.arrivals.pop_front();
+        }
+        self.frames += 1;
+        self.length = length;
+        self.last_seen = Some(now);
+    }
+
+    fn fps(&self, now: Instant) -> f64 {
+        let n = self
+            .arrivals
+            .iter()
+            .filter(|t| now.duration_since(**t) <= RATE_WINDOW)
+            .count();
+        n as f64 / RATE_WINDOW.as_secs_f64()
+    }
+
+    fn age(&self, now: Instant) -> Duration {
+        self.last_seen
+            .map(|t| now.duration_since(t))
+            .unwrap_or(Duration::MAX)
+    }
+}
+
+#[derive(Default)]
+struct SequenceStats {
+    last: Option<(u8, Instant)>,
+    lost: u64,
+    out_of_order: u64,
+    discarded: u64,
+}
+
+impl SequenceStats {
+    /// Sequence numbers run 1..=255 and wrap; 0 means the sender does not
+    /// use them. A jump of up to half the window counts the skipped packets
+    /// as lost, anything behind (or repeated) counts as out of order. After
+    /// `SEQUENCE_TIMEOUT` of silence the count starts over, as in the
+    /// sequence filter.
+    fn record(&mut self, seq: u8, now: Instant) {
+        if seq == 0 {
+            return;
+        }
+        let Some((last, _)) = self
+            .last
+            .filter(|(_, seen)| now.duration_since(*seen) < SEQUENCE_TIMEOUT)
+        else {
+            self.last = Some((seq, now));
+            return;
+        };
+        if artnet::sequence_is_behind(last, seq) {
+            self.out_of_order += 1;
+        } else {
+            self.lost += artnet::sequence_distance(last, seq) as u64 - 1;
+            self.last = Some((seq, now));
+        }
+    }
+}
+
+#[derive(Default)]
+struct SourceEntry {
+    stream: StreamStats,
+    sequence: SequenceStats,
+}
+
+#[derive(Default)]
+struct UniverseEntry {
+    stream: StreamStats,
+    sources: Vec<(SourceKey, SourceEntry)>,
+}
+
+impl UniverseEntry {
+    fn source_mut(&mut self, source: SourceKey) -> &mut SourceEntry {
+        let idx = match self.sources.iter().position(|(k, _)| *k == source) {
+            Some(i) => i,
+            None => {
+                self.sources.push((source, SourceEntry::default()));
+                self.sources.len() - 1
+            }
+        };
+        &mut self.sources[idx].1
+    }
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct SourceStatsInfo {
+    pub source_ip: String,
+    pub physical: u8,
+    pub fps: f64,
+    pub jitter_ms: f64,
+    pub frames: u64,
+    pub lost: u64,
+    pub out_of_order: u64,
+    pub discarded: u64,
+    pub length: u16,
+    pub last_seen_ms: u64, // milliseconds ago
+}
+
+#[derive(Clone, serde::Serialize)]
+pub struct UniverseStatsInfo {
+    pub port_address: PortAddress,
+    pub fps: f64,
+    pub jitter_ms: f64,
+    pub frames: u64,
+    pub lost: u64,
+    pub out_of_order: u64,
+    pub discarded: u64,
+    pub length: u16,
+    pub last_seen_ms: u64,
+    pub sources: Vec<SourceStatsInfo>,
+}
+
+/// Receive statistics per Port-Address and per source.
+#[derive(Default)]
+pub struct ReceiveStats {
+    universes: BTreeMap<PortAddress, UniverseEntry>,
+}
+
+impl ReceiveStats {
+    pub fn record(&mut self, frame: &DmxFrame, source: SourceKey, now: Instant) {
+        let uni = self.universes.entry(frame.port_address).or_default();
+        uni.stream.record(frame.length, now);
+        let entry = uni.source_mut(source);
+        entry.stream.record(frame.length, now);
+        entry.sequence.record(frame.sequence, now);
+    }
+
+    /// Counts a frame dropped by the out-of-order filter.
+    pub fn record_discarded(&mut self, addr: PortAddress, source: SourceKey) {
+        let uni = self.universes.entry(addr).or_default();
+        uni.source_mut(source).sequence.discarded += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.universes.clear();
+    }
+
+    /// Drops silent entries and returns the remaining ones.
+    pub fn snapshot(&mut self, now: Instant) -> Vec<UniverseStatsInfo> {
+        self.universes.retain(|_, uni| {
+            uni.sources
+                .retain(|(_, s)| s.stream.age(now) <= STATS_EXPIRY);
+            !uni.sources.is_empty()
+        });
+        self.universes
+            .iter()
+            .map(|(addr, uni)| {
+                let sources: Vec<SourceStatsInfo> = uni
+                    .sources
+                    .iter()
+                    .map(|(key, s)| SourceStatsInfo {
+                        source_ip: key.ip.to_string(),
+                        physical: key.physical,
+                        fps: s.stream.fps(now),
+                        jitter_ms: s.stream.jitter_ms,
+                        frames: s.stream.frames,
+                        lost: s.sequence.lost,
+                        out_of_order: s.sequence.out_of_order,
+                        discarded: s.sequence.discarded,
+                        length: s.stream.length,
+                        last_seen_ms: s.stream.age(now).as_millis() as u64,
+                    })
+                    .collect();
+                UniverseStatsInfo {
+                    port_address: *addr,
+                    fps: uni.stream.fps(now),
+                    jitter_ms: uni.stream.jitter_ms,
+                    frames: uni.stream.frames,
+                    lost: sources.iter().map(|s| s.lost).sum(),
+                    out_of_order: sources.iter().map(|s| s.out_of_order).sum(),
+                    discarded: sources.iter().map(|s| s.discarded).sum(),
+                    length: uni.stream.length,
+                    last_seen_ms: uni.stream.age(now).as_millis() as u64,
+                    sources,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::artnet;
+
+const TIMECODE_LEN: usize = 19;
+/// Frames in ten minutes of drop-frame timecode.
+const DF_FRAMES_PER_10_MIN: u64 = 17_982;
+/// Frames in a minute that drops two frame numbers.
+const DF_FRAMES_PER_MIN: u64 = 1_798;
+
+/// ArtTimeCode frame type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimecodeType {
+    /// 24 fps
+    Film,
+    /// 25 fps
+    #[default]
+    Ebu,
+    /// 29.97 fps drop-frame
+    Df,
+    /// 30 fps
+    Smpte,
+}
+
+impl TimecodeType {
+    pub fn from_wire(b: u8) -> Result<Self> {
+        match b {
+            0 => Ok(Self::Film),
+            1 => Ok(Self::Ebu),
+            2 => Ok(Self::Df),
+            3 => Ok(Self::Smpte),
+            other => Err(anyhow!("Unknown timecode type {}", other)),
+        }
+    }
+
+    pub fn to_wire(self) -> u8 {
+        match self {
+            Self::Film => 0,
+            Self::Ebu => 1,
+            Self::Df => 2,
+            Self::Smpte => 3,
+        }
+    }
+
+    /// Frame numbers per second (30 for drop-frame).
+    pub fn nominal_fps(self) -> u8 {
+        match self {
+            Self::Film => 24,
+            Self::Ebu => 25,
+            Self::Df | Self::Smpte => 30,
+        }
+    }
+
+    /// Real frame rate as numerator / denominator.
+    fn rate(self) -> (u64, u64) {
+        match self {
+            Self::Df => (30_000, 1_001),
+            other => (other.nominal_fps() as u64, 1),
+        }
+    }
+
+    /// Duration of one frame.
+    pub fn frame_duration(self) -> std::time::Duration {
+        let (num, den) = self.rate();
+        std::time::Duration::from_nanos(den * 1_000_000_000 / num)
+    }
+
+    fn frames_per_day(self) -> u64 {
+        match self {
+            Self::Df => 24 * 6 * DF_FRAMES_PER_10_MIN,
+            other => other.nominal_fps() as u64 * 86_400,
+        }
+    }
+}
+
+impl FromStr for TimecodeType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "film" | "24" => Ok(Self::Film),
+            "ebu" | "25" => Ok(Self::Ebu),
+            "df" | "29.97" => Ok(Self::Df),
+            "smpte" | "30" => Ok(Self::Smpte),
+            _ => Err(anyhow!("Timecode type must be film, ebu, df or smpte")),
+        }
+    }
+}
+
+/// SMPTE/EBU timecode as carried by ArtTimeCode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub kind: TimecodeType,
+}
+
+impl Timecode {
+    /// Checks the field ranges, including the frame numbers drop-frame skips.
+    fn validate(self) -> Result<Self> {
+        if self.hours > 23 || self.minutes > 59 || self.seconds > 59 {
+            return Err(anyhow!("Timecode {} out of range", self));
+        }
+        if self.frames >= self.kind.nominal_fps() {
+            return Err(anyhow!(
+                "Frame {} out of range for {} fps",
+                self.frames,
+                self.kind.nominal_fps()
+            ));
+        }
+        if self.kind == TimecodeType::Df
+            && self.seconds == 0
+            && self.frames < 2
+            && !self.minutes.is_multiple_of(10)
+        {
+            return Err(anyhow!("Drop-frame timecode has no frame {}", self));
+        }
+        Ok(self)
+    }
+
+    /// Parses "HH:MM:SS:FF" (";" before the frames is accepted too).
+    pub fn parse(s: &str, kind: TimecodeType) -> Result<Self> {
+        let parts: Vec<&str> = s.trim().split([':', ';', '.']).collect();
+        let [h, m, sec, f] = parts[..] else {
+            return Err(anyhow!("Expected HH:MM:SS:FF, got {:?}", s));
+        };
+        Self {
+            hours: h.parse()?,
+            minutes: m.parse()?,
+            seconds: sec.parse()?,
+            frames: f.parse()?,
+            kind,
+        }
+        .validate()
+    }
+
+    /// Frames elapsed since 00:00:00:00.
+    pub fn frame_count(self) -> u64 {
+        let fps = self.kind.nominal_fps() as u64;
+        let minutes = self.hours as u64 * 60 + self.minutes as u64;
+        let nominal = (minutes * 60 + self.seconds as u64) * fps + self.frames as u64;
+        match self.kind {
+            TimecodeType::Df => nominal - 2 * (minutes - minutes / 10),
+            _ => nominal,
+        }
+    }
+
+    /// Timecode `count` frames after 00:00:00:00, wrapping at 24 hours.
+    pub fn from_frame_count(count: u64, kind: TimecodeType) -> Self {
+        let mut count = count % kind.frames_per_day();
+        if kind == TimecodeType::Df {
+            // Add back the frame numbers skipped at the start of each minute.
+            let tens = count / DF_FRAMES_PER_10_MIN;
+            let rest = count % DF_FRAMES_PER_10_MIN;
+            count += 18 * tens;
+            if rest >= 2 {
+                count += 2 * ((rest - 2) / DF_FRAMES_PER_MIN);
+            }
+        }
+        let fps = kind.nominal_fps() as u64;
+        Self {
+            hours: (count / (fps * 3600)) as u8,
+            minutes: (count / (fps * 60) % 60) as u8,
+            seconds: (count / fps % 60) as u8,
+            frames: (count % fps) as u8,
+            kind,
+        }
+    }
+
+    /// Wall-clock position of the frame.
+    pub fn to_ms(self) -> u64 {
+        let (num, den) = self.kind.rate();
+        self.frame_count() * 1000 * den / num
+    }
+}
+
+impl fmt::Display for Timecode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.kind == TimecodeType::Df {
+            ';'
+        } else {
+            ':'
+        };
+        write!(
+            f,
+            "{:02}:{:02}:{:02}{}{:02}",
+            self.hours, self.minutes, self.seconds, sep, self.frames
+        )
+    }
+}
+
+pub fn encode_timecode(tc: Timecode, stream_id: u8) -> Vec<u8> {
+    let mut pkt = Vec::with_capacity(TIMECODE_LEN);
+    pkt.extend_from_slice(artnet::ARTNET_ID);
+    pkt.extend_from_slice(&artnet::OP_TIME_CODE.to_le_bytes());
+    pkt.extend_from_slice(&artnet::PROT_VER.to_be_bytes());
+    pkt.push(0); // Filler1
+    pkt.push(stream_id);
+    pkt.push(tc.frames);
+    pkt.push(tc.seconds);
+    pkt.push(tc.minutes);
+    pkt.push(tc.hours);
+    pkt.push(tc.kind.to_wire());
+    pkt
+}
+
+/// Returns the timecode and its stream id.
+pub fn parse_timecode(buf: &[u8]) -> Result<(Timecode, u8)> {
+    if buf.len() < TIMECODE_LEN || artnet::opcode(buf) != Some(artnet::OP_TIME_CODE) {
+        return Err(anyhow!("Not an ArtTimeCode packet"));
+    }
+    let tc = Timecode {
+        frames: buf[14],
+        seconds: buf[15],
+        minutes: buf[16],
+        hours: buf[17],
+        kind: TimecodeType::from_wire(buf[18])?,
+    }
+    .validate()?;
+    Ok((tc, buf[13]))
+}
+
+/// Timecode generator, sent to the sender's target.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TimecodeConfig {
+    /// Start and stop together with the sender.
+    #[serde(default)]
+    pub with_sender: bool,
+    #[serde(default)]
+    pub kind: TimecodeType,
+    /// "HH:MM:SS:FF" to count up from.
+    #[serde(default = "default_start")]
+    pub start: String,
+    #[serde(default)]
+    pub stream_id: u8,
+}
+
+fn default_start() -> String {
+    "00:00:00:00".into()
+}
+
+impl Default for TimecodeConfig {
+    fn default() -> Self {
+        Self {
+            with_sender: false,
+            kind: TimecodeType::default(),
+            start: default_start(),
+            stream_id: 0,
+        }
+    }
+}
+
+impl TimecodeConfig {
+    pub fn start_timecode(&self) -> Result<Timecode> {
+        Timecode::parse(&self.start, self.kind)
+    }
+}
+
+/// Received ArtTimeCode, as reported to the UI.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimecodeEvent {
+    pub source_ip: String,
+    pub stream_id: u8,
+    pub timecode: Timecode,
+    pub text: String,
+    pub ms: u64,
+}
@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+
+use crate::artnet;
+
+/// OemCode / EstaMan addressing every device.
+pub const ALL_DEVICES: u16 = 0xffff;
+const TRIGGER_LEN: usize = 18 + 512;
+const COMMAND_HEADER_LEN: usize = 16;
+const MAX_COMMAND_LEN: usize = 512;
+
+/// ArtTrigger as sent by consoles to fire macros, keys and shows.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtTrigger {
+    pub oem: u16,
+    pub key: u8,
+    pub sub_key: u8,
+    /// Payload with trailing zeros removed.
+    pub data: Vec<u8>,
+}
+
+/// Name of the ArtTrigger key, for display.
+pub fn key_name(key: u8) -> &'static str {
+    match key {
+        0 => "ascii",
+        1 => "macro",
+        2 => "soft",
+        3 => "show",
+        _ => "undefined",
+    }
+}
+
+pub fn encode_trigger(oem: u16, key: u8, sub_key: u8, data: &[u8]) -> Vec<u8> {
+    let mut pkt = Vec::with_capacity(TRIGGER_LEN);
+    pkt.extend_from_slice(artnet::ARTNET_ID);
+    pkt.extend_from_slice(&artnet::OP_TRIGGER.to_le_bytes());
+    pkt.extend_from_slice(&artnet::PROT_VER.to_be_bytes());
+    pkt.extend_from_slice(&[0, 0]); // Filler1-2
+    pkt.extend_from_slice(&oem.to_be_bytes());
+    pkt.push(key);
+    pkt.push(sub_key);
+    let n = data.len().min(512);
+    pkt.extend_from_slice(&data[..n]);
+    pkt.resize(TRIGGER_LEN, 0);
+    pkt
+}
+
+pub fn parse_trigger(buf: &[u8]) -> Result<ArtTrigger> {
+    if buf.len() < 18 || artnet::opcode(buf) != Some(artnet::OP_TRIGGER) {
+        return Err(anyhow!("Not an ArtTrigger packet"));
+    }
+    let mut data = buf[18..buf.len().min(TRIGGER_LEN)].to_vec();
+    while data.last() == Some(&0) {
+        data.pop();
+    }
+    Ok(ArtTrigger {
+        oem: u16::from_be_bytes([buf[14], buf[15]]),
+        key: buf[16],
+        sub_key: buf[17],
+        data,
+    })
+}
+
+/// ArtCommand: "&"-terminated text commands such as "SwoutText=Playback&".
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtCommand {
+    pub esta: u16,
+    pub text: String,
+}
+
+impl ArtCommand {
+    /// The individual commands, without their "&" separators.
+    pub fn commands(&self) -> impl Iterator<Item = &str> {
+        self.text
+            .split('&')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+    }
+}
+
+pub fn encode_command(esta: u16, text: &str) -> Vec<u8> {
+    let mut data: Vec<u8> = text.bytes().take(MAX_COMMAND_LEN - 1).collect();
+    data.push(0);
+    let mut pkt = Vec::with_capacity(COMMAND_HEADER_LEN + data.len());
+    pkt.extend_from_slice(artnet::ARTNET_ID);
+    pkt.extend_from_slice(&artnet::OP_COMMAND.to_le_bytes());
+    pkt.extend_from_slice(&artnet::PROT_VER.to_be_bytes());
+    pkt.extend_from_slice(&esta.to_be_bytes());
+    pkt.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    pkt.extend_from_slice(&data);
+    pkt
+}
+
+pub fn parse_command(buf: &[u8]) -> Result<ArtCommand> {
+    if buf.len() < COMMAND_HEADER_LEN || artnet::opcode(buf) != Some(artnet::OP_COMMAND) {
+        return Err(anyhow!("Not an ArtCommand packet"));
+    }
+    let len = (u16::from_be_bytes([buf[14], buf[15]]) as usize).min(MAX_COMMAND_LEN);
+    let data = &buf[COMMAND_HEADER_LEN..buf.len().min(COMMAND_HEADER_LEN + len)];
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    Ok(ArtCommand {
+        esta: u16::from_be_bytes([buf[12], buf[13]]),
+        text: String::from_utf8_lossy(&data[..end]).into_owned(),
+    })
+}
+
+/// Packet a mapping is matched against.
+pub enum Incoming<'a> {
+    Trigger(&'a ArtTrigger),
+    Command(&'a ArtCommand),
+}
+
+/// What a mapping reacts to.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TriggerMatch {
+    /// ArtTrigger with this key, and this sub-key unless it is left out.
+    #[serde(rename_all = "camelCase")]
+    Trigger {
+        key: u8,
+        #[serde(default)]
+        sub_key: Option<u8>,
+    },
+    /// One ArtCommand entry, compared case-insensitively.
+    Command { text: String },
+}
+
+impl TriggerMatch {
+    pub fn matches(&self, packet: &Incoming) -> bool {
+        match (self, packet) {
+            (Self::Trigger { key, sub_key }, Incoming::Trigger(t)) => {
+                *key == t.key && sub_key.is_none_or(|s| s == t.sub_key)
+            }
+            (Self::Command { text }, Incoming::Command(c)) => {
+                let text = text.trim().trim_end_matches('&');
+                c.commands().any(|cmd| cmd.eq_ignore_ascii_case(text))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Internal action fired by a mapping.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TriggerAction {
+    #[serde(rename_all = "camelCase")]
+    PlayFile {
+        path: String,
+        #[serde(default)]
+        loop_playback: bool,
+    },
+    StopPlayback,
+    /// Runs `mode` ("sinusoid", "ramp", "square", "chaser", "noise") on
+    /// every channel of the selected universe.
+    #[serde(rename_all = "camelCase")]
+    StartAnimation {
+        mode: String,
+        frequency: f64,
+        master_value: u8,
+    },
+    StopAnimation,
+    /// Stops animation and zeroes every output universe.
+    Blackout,
+    RecallSnapshot {
+        name: String,
+    },
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TriggerMapping {
+    pub on: TriggerMatch,
+    pub action: TriggerAction,
+}
+
+pub fn matching_actions<'a>(
+    mappings: &'a [TriggerMapping],
+    packet: &Incoming,
+) -> Vec<&'a TriggerAction> {
+    mappings
+        .iter()
+        .filter(|m| m.on.matches(packet))
+        .map(|m| &m.action)
+        .collect()
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerEvent {
+    pub source_ip: String,
+    pub key_name: &'static str,
+    #[serde(flatten)]
+    pub trigger: ArtTrigger,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandEvent {
+    pub source_ip: String,
+    #[serde(flatten)]
+    pub command: ArtCommand,
+}
+
+/// An action fired by a received packet, and its error if it failed.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerActionEvent {
+    pub action: TriggerAction,
+    pub error: Option<String>,
+}
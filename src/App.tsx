@@ -1,15 +1,30 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import type { DiscoveredNode } from "./artdiscover";
+import { joinPortAddress, splitPortAddress } from "./portAddress";
+import {
+  TIMECODE_TYPES,
+  type TimecodeConfig,
+  type TimecodeType,
+} from "./timecode";
 import "./App.css";
 import MonitorCanvas from "./components/MonitorCanvas";
 import SenderTab from "./components/SenderTab";
 import RecordPlayTab from "./components/RecordPlayTab";
 import DiscoverTab from "./components/DiscoverTab";
+import TriggersTab from "./components/TriggersTab";
+import InspectorTab from "./components/InspectorTab";
+
+// Receiver output mode reported by `artnet:sync_mode`.
+type SyncModeInfo = {
+  mode: "synchronous" | "non_synchronous";
+  source: string | null;
+};
 
 function App() {
   const [tab, setTab] = useState<
-    "monitor" | "sender" | "recplay" | "discover"
+    "monitor" | "sender" | "recplay" | "discover" | "triggers" | "inspector"
   >("monitor");
   const [faders, setFaders] = useState<number[]>(Array(512).fill(0));
   // path handled within RecordPlayTab now
@@ -19,7 +34,12 @@ function App() {
   // SETTINGS state
   const [showMon, setShowMon] = useState(false);
   const [showSnd, setShowSnd] = useState(false);
-  const [monCfg, setMonCfg] = useState({ bind_ip: "0.0.0.0", port: 6454 });
+  const [monCfg, setMonCfg] = useState({
+    bind_ip: "0.0.0.0",
+    port: 6454,
+    merge_mode: "htp",
+    reject_out_of_order: false,
+  });
   const [sndCfg, setSndCfg] = useState({
     target_ip: "255.255.255.255",
     port: 6454,
@@ -27,20 +47,42 @@ function App() {
     net: 0,
     subnet: 0,
     universe: 0,
+    sync: false,
+    unicast_subscribers: false,
+  });
+  // Non-zero sends ArtNzs with this start code instead of ArtDmx.
+  const [startCode, setStartCode] = useState(0);
+  const [tcCfg, setTcCfg] = useState<TimecodeConfig>({
+    with_sender: false,
+    kind: "ebu",
+    start: "00:00:00:00",
+    stream_id: 0,
   });
 
   const [discoveryIntervalSec, setDiscoveryIntervalSec] = useState(10);
   const [discoveredNodes, setDiscoveredNodes] = useState<DiscoveredNode[]>([]);
   const [discoveryScanning, setDiscoveryScanning] = useState(false);
   const [discoveryError, setDiscoveryError] = useState<string | null>(null);
+  const [syncMode, setSyncMode] = useState<SyncModeInfo>({
+    mode: "non_synchronous",
+    source: null,
+  });
   const discoveryInFlightRef = useRef(false);
 
   // Load settings once
   useEffect(() => {
     invoke("load_settings")
       .then((s: any) => {
-        if (s?.receiver) setMonCfg(s.receiver);
-        if (s?.sender) setSndCfg(s.sender);
+        if (s?.receiver) setMonCfg({
+            merge_mode: "htp",
+            reject_out_of_order: false,
+            ...s.receiver,
+          });
+        if (s?.sender) {
+          const { port_address, ...rest } = s.sender;
+          setSndCfg({ ...rest, ...splitPortAddress(Number(port_address) || 0) });
+        }
+        if (s?.timecode) setTcCfg((prev) => ({ ...prev, ...s.timecode }));
         const di = Number(s?.discovery_interval_sec);
         if (Number.isFinite(di)) {
           const v = Math.max(0, Math.min(86400, Math.round(di)));
@@ -52,6 +94,26 @@ function App() {
       });
   }, []);
 
+  const senderConfigPayload = useCallback(
+    () => ({
+      target_ip: sndCfg.target_ip,
+      port: sndCfg.port,
+      port_address: joinPortAddress(sndCfg),
+      fps: sndCfg.fps,
+      sync: sndCfg.sync,
+      unicast_subscribers: sndCfg.unicast_subscribers,
+    }),
+    [sndCfg]
+  );
+
+  const applySenderConfig = useCallback(async () => {
+    await invoke("set_sender_config", { cfg: senderConfigPayload() });
+    await invoke("set_sender_universe_start_code", {
+      portAddress: joinPortAddress(sndCfg),
+      startCode,
+    });
+  }, [senderConfigPayload, sndCfg, startCode]);
+
   const performDiscovery = useCallback(
     async (extraBroadcastIps?: string[], timeoutMs = 2000) => {
       if (discoveryInFlightRef.current) return;
@@ -59,20 +121,15 @@ function App() {
       setDiscoveryScanning(true);
       setDiscoveryError(null);
       try {
-        const list = await invoke<DiscoveredNode[]>("artnet_discover", {
-          cfg: {
-            target_ip: sndCfg.target_ip,
-            port: sndCfg.port,
-            net: sndCfg.net,
-            subnet: sndCfg.subnet,
-            universe: sndCfg.universe,
-            fps: sndCfg.fps,
-          },
+        await invoke<DiscoveredNode[]>("artnet_discover", {
+          cfg: senderConfigPayload(),
           extraBroadcastIps:
             extraBroadcastIps?.length ? extraBroadcastIps : null,
           timeoutMs,
         });
-        setDiscoveredNodes(list);
+        setDiscoveredNodes(
+          await invoke<DiscoveredNode[]>("list_discovered_nodes")
+        );
       } catch (e) {
         setDiscoveryError(String(e));
       } finally {
@@ -80,16 +137,23 @@ function App() {
         setDiscoveryScanning(false);
       }
     },
-    [sndCfg]
+    [senderConfigPayload]
   );
 
+  // The backend polls every discoveryIntervalSec and reports node changes.
   useEffect(() => {
-    if (discoveryIntervalSec <= 0) return undefined;
-    const run = () => void performDiscovery();
-    const id = window.setInterval(run, discoveryIntervalSec * 1000);
-    run();
-    return () => window.clearInterval(id);
-  }, [discoveryIntervalSec, performDiscovery]);
+    const refresh = () =>
+      invoke<DiscoveredNode[]>("list_discovered_nodes")
+        .then(setDiscoveredNodes)
+        .catch(() => {});
+    refresh();
+    const uns = [
+      "discovery:node_added",
+      "discovery:node_changed",
+      "discovery:node_lost",
+    ].map((ev) => listen(ev, refresh));
+    return () => uns.forEach((un) => void un.then((f) => f()));
+  }, []);
 
   // Auto-start monitor on app open (backend also autostarts; this ensures it runs even if settings aren't loaded yet)
   useEffect(() => {
@@ -100,6 +164,17 @@ function App() {
     })();
   }, []);
 
+  // The receiver switches to synchronous mode while ArtSync keeps arriving.
+  useEffect(() => {
+    invoke<SyncModeInfo>("get_receiver_sync_mode")
+      .then(setSyncMode)
+      .catch(() => {});
+    const un = listen<SyncModeInfo>("artnet:sync_mode", (e) =>
+      setSyncMode(e.payload)
+    );
+    return () => void un.then((f) => f());
+  }, []);
+
   // MonitorCanvas handles its own event subscription for performance.
 
   // Monitor runs automatically; no start/stop controls
@@ -110,7 +185,13 @@ function App() {
       await invoke("stop_sender");
       setSenderRunning(false);
     } else {
-      await invoke("set_sender_config", { cfg: sndCfg });
+      try {
+        await applySenderConfig();
+      } catch (e) {
+        alert(String(e));
+        return;
+      }
+      await invoke("set_timecode_config", { cfg: tcCfg });
       await invoke("start_sender");
       setSenderRunning(true);
     }
@@ -253,8 +334,30 @@ function App() {
           >
             Discover
           </button>
+          <button
+            className={`tab ${tab === "triggers" ? "active" : ""}`}
+            onClick={() => setTab("triggers")}
+          >
+            Triggers
+          </button>
+          <button
+            className={`tab ${tab === "inspector" ? "active" : ""}`}
+            onClick={() => setTab("inspector")}
+          >
+            Inspector
+          </button>
         </nav>
         <div className="spacer" />
+        {tab === "monitor" && (
+          <span
+            className="status"
+            title="Receiver output mode: synchronous while ArtSync is received"
+          >
+            {syncMode.mode === "synchronous"
+              ? `Synchronous (ArtSync from ${syncMode.source ?? "?"})`
+              : "Non-synchronous"}
+          </span>
+        )}
         {tab === "monitor" && (
           <button
             className="iconbtn"
@@ -315,6 +418,14 @@ function App() {
             onScan={(extras, tm) => void performDiscovery(extras, tm)}
           />
         </section>
+
+        <section className={`view ${tab === "triggers" ? "active" : ""}`}>
+          <TriggersTab />
+        </section>
+
+        <section className={`view ${tab === "inspector" ? "active" : ""}`}>
+          <InspectorTab active={tab === "inspector"} />
+        </section>
       </main>
 
       {/* Monitor settings modal */}
@@ -345,6 +456,78 @@ function App() {
               }
             />
           </div>
+          <div className="row">
+            <label>Merge mode</label>
+            <select
+              value={monCfg.merge_mode}
+              onChange={(e) =>
+                setMonCfg({ ...monCfg, merge_mode: e.currentTarget.value })
+              }
+            >
+              <option value="htp">HTP (highest takes precedence)</option>
+              <option value="ltp">LTP (latest takes precedence)</option>
+            </select>
+          </div>
+          <div className="row">
+            <label>Drop out-of-order packets</label>
+            <input
+              type="checkbox"
+              checked={!!monCfg.reject_out_of_order}
+              onChange={(e) =>
+                setMonCfg({
+                  ...monCfg,
+                  reject_out_of_order: e.currentTarget.checked,
+                })
+              }
+            />
+          </div>
+          <div className="row">
+            <label>Send ArtSync</label>
+            <input
+              type="checkbox"
+              checked={!!sndCfg.sync}
+              onChange={(e) =>
+                setSndCfg({ ...sndCfg, sync: e.currentTarget.checked })
+              }
+            />
+          </div>
+          <div className="row">
+            <label>Unicast to subscribed nodes</label>
+            <input
+              type="checkbox"
+              checked={!!sndCfg.unicast_subscribers}
+              onChange={(e) =>
+                setSndCfg({
+                  ...sndCfg,
+                  unicast_subscribers: e.currentTarget.checked,
+                })
+              }
+            />
+          </div>
+          <p className="field-hint">
+            Sends each universe only to discovered nodes whose output ports
+            take it, broadcasting while none are known. Nodes are polled every
+            10 s when the discovery interval is 0.
+          </p>
+          <div className="row">
+            <label>Start code</label>
+            <input
+              type="number"
+              min={0}
+              max={255}
+              value={startCode}
+              onChange={(e) =>
+                setStartCode(
+                  Math.max(0, Math.min(255, Number(e.currentTarget.value) | 0))
+                )
+              }
+            />
+          </div>
+          <p className="field-hint">
+            0 sends DMX (ArtDmx). Any other value, e.g. 23 for text, sends
+            ArtNzs with that start code (sACN universes carry it in the DMP
+            layer).
+          </p>
           <div className="row">
             <label>Discovery interval (sec)</label>
             <input
@@ -485,12 +668,60 @@ function App() {
             0 disables auto-discovery (default 10). Same value saved with
             settings.
           </p>
+          <div className="row">
+            <label>Send ArtTimeCode</label>
+            <input
+              type="checkbox"
+              checked={tcCfg.with_sender}
+              onChange={(e) =>
+                setTcCfg({ ...tcCfg, with_sender: e.currentTarget.checked })
+              }
+            />
+          </div>
+          <div className="row">
+            <label>Timecode type</label>
+            <select
+              value={tcCfg.kind}
+              onChange={(e) =>
+                setTcCfg({
+                  ...tcCfg,
+                  kind: e.currentTarget.value as TimecodeType,
+                })
+              }
+            >
+              {TIMECODE_TYPES.map((t) => (
+                <option key={t.value} value={t.value}>
+                  {t.label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div className="row">
+            <label>Timecode start</label>
+            <input
+              value={tcCfg.start}
+              placeholder="HH:MM:SS:FF"
+              onChange={(e) =>
+                setTcCfg({ ...tcCfg, start: e.currentTarget.value })
+              }
+            />
+          </div>
+          <p className="field-hint">
+            Timecode runs from the start value while the sender is on, to the
+            same target IP and port.
+          </p>
           {discoveryPicker}
           <div className="actions">
             <button
               className="btn"
               onClick={async () => {
-                await invoke("set_sender_config", { cfg: sndCfg });
+                try {
+                  await applySenderConfig();
+                  await invoke("set_timecode_config", { cfg: tcCfg });
+                } catch (e) {
+                  alert(String(e));
+                  return;
+                }
                 await saveSettings();
                 setShowSnd(false);
               }}
@@ -1,3 +1,74 @@
+export type PortProtocol =
+  | "dmx512"
+  | "midi"
+  | "avab"
+  | "colortranCmx"
+  | "adb625"
+  | "artNet"
+  | "dali"
+  | { other: number };
+
+export type InputPortStatus = {
+  portAddress: number;
+  dataReceived: boolean;
+  testPackets: boolean;
+  sips: boolean;
+  textPackets: boolean;
+  disabled: boolean;
+  receiveErrors: boolean;
+  convertsTo: "artnet" | "sacn";
+};
+
+export type OutputPortStatus = {
+  portAddress: number;
+  dataTransmitting: boolean;
+  testPackets: boolean;
+  sips: boolean;
+  textPackets: boolean;
+  merging: boolean;
+  shortDetected: boolean;
+  mergeMode: "htp" | "ltp";
+  source: "artnet" | "sacn";
+  rdmEnabled: boolean;
+  continuous: boolean;
+};
+
+export type NodePortInfo = {
+  index: number;
+  protocol: PortProtocol;
+  input: InputPortStatus | null;
+  output: OutputPortStatus | null;
+};
+
+export type NodeStatus1 = {
+  indicators: "unknown" | "locate" | "mute" | "normal";
+  programmingAuthority: "unknown" | "frontPanel" | "network" | "notUsed";
+  romBoot: boolean;
+  rdmCapable: boolean;
+  ubeaPresent: boolean;
+};
+
+export type NodeStatus2 = {
+  webConfig: boolean;
+  dhcpConfigured: boolean;
+  dhcpCapable: boolean;
+  portAddress15bit: boolean;
+  sacnSwitchable: boolean;
+  squawking: boolean;
+  outputStyleSwitchable: boolean;
+  rdmViaArtCommand: boolean;
+};
+
+export type NodeStatus3 = {
+  failsafe: "holdLast" | "allZero" | "allFull" | "scene";
+  failsafeProgrammable: boolean;
+  llrp: boolean;
+  portDirectionSwitchable: boolean;
+  rdmnet: boolean;
+  backgroundQueue: boolean;
+  backgroundDiscoveryProgrammable: boolean;
+};
+
 export type DiscoveredNode = {
   udpSource: string;
   ip: string;
@@ -8,21 +79,172 @@ export type DiscoveredNode = {
   netSwitch: number;
   subSwitch: number;
   firmware: string;
-  oemHex: string;
+  oem: number;
   estaCode: number;
   mac: string;
   bindIp: string;
   bindIndex: number;
   style: number;
-  numPorts: number[];
-  portTypesHex: string;
-  swinHex: string;
-  swoutHex: string;
+  styleName: string;
+  numPorts: number;
+  ports: NodePortInfo[];
+  acnPriority: number;
+  status1: NodeStatus1;
+  status2: NodeStatus2;
+  status3: NodeStatus3;
+  /** Unix time in ms, set for nodes tracked by background discovery. */
+  firstSeenMs?: number;
+  lastSeenMs?: number;
+};
+
+const PROTOCOL_LABELS: Record<string, string> = {
+  dmx512: "DMX",
+  midi: "MIDI",
+  avab: "Avab",
+  colortranCmx: "CMX",
+  adb625: "ADB",
+  artNet: "Art-Net",
+  dali: "DALI",
 };
 
+export function portProtocolLabel(p: PortProtocol): string {
+  if (typeof p === "string") return PROTOCOL_LABELS[p] ?? p;
+  return `0x${p.other.toString(16).padStart(2, "0")}`;
+}
+
 export function parseDiscoverExtraIps(raw: string): string[] {
   return raw
     .split(/[\s,]+/)
     .map((s) => s.trim())
     .filter(Boolean);
 }
+
+/** ArtAddress Command; port commands address 0..3 within the node's page. */
+export type AddressCommand =
+  | "none"
+  | "cancelMerge"
+  | "ledNormal"
+  | "ledMute"
+  | "ledLocate"
+  | "resetRxFlags"
+  | "failHold"
+  | "failZero"
+  | "failFull"
+  | "failScene"
+  | { mergeLtp: number }
+  | { mergeHtp: number }
+  | { artNetSel: number }
+  | { acnSel: number }
+  | { clearOutput: number };
+
+/** Fields left null are not changed on the node. */
+export type AddressRequest = {
+  ip: string;
+  port?: number;
+  bind_index: number;
+  short_name: string | null;
+  long_name: string | null;
+  net_switch: number | null;
+  sub_switch: number | null;
+  sw_in: (number | null)[];
+  sw_out: (number | null)[];
+  acn_priority: number | null;
+  command: AddressCommand;
+};
+
+export type AddressOutcome = {
+  reply: DiscoveredNode | null;
+  mismatches: string[];
+  verified: boolean;
+};
+
+/** ArtIpProg; with nothing set it only reads the node's settings. */
+export type IpProgRequest = {
+  ip: string;
+  port?: number;
+  new_ip: string | null;
+  subnet_mask: string | null;
+  gateway: string | null;
+  prog_port: number | null;
+  dhcp: boolean;
+  reset_defaults: boolean;
+};
+
+export type IpSettings = {
+  ip: string;
+  subnetMask: string;
+  gateway: string;
+  port: number;
+  dhcp: boolean;
+};
+
+export type IpProgOutcome = {
+  reply: IpSettings | null;
+  replyFrom: string | null;
+  mismatches: string[];
+  verified: boolean;
+};
+
+/** RDM UID as "mmmm:dddddddd" (hex manufacturer and device id). */
+export type Uid = string;
+
+/** Table of devices behind one output port of a node. */
+export type TodEntry = {
+  nodeIp: string;
+  bindIndex: number;
+  port: number;
+  portAddress: number;
+  uids: Uid[];
+  complete: boolean;
+};
+
+export type RdmParam =
+  | "deviceInfo"
+  | "deviceLabel"
+  | "dmxStartAddress"
+  | "dmxPersonality"
+  | "identifyDevice"
+  | { sensorValue: number };
+
+export type RdmSetValue =
+  | { deviceLabel: string }
+  | { dmxStartAddress: number }
+  | { dmxPersonality: number }
+  | { identifyDevice: boolean }
+  | { sensorValue: number };
+
+export type RdmDeviceInfo = {
+  protocolVersion: number;
+  modelId: number;
+  productCategory: number;
+  softwareVersion: number;
+  footprint: number;
+  personality: number;
+  personalityCount: number;
+  startAddress: number;
+  subDeviceCount: number;
+  sensorCount: number;
+};
+
+export type RdmSensorReading = {
+  sensor: number;
+  value: number;
+  lowest: number;
+  highest: number;
+  recorded: number;
+};
+
+export type RdmValue =
+  | { deviceInfo: RdmDeviceInfo }
+  | { deviceLabel: string }
+  | { dmxStartAddress: number }
+  | { dmxPersonality: { current: number; count: number } }
+  | { identifyDevice: boolean }
+  | { sensorValue: RdmSensorReading };
+
+export type RdmReply = {
+  status: "ack" | "ackTimer" | "nack" | "ackOverflow";
+  ackTimerMs: number | null;
+  nackReason: string | null;
+  value: RdmValue | null;
+};
@@ -1,8 +1,52 @@
 import { useState } from "react";
 import {
   type DiscoveredNode,
+  type NodePortInfo,
   parseDiscoverExtraIps,
+  portProtocolLabel,
 } from "../artdiscover";
+import { portAddressKey } from "../portAddress";
+import NodeAddressPanel from "./NodeAddressPanel";
+import NodeIpPanel from "./NodeIpPanel";
+import NodeRdmPanel from "./NodeRdmPanel";
+
+function portLines(port: NodePortInfo): string[] {
+  const proto = portProtocolLabel(port.protocol);
+  const lines: string[] = [];
+  if (port.input) {
+    const i = port.input;
+    lines.push(
+      [
+        `IN ${portAddressKey(i.portAddress)}`,
+        proto,
+        i.convertsTo === "sacn" ? "sACN" : null,
+        i.dataReceived ? "data" : null,
+        i.disabled ? "disabled" : null,
+        i.receiveErrors ? "errors" : null,
+      ]
+        .filter(Boolean)
+        .join(" "),
+    );
+  }
+  if (port.output) {
+    const o = port.output;
+    lines.push(
+      [
+        `OUT ${portAddressKey(o.portAddress)}`,
+        proto,
+        o.source === "sacn" ? "sACN" : "Art-Net",
+        o.mergeMode.toUpperCase(),
+        o.merging ? "merging" : null,
+        o.dataTransmitting ? "data" : null,
+        o.rdmEnabled ? "RDM" : null,
+        o.shortDetected ? "short" : null,
+      ]
+        .filter(Boolean)
+        .join(" "),
+    );
+  }
+  return lines;
+}
 
 interface DiscoverTabProps {
   onApplyTargetIp: (ip: string) => void;
@@ -23,6 +67,12 @@ export default function DiscoverTab({
 }: DiscoverTabProps) {
   const [extraBroadcasts, setExtraBroadcasts] = useState("");
   const [timeoutMs, setTimeoutMs] = useState(2000);
+  const [configuring, setConfiguring] = useState<{
+    key: string;
+    panel: "address" | "ip" | "rdm";
+  } | null>(null);
+  const rowKey = (r: DiscoveredNode) => `${r.ip}-${r.mac}-${r.bindIndex}`;
+  const configNode = rows.find((r) => rowKey(r) === configuring?.key);
 
   return (
     <section className="discover-pane">
@@ -70,6 +120,27 @@ export default function DiscoverTab({
         </span>
       </div>
       {error && <div className="discover-error">{error}</div>}
+      {configNode && configuring?.panel === "address" && (
+        <NodeAddressPanel
+          key={configuring.key}
+          node={configNode}
+          onClose={() => setConfiguring(null)}
+        />
+      )}
+      {configNode && configuring?.panel === "ip" && (
+        <NodeIpPanel
+          key={configuring.key}
+          node={configNode}
+          onClose={() => setConfiguring(null)}
+        />
+      )}
+      {configNode && configuring?.panel === "rdm" && (
+        <NodeRdmPanel
+          key={configuring.key}
+          node={configNode}
+          onClose={() => setConfiguring(null)}
+        />
+      )}
       <div className="discover-table-wrap">
         <table className="discover-table">
           <thead>
@@ -84,8 +155,8 @@ export default function DiscoverTab({
               <th>Net/Sub</th>
               <th>ESTA</th>
               <th>OEM</th>
+              <th>Style</th>
               <th>Ports</th>
-              <th>SwOut</th>
               <th>Status</th>
             </tr>
           </thead>
@@ -98,7 +169,7 @@ export default function DiscoverTab({
               </tr>
             )}
             {rows.map((r) => (
-              <tr key={`${r.ip}-${r.mac}-${r.udpSource}`}>
+              <tr key={rowKey(r)}>
                 <td>
                   <button
                     type="button"
@@ -106,6 +177,29 @@ export default function DiscoverTab({
                     onClick={() => onApplyTargetIp(r.ip)}
                   >
                     Use IP
+                  </button>{" "}
+                  <button
+                    type="button"
+                    className="btn btn-small"
+                    onClick={() =>
+                      setConfiguring({ key: rowKey(r), panel: "address" })
+                    }
+                  >
+                    Configure
+                  </button>{" "}
+                  <button
+                    type="button"
+                    className="btn btn-small"
+                    onClick={() => setConfiguring({ key: rowKey(r), panel: "ip" })}
+                  >
+                    IP…
+                  </button>{" "}
+                  <button
+                    type="button"
+                    className="btn btn-small"
+                    onClick={() => setConfiguring({ key: rowKey(r), panel: "rdm" })}
+                  >
+                    RDM…
                   </button>
                 </td>
                 <td className="mono">{r.ip}</td>
@@ -118,10 +212,24 @@ export default function DiscoverTab({
                   {r.netSwitch}/{r.subSwitch}
                 </td>
                 <td className="mono">{r.estaCode}</td>
-                <td className="mono">{r.oemHex}</td>
-                <td className="mono discover-small">{r.numPorts.join("/")}</td>
-                <td className="mono discover-small">{r.swoutHex}</td>
+                <td className="mono">
+                  0x{r.oem.toString(16).padStart(4, "0")}
+                </td>
+                <td className="discover-small">{r.styleName}</td>
+                <td className="mono discover-small">
+                  {r.ports.length === 0
+                    ? "—"
+                    : r.ports.flatMap(portLines).map((line, i) => (
+                        <div key={i}>{line}</div>
+                      ))}
+                </td>
                 <td className="discover-report" title={r.nodeReport}>
+                  {r.status1.indicators !== "normal" &&
+                    r.status1.indicators !== "unknown" && (
+                      <strong>{r.status1.indicators} · </strong>
+                    )}
+                  {r.status1.rdmCapable && "RDM · "}
+                  {r.status2.dhcpConfigured && "DHCP · "}
                   {r.nodeReport || "—"}
                 </td>
               </tr>
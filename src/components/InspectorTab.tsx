@@ -0,0 +1,233 @@
+import { useEffect, useRef, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { save as dialogSave } from "@tauri-apps/plugin-dialog";
+import {
+  formatOpcode,
+  type CaptureStatus,
+  type InspectedPacket,
+  type PacketField,
+} from "../inspector";
+
+const POLL_MS = 500;
+
+function FieldTree({ fields }: { fields: PacketField[] }) {
+  return (
+    <ul className="inspector-tree">
+      {fields.map((f, i) => (
+        <li key={`${f.name}-${i}`}>
+          <span className="field-name">{f.name}</span>
+          {f.children?.length ? (
+            <FieldTree fields={f.children} />
+          ) : (
+            <>
+              : <span className="field-value mono">{f.value ?? ""}</span>
+            </>
+          )}
+        </li>
+      ))}
+    </ul>
+  );
+}
+
+const formatTime = (ms: number) =>
+  `${new Date(ms).toLocaleTimeString()}.${String(ms % 1000).padStart(3, "0")}`;
+
+export default function InspectorTab({ active }: { active: boolean }) {
+  const [status, setStatus] = useState<CaptureStatus | null>(null);
+  const [capacity, setCapacity] = useState(2000);
+  const [packets, setPackets] = useState<InspectedPacket[]>([]);
+  const [filter, setFilter] = useState("");
+  const [hideDmx, setHideDmx] = useState(true);
+  const [selected, setSelected] = useState<number | null>(null);
+  const [error, setError] = useState<string | null>(null);
+  const [exported, setExported] = useState<string | null>(null);
+  const nextIdRef = useRef(0);
+
+  useEffect(() => {
+    invoke<CaptureStatus>("get_packet_capture_status")
+      .then((s) => {
+        setStatus(s);
+        setCapacity(s.capacity);
+      })
+      .catch((e) => setError(String(e)));
+  }, []);
+
+  // Fetch new packets while capturing and the tab is visible.
+  useEffect(() => {
+    if (!active || !status?.enabled) return;
+    const timer = window.setInterval(async () => {
+      try {
+        const fresh = await invoke<InspectedPacket[]>("get_captured_packets", {
+          fromId: nextIdRef.current,
+          limit: capacity,
+        });
+        if (fresh.length === 0) return;
+        nextIdRef.current = fresh[fresh.length - 1].id + 1;
+        setPackets((prev) => [...prev, ...fresh].slice(-capacity));
+      } catch (e) {
+        setError(String(e));
+      }
+    }, POLL_MS);
+    return () => window.clearInterval(timer);
+  }, [active, status?.enabled, capacity]);
+
+  async function setCapture(enabled: boolean) {
+    setError(null);
+    try {
+      setStatus(await invoke<CaptureStatus>("set_packet_capture", { enabled, capacity }));
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function clear() {
+    await invoke("clear_captured_packets").catch((e) => setError(String(e)));
+    setPackets([]);
+    setSelected(null);
+  }
+
+  async function exportPcapng() {
+    setError(null);
+    setExported(null);
+    const path = await dialogSave({
+      defaultPath: "artnet-capture.pcapng",
+      filters: [{ name: "Wireshark capture", extensions: ["pcapng"] }],
+    });
+    if (!path) return;
+    try {
+      const count = await invoke<number>("export_capture_pcapng", { path });
+      setExported(`Saved ${count} packets to ${path}`);
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  const needle = filter.trim().toLowerCase();
+  const shown = packets.filter(
+    (p) =>
+      !(hideDmx && p.opcodeName === "ArtDmx") &&
+      (!needle ||
+        p.opcodeName.toLowerCase().includes(needle) ||
+        p.source.includes(needle)),
+  );
+  const current = packets.find((p) => p.id === selected);
+
+  return (
+    <section className="discover-pane">
+      <div className="discover-toolbar">
+        <button
+          type="button"
+          className="btn"
+          onClick={() => void setCapture(!status?.enabled)}
+        >
+          {status?.enabled ? "Stop capture" : "Start capture"}
+        </button>
+        <label className="discover-label">
+          Keep
+          <input
+            type="number"
+            min={1}
+            max={100000}
+            className="discover-input discover-input-narrow"
+            value={capacity}
+            onChange={(e) =>
+              setCapacity(Math.min(100000, Math.max(1, Number(e.currentTarget.value) || 1)))
+            }
+          />
+          packets
+        </label>
+        <label className="discover-label">
+          Filter
+          <input
+            type="text"
+            placeholder="OpCode or source"
+            className="discover-input"
+            value={filter}
+            onChange={(e) => setFilter(e.currentTarget.value)}
+          />
+        </label>
+        <label className="discover-label">
+          Hide ArtDmx
+          <input
+            type="checkbox"
+            checked={hideDmx}
+            onChange={(e) => setHideDmx(e.currentTarget.checked)}
+          />
+        </label>
+        <button type="button" className="btn" onClick={() => void clear()}>
+          Clear
+        </button>
+        <button type="button" className="btn" onClick={() => void exportPcapng()}>
+          Export pcapng…
+        </button>
+        <span className="discover-hint">
+          {exported ?? `${shown.length} of ${packets.length} shown`}
+        </span>
+      </div>
+      {status && !status.enabled && packets.length === 0 && (
+        <p className="field-hint">
+          Capture records every packet reaching the monitor's Art-Net port, decoded by
+          OpCode. The monitor must be running.
+        </p>
+      )}
+      {error && <div className="discover-error">{error}</div>}
+      <div className="inspector-layout">
+        <div className="discover-table-wrap inspector-list">
+          <table className="discover-table">
+            <thead>
+              <tr>
+                <th>#</th>
+                <th>Time</th>
+                <th>Source</th>
+                <th>OpCode</th>
+                <th>Ver</th>
+                <th>Bytes</th>
+              </tr>
+            </thead>
+            <tbody>
+              {shown.length === 0 ? (
+                <tr>
+                  <td colSpan={6} className="discover-empty">
+                    No packets
+                  </td>
+                </tr>
+              ) : (
+                shown.map((p) => (
+                  <tr
+                    key={p.id}
+                    className={p.id === selected ? "selected" : ""}
+                    onClick={() => setSelected(p.id)}
+                  >
+                    <td className="mono">{p.id}</td>
+                    <td className="mono">{formatTime(p.atMs)}</td>
+                    <td className="mono">{p.source}</td>
+                    <td>
+                      {p.opcodeName}{" "}
+                      <span className="mono node-address-sub">{formatOpcode(p.opcode)}</span>
+                    </td>
+                    <td className="mono">{p.protVer ?? "—"}</td>
+                    <td className="mono">{p.length}</td>
+                  </tr>
+                ))
+              )}
+            </tbody>
+          </table>
+        </div>
+        <div className="inspector-detail">
+          {current ? (
+            <>
+              <div className="node-address-title">
+                {current.opcodeName} from {current.source}
+              </div>
+              {current.error && <div className="discover-error">{current.error}</div>}
+              {current.fields.length > 0 && <FieldTree fields={current.fields} />}
+              <div className="inspector-hex">{current.hex}</div>
+            </>
+          ) : (
+            <div className="discover-empty">Select a packet</div>
+          )}
+        </div>
+      </div>
+    </section>
+  );
+}
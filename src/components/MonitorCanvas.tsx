@@ -2,12 +2,12 @@ import { useCallback, useEffect, useRef, useState, useMemo } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { Stage, Layer, Rect, Text, Group } from "react-konva";
 import ChannelTooltip from "./ChannelTooltip";
+import NzsPanel from "./NzsPanel";
+import { portAddressKey } from "../portAddress";
 
 type Frame = {
   values: number[];
-  net: number;
-  subnet: number;
-  universe: number;
+  port_address: number;
 };
 
 interface ChannelData {
@@ -328,7 +328,7 @@ export default function MonitorCanvas() {
     const un = listen<Frame>("artnet:dmx", (e) => {
       const p = e.payload;
       if (!p) return;
-      const key = `${p.net}/${p.subnet}/${p.universe}`;
+      const key = portAddressKey(p.port_address);
       let buf = currentBufRef.current;
       if (!buf) {
         buf = new Uint8Array(512);
@@ -473,6 +473,8 @@ export default function MonitorCanvas() {
         })}
       </div>
 
+      <NzsPanel />
+
       {hoveredChannel && (
         <ChannelTooltip
           channel={hoveredChannel}
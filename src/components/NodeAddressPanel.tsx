@@ -0,0 +1,273 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  AddressCommand,
+  AddressOutcome,
+  AddressRequest,
+  DiscoveredNode,
+} from "../artdiscover";
+
+interface NodeAddressPanelProps {
+  node: DiscoveredNode;
+  onClose: () => void;
+}
+
+type CommandKind =
+  | "none"
+  | "ledNormal"
+  | "ledMute"
+  | "ledLocate"
+  | "cancelMerge"
+  | "mergeLtp"
+  | "mergeHtp"
+  | "artNetSel"
+  | "acnSel"
+  | "clearOutput";
+
+const COMMANDS: { kind: CommandKind; label: string; perPort: boolean }[] = [
+  { kind: "none", label: "No command", perPort: false },
+  { kind: "ledLocate", label: "LED locate", perPort: false },
+  { kind: "ledMute", label: "LED mute", perPort: false },
+  { kind: "ledNormal", label: "LED normal", perPort: false },
+  { kind: "cancelMerge", label: "Cancel merge", perPort: false },
+  { kind: "mergeHtp", label: "Merge HTP", perPort: true },
+  { kind: "mergeLtp", label: "Merge LTP", perPort: true },
+  { kind: "artNetSel", label: "Output Art-Net", perPort: true },
+  { kind: "acnSel", label: "Output sACN", perPort: true },
+  { kind: "clearOutput", label: "Clear output buffer", perPort: true },
+];
+
+function toCommand(kind: CommandKind, port: number): AddressCommand {
+  switch (kind) {
+    case "mergeLtp":
+      return { mergeLtp: port };
+    case "mergeHtp":
+      return { mergeHtp: port };
+    case "artNetSel":
+      return { artNetSel: port };
+    case "acnSel":
+      return { acnSel: port };
+    case "clearOutput":
+      return { clearOutput: port };
+    default:
+      return kind;
+  }
+}
+
+function portUniverse(node: DiscoveredNode, index: number, dir: "input" | "output") {
+  const pa = node.ports.find((p) => p.index === index)?.[dir]?.portAddress;
+  return pa === undefined ? null : pa & 0x0f;
+}
+
+// Unchanged values are sent as "no change".
+function changed<T>(value: T, original: T): T | null {
+  return value === original ? null : value;
+}
+
+// ArtAddress cannot clear a name: an empty one means "no change".
+function changedName(value: string, original: string): string | null {
+  const v = value.trim();
+  return v === "" ? null : changed(v, original);
+}
+
+export default function NodeAddressPanel({ node, onClose }: NodeAddressPanelProps) {
+  const [shortName, setShortName] = useState(node.shortName);
+  const [longName, setLongName] = useState(node.longName);
+  const [net, setNet] = useState(node.netSwitch & 0x7f);
+  const [subnet, setSubnet] = useState(node.subSwitch & 0x0f);
+  const [swIn, setSwIn] = useState(() =>
+    [0, 1, 2, 3].map((i) => portUniverse(node, i, "input")),
+  );
+  const [swOut, setSwOut] = useState(() =>
+    [0, 1, 2, 3].map((i) => portUniverse(node, i, "output")),
+  );
+  const [commandKind, setCommandKind] = useState<CommandKind>("none");
+  const [commandPort, setCommandPort] = useState(0);
+  const [busy, setBusy] = useState(false);
+  const [outcome, setOutcome] = useState<AddressOutcome | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const ports = [...node.ports].sort((a, b) => a.index - b.index);
+  const perPort = COMMANDS.find((c) => c.kind === commandKind)?.perPort ?? false;
+
+  async function apply() {
+    const request: AddressRequest = {
+      ip: node.ip,
+      port: node.artnetPort || undefined,
+      bind_index: node.bindIndex,
+      short_name: changedName(shortName, node.shortName),
+      long_name: changedName(longName, node.longName),
+      net_switch: changed(net, node.netSwitch & 0x7f),
+      sub_switch: changed(subnet, node.subSwitch & 0x0f),
+      sw_in: swIn.map((u, i) => changed(u, portUniverse(node, i, "input"))),
+      sw_out: swOut.map((u, i) => changed(u, portUniverse(node, i, "output"))),
+      acn_priority: null,
+      command: toCommand(commandKind, commandPort),
+    };
+    setBusy(true);
+    setError(null);
+    setOutcome(null);
+    try {
+      setOutcome(await invoke<AddressOutcome>("artnet_address", { request }));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  const universeInput = (
+    value: number | null,
+    onChange: (v: number) => void,
+  ) =>
+    value === null ? (
+      <span className="node-address-sub">—</span>
+    ) : (
+      <input
+        type="number"
+        min={0}
+        max={15}
+        className="discover-input discover-input-narrow"
+        value={value}
+        onChange={(e) =>
+          onChange(Math.min(15, Math.max(0, Number(e.currentTarget.value) || 0)))
+        }
+      />
+    );
+
+  return (
+    <div className="node-address">
+      <div className="node-address-title">
+        Configure {node.shortName || node.ip}{" "}
+        <span className="mono node-address-sub">
+          {node.ip} · bind {node.bindIndex}
+        </span>
+      </div>
+      <div className="discover-toolbar">
+        <label className="discover-label">
+          Short name
+          <input
+            type="text"
+            maxLength={17}
+            className="discover-input"
+            value={shortName}
+            onChange={(e) => setShortName(e.currentTarget.value)}
+          />
+        </label>
+        <label className="discover-label">
+          Long name
+          <input
+            type="text"
+            maxLength={63}
+            className="discover-input"
+            value={longName}
+            onChange={(e) => setLongName(e.currentTarget.value)}
+          />
+        </label>
+        <label className="discover-label">
+          Net
+          <input
+            type="number"
+            min={0}
+            max={127}
+            className="discover-input discover-input-narrow"
+            value={net}
+            onChange={(e) =>
+              setNet(Math.min(127, Math.max(0, Number(e.currentTarget.value) || 0)))
+            }
+          />
+        </label>
+        <label className="discover-label">
+          Subnet
+          <input
+            type="number"
+            min={0}
+            max={15}
+            className="discover-input discover-input-narrow"
+            value={subnet}
+            onChange={(e) =>
+              setSubnet(Math.min(15, Math.max(0, Number(e.currentTarget.value) || 0)))
+            }
+          />
+        </label>
+      </div>
+      {ports.length > 0 && (
+        <table className="discover-table node-address-ports">
+          <thead>
+            <tr>
+              <th>Port</th>
+              <th>Input universe</th>
+              <th>Output universe</th>
+            </tr>
+          </thead>
+          <tbody>
+            {ports.map((p) => (
+              <tr key={p.index}>
+                <td className="mono">{p.index}</td>
+                <td>
+                  {universeInput(swIn[p.index], (v) =>
+                    setSwIn((prev) => prev.map((u, i) => (i === p.index ? v : u))),
+                  )}
+                </td>
+                <td>
+                  {universeInput(swOut[p.index], (v) =>
+                    setSwOut((prev) => prev.map((u, i) => (i === p.index ? v : u))),
+                  )}
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+      <div className="discover-toolbar">
+        <label className="discover-label">
+          Command
+          <select
+            className="discover-input"
+            value={commandKind}
+            onChange={(e) => setCommandKind(e.currentTarget.value as CommandKind)}
+          >
+            {COMMANDS.map((c) => (
+              <option key={c.kind} value={c.kind}>
+                {c.label}
+              </option>
+            ))}
+          </select>
+        </label>
+        {perPort && (
+          <label className="discover-label">
+            On port
+            <select
+              className="discover-input discover-input-narrow"
+              value={commandPort}
+              onChange={(e) => setCommandPort(Number(e.currentTarget.value))}
+            >
+              {[0, 1, 2, 3].map((i) => (
+                <option key={i} value={i}>
+                  {i}
+                </option>
+              ))}
+            </select>
+          </label>
+        )}
+        <button type="button" className="btn" disabled={busy} onClick={() => void apply()}>
+          {busy ? "Sending…" : "Send ArtAddress"}
+        </button>
+        <button type="button" className="btn" onClick={onClose}>
+          Close
+        </button>
+      </div>
+      {error && <div className="discover-error">{error}</div>}
+      {outcome &&
+        (outcome.verified ? (
+          <div className="discover-hint">Confirmed by the node's ArtPollReply.</div>
+        ) : (
+          <div className="discover-error">
+            {outcome.mismatches.map((m) => (
+              <div key={m}>{m}</div>
+            ))}
+          </div>
+        ))}
+    </div>
+  );
+}
@@ -0,0 +1,192 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  DiscoveredNode,
+  IpProgOutcome,
+  IpProgRequest,
+  IpSettings,
+} from "../artdiscover";
+
+interface NodeIpPanelProps {
+  node: DiscoveredNode;
+  onClose: () => void;
+}
+
+function queryRequest(node: DiscoveredNode): IpProgRequest {
+  return {
+    ip: node.ip,
+    port: node.artnetPort || undefined,
+    new_ip: null,
+    subnet_mask: null,
+    gateway: null,
+    prog_port: null,
+    dhcp: false,
+    reset_defaults: false,
+  };
+}
+
+// Unchanged or empty fields are not programmed.
+function changed(value: string, original: string | undefined): string | null {
+  const v = value.trim();
+  return v === "" || v === original ? null : v;
+}
+
+function describe(s: IpSettings) {
+  return `${s.ip} / ${s.subnetMask} via ${s.gateway}, port ${s.port}, DHCP ${
+    s.dhcp ? "on" : "off"
+  }`;
+}
+
+export default function NodeIpPanel({ node, onClose }: NodeIpPanelProps) {
+  const [current, setCurrent] = useState<IpSettings | null>(null);
+  const [ip, setIp] = useState("");
+  const [mask, setMask] = useState("");
+  const [gateway, setGateway] = useState("");
+  const [dhcp, setDhcp] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [outcome, setOutcome] = useState<IpProgOutcome | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  async function run(request: IpProgRequest) {
+    setBusy(true);
+    setError(null);
+    try {
+      const res = await invoke<IpProgOutcome>("artnet_ip_prog", { request });
+      setOutcome(res);
+      if (res.reply) {
+        setCurrent(res.reply);
+        setIp(res.reply.ip);
+        setMask(res.reply.subnetMask);
+        setGateway(res.reply.gateway);
+        setDhcp(res.reply.dhcp);
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  // Read once per opened node.
+  useEffect(() => {
+    void run(queryRequest(node));
+  }, [node.ip, node.bindIndex]);
+
+  function program() {
+    const request: IpProgRequest = {
+      ...queryRequest(node),
+      dhcp: dhcp && !current?.dhcp,
+      new_ip: dhcp ? null : changed(ip, current?.ip),
+      subnet_mask: dhcp ? null : changed(mask, current?.subnetMask),
+      gateway: dhcp ? null : changed(gateway, current?.gateway),
+    };
+    // Turning DHCP off needs a static address to fall back to.
+    if (!dhcp && current?.dhcp && !request.new_ip) request.new_ip = ip.trim();
+    const plan = request.dhcp
+      ? "enable DHCP"
+      : [
+          request.new_ip && `IP ${request.new_ip}`,
+          request.subnet_mask && `mask ${request.subnet_mask}`,
+          request.gateway && `gateway ${request.gateway}`,
+        ]
+          .filter(Boolean)
+          .join(", ");
+    if (!plan) {
+      setError("Nothing to change");
+      return;
+    }
+    if (
+      !window.confirm(
+        `Reprogram ${node.shortName || node.ip} (${node.ip}): ${plan}?\n\n` +
+          "The node may drop off the network if the new settings are wrong.",
+      )
+    ) {
+      return;
+    }
+    void run(request);
+  }
+
+  return (
+    <div className="node-address">
+      <div className="node-address-title">
+        IP settings of {node.shortName || node.ip}{" "}
+        <span className="mono node-address-sub">{node.ip}</span>
+      </div>
+      <div className="field-hint">
+        {current
+          ? `Node reports ${describe(current)}`
+          : busy
+            ? "Reading settings…"
+            : "No ArtIpProgReply yet"}
+      </div>
+      <div className="discover-toolbar">
+        <label className="discover-label">
+          IP address
+          <input
+            type="text"
+            className="discover-input"
+            disabled={dhcp}
+            value={ip}
+            onChange={(e) => setIp(e.currentTarget.value)}
+          />
+        </label>
+        <label className="discover-label">
+          Subnet mask
+          <input
+            type="text"
+            className="discover-input"
+            disabled={dhcp}
+            value={mask}
+            onChange={(e) => setMask(e.currentTarget.value)}
+          />
+        </label>
+        <label className="discover-label">
+          Gateway
+          <input
+            type="text"
+            className="discover-input"
+            disabled={dhcp}
+            value={gateway}
+            onChange={(e) => setGateway(e.currentTarget.value)}
+          />
+        </label>
+        <label className="discover-label">
+          DHCP
+          <input
+            type="checkbox"
+            checked={dhcp}
+            onChange={(e) => setDhcp(e.currentTarget.checked)}
+          />
+        </label>
+        <button
+          type="button"
+          className="btn"
+          disabled={busy}
+          onClick={() => void run(queryRequest(node))}
+        >
+          Read
+        </button>
+        <button type="button" className="btn" disabled={busy || !current} onClick={program}>
+          Program…
+        </button>
+        <button type="button" className="btn" onClick={onClose}>
+          Close
+        </button>
+      </div>
+      {error && <div className="discover-error">{error}</div>}
+      {outcome && outcome.mismatches.length > 0 && (
+        <div className="discover-error">
+          {outcome.mismatches.map((m) => (
+            <div key={m}>{m}</div>
+          ))}
+        </div>
+      )}
+      {outcome?.reply && outcome.replyFrom && outcome.replyFrom !== node.ip && (
+        <div className="field-hint">
+          Reply came from {outcome.replyFrom}; the node table updates on the
+          next scan.
+        </div>
+      )}
+    </div>
+  );
+}
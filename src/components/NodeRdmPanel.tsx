@@ -0,0 +1,317 @@
+import { useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  DiscoveredNode,
+  RdmParam,
+  RdmReply,
+  RdmSetValue,
+  RdmValue,
+  TodEntry,
+  Uid,
+} from "../artdiscover";
+import { portAddressKey } from "../portAddress";
+
+interface NodeRdmPanelProps {
+  node: DiscoveredNode;
+  onClose: () => void;
+}
+
+type Target = { portAddress: number; uid: Uid };
+
+function describeValue(v: RdmValue): string {
+  if ("deviceInfo" in v) {
+    const d = v.deviceInfo;
+    return (
+      `model ${d.modelId}, software ${d.softwareVersion}, ` +
+      `footprint ${d.footprint}, personality ${d.personality}/${d.personalityCount}, ` +
+      `start ${d.startAddress}, ${d.sensorCount} sensor(s)`
+    );
+  }
+  if ("deviceLabel" in v) return `label "${v.deviceLabel}"`;
+  if ("dmxStartAddress" in v) return `start address ${v.dmxStartAddress}`;
+  if ("dmxPersonality" in v) {
+    return `personality ${v.dmxPersonality.current}/${v.dmxPersonality.count}`;
+  }
+  if ("identifyDevice" in v) return `identify ${v.identifyDevice ? "on" : "off"}`;
+  const s = v.sensorValue;
+  return `sensor ${s.sensor}: ${s.value} (low ${s.lowest}, high ${s.highest}, recorded ${s.recorded})`;
+}
+
+function describeReply(r: RdmReply): string {
+  switch (r.status) {
+    case "nack":
+      return `NACK: ${r.nackReason ?? "no reason given"}`;
+    case "ackTimer":
+      return `Busy; ask again in ${r.ackTimerMs ?? 0} ms`;
+    default:
+      return r.value ? describeValue(r.value) : "Acknowledged";
+  }
+}
+
+export default function NodeRdmPanel({ node, onClose }: NodeRdmPanelProps) {
+  const [tod, setTod] = useState<TodEntry[]>([]);
+  const [fullDiscovery, setFullDiscovery] = useState(false);
+  const [target, setTarget] = useState<Target | null>(null);
+  const [label, setLabel] = useState("");
+  const [startAddress, setStartAddress] = useState(1);
+  const [personality, setPersonality] = useState(1);
+  const [sensor, setSensor] = useState(0);
+  const [busy, setBusy] = useState(false);
+  const [result, setResult] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  async function discover() {
+    setBusy(true);
+    setError(null);
+    try {
+      const entries = await invoke<TodEntry[]>("rdm_discover", {
+        nodeIp: node.ip,
+        flush: fullDiscovery,
+      });
+      setTod(entries);
+      if (
+        target &&
+        !entries.some(
+          (e) => e.portAddress === target.portAddress && e.uids.includes(target.uid),
+        )
+      ) {
+        setTarget(null);
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function request(
+    command: "rdm_get" | "rdm_set",
+    arg: { param: RdmParam } | { value: RdmSetValue },
+  ) {
+    if (!target) return;
+    setBusy(true);
+    setError(null);
+    setResult(null);
+    try {
+      const reply = await invoke<RdmReply>(command, {
+        nodeIp: node.ip,
+        portAddress: target.portAddress,
+        uid: target.uid,
+        ...arg,
+      });
+      setResult(describeReply(reply));
+      // Pre-fill the SET fields from what the device reports.
+      const v = reply.value;
+      if (v && "deviceInfo" in v) {
+        setStartAddress(v.deviceInfo.startAddress);
+        setPersonality(v.deviceInfo.personality);
+      } else if (v && "deviceLabel" in v) {
+        setLabel(v.deviceLabel);
+      }
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  const get = (param: RdmParam) => void request("rdm_get", { param });
+  const set = (value: RdmSetValue) => void request("rdm_set", { value });
+
+  return (
+    <div className="node-address">
+      <div className="node-address-title">
+        RDM devices behind {node.shortName || node.ip}{" "}
+        <span className="mono node-address-sub">{node.ip}</span>
+      </div>
+      <div className="discover-toolbar">
+        <button type="button" className="btn" disabled={busy} onClick={() => void discover()}>
+          {busy ? "Working…" : "Discover devices"}
+        </button>
+        <label className="discover-label">
+          Full discovery
+          <input
+            type="checkbox"
+            checked={fullDiscovery}
+            onChange={(e) => setFullDiscovery(e.currentTarget.checked)}
+          />
+        </label>
+        <button type="button" className="btn" onClick={onClose}>
+          Close
+        </button>
+      </div>
+      {tod.length > 0 && (
+        <table className="discover-table node-address-ports">
+          <thead>
+            <tr>
+              <th>Port</th>
+              <th>Universe</th>
+              <th>Devices</th>
+            </tr>
+          </thead>
+          <tbody>
+            {tod.map((e) => (
+              <tr key={`${e.bindIndex}-${e.port}-${e.portAddress}`}>
+                <td className="mono">{e.port}</td>
+                <td className="mono">
+                  {portAddressKey(e.portAddress)}
+                  {!e.complete && " (incomplete)"}
+                </td>
+                <td>
+                  {e.uids.length === 0
+                    ? "—"
+                    : e.uids.map((uid) => (
+                        <label key={uid} className="discover-label mono">
+                          <input
+                            type="radio"
+                            name="rdm-target"
+                            checked={
+                              target?.uid === uid && target.portAddress === e.portAddress
+                            }
+                            onChange={() => setTarget({ portAddress: e.portAddress, uid })}
+                          />
+                          {uid}
+                        </label>
+                      ))}
+                </td>
+              </tr>
+            ))}
+          </tbody>
+        </table>
+      )}
+      {target && (
+        <>
+          <div className="discover-toolbar">
+            <button type="button" className="btn" disabled={busy} onClick={() => get("deviceInfo")}>
+              Device info
+            </button>
+            <button type="button" className="btn" disabled={busy} onClick={() => get("deviceLabel")}>
+              Label
+            </button>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => get("dmxStartAddress")}
+            >
+              Start address
+            </button>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => get("dmxPersonality")}
+            >
+              Personality
+            </button>
+            <label className="discover-label">
+              Sensor
+              <input
+                type="number"
+                min={0}
+                max={254}
+                className="discover-input discover-input-narrow"
+                value={sensor}
+                onChange={(e) =>
+                  setSensor(Math.min(254, Math.max(0, Number(e.currentTarget.value) || 0)))
+                }
+              />
+            </label>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => get({ sensorValue: sensor })}
+            >
+              Read sensor
+            </button>
+          </div>
+          <div className="discover-toolbar">
+            <label className="discover-label">
+              Label
+              <input
+                type="text"
+                maxLength={32}
+                className="discover-input"
+                value={label}
+                onChange={(e) => setLabel(e.currentTarget.value)}
+              />
+            </label>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => set({ deviceLabel: label })}
+            >
+              Set label
+            </button>
+            <label className="discover-label">
+              Start address
+              <input
+                type="number"
+                min={1}
+                max={512}
+                className="discover-input discover-input-narrow"
+                value={startAddress}
+                onChange={(e) =>
+                  setStartAddress(
+                    Math.min(512, Math.max(1, Number(e.currentTarget.value) || 1)),
+                  )
+                }
+              />
+            </label>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => set({ dmxStartAddress: startAddress })}
+            >
+              Set address
+            </button>
+            <label className="discover-label">
+              Personality
+              <input
+                type="number"
+                min={1}
+                max={255}
+                className="discover-input discover-input-narrow"
+                value={personality}
+                onChange={(e) =>
+                  setPersonality(
+                    Math.min(255, Math.max(1, Number(e.currentTarget.value) || 1)),
+                  )
+                }
+              />
+            </label>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => set({ dmxPersonality: personality })}
+            >
+              Set personality
+            </button>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => set({ identifyDevice: true })}
+            >
+              Identify on
+            </button>
+            <button
+              type="button"
+              className="btn"
+              disabled={busy}
+              onClick={() => set({ identifyDevice: false })}
+            >
+              Identify off
+            </button>
+          </div>
+        </>
+      )}
+      {error && <div className="discover-error">{error}</div>}
+      {result && <div className="discover-hint">{result}</div>}
+    </div>
+  );
+}
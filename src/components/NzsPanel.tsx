@@ -0,0 +1,98 @@
+import { useEffect, useRef, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { portAddressKey } from "../portAddress";
+
+// ArtNzs, or sACN with an alternate start code, as emitted by the receiver.
+type NzsEvent = {
+  source_ip: string;
+  port_address: number;
+  start_code: number;
+  length: number;
+  sequence: number;
+  values: number[];
+};
+
+type NzsStream = NzsEvent & { key: string; count: number; lastSeen: number };
+
+const TEXT_START_CODE = 0x17;
+const STREAM_TTL_MS = 10000;
+const PREVIEW_BYTES = 24;
+
+function preview(s: NzsStream) {
+  if (s.start_code === TEXT_START_CODE) {
+    const end = s.values.indexOf(0);
+    return String.fromCharCode(...s.values.slice(0, end < 0 ? undefined : end));
+  }
+  const hex = s.values
+    .slice(0, PREVIEW_BYTES)
+    .map((v) => v.toString(16).padStart(2, "0"))
+    .join(" ");
+  return s.values.length > PREVIEW_BYTES ? `${hex} …` : hex;
+}
+
+/** Streams with a non-zero start code, kept apart from the DMX monitor. */
+export default function NzsPanel() {
+  const streamsRef = useRef<Map<string, NzsStream>>(new Map());
+  const [streams, setStreams] = useState<NzsStream[]>([]);
+
+  useEffect(() => {
+    const un = listen<NzsEvent>("artnet:nzs", (e) => {
+      const p = e.payload;
+      if (!p) return;
+      const key = `${portAddressKey(p.port_address)} ${p.start_code} ${p.source_ip}`;
+      const prev = streamsRef.current.get(key);
+      streamsRef.current.set(key, {
+        ...p,
+        key,
+        count: (prev?.count ?? 0) + 1,
+        lastSeen: Date.now(),
+      });
+    });
+    // Refresh at a fixed rate rather than per packet.
+    const id = window.setInterval(() => {
+      const now = Date.now();
+      for (const [key, s] of streamsRef.current) {
+        if (now - s.lastSeen > STREAM_TTL_MS) streamsRef.current.delete(key);
+      }
+      setStreams([...streamsRef.current.values()]);
+    }, 500);
+    return () => {
+      window.clearInterval(id);
+      un.then((fn) => fn());
+    };
+  }, []);
+
+  if (streams.length === 0) return null;
+  return (
+    <div className="discover-table-wrap nzs-panel">
+      <div className="node-address-title">Alternate start codes</div>
+      <table className="discover-table">
+        <thead>
+          <tr>
+            <th>Universe</th>
+            <th>Start code</th>
+            <th>Source</th>
+            <th>Packets</th>
+            <th>Length</th>
+            <th>Data</th>
+          </tr>
+        </thead>
+        <tbody>
+          {streams.map((s) => (
+            <tr key={s.key}>
+              <td className="mono">{portAddressKey(s.port_address)}</td>
+              <td className="mono">
+                0x{s.start_code.toString(16).padStart(2, "0")}
+                {s.start_code === TEXT_START_CODE ? " (text)" : ""}
+              </td>
+              <td className="mono">{s.source_ip}</td>
+              <td className="mono">{s.count}</td>
+              <td className="mono">{s.length}</td>
+              <td className="mono">{preview(s)}</td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+    </div>
+  );
+}
@@ -12,22 +12,42 @@ import {
   open as dialogOpen,
   save as dialogSave,
 } from "@tauri-apps/plugin-dialog";
+import {
+  joinPortAddress,
+  portAddressKey,
+  splitPortAddress,
+} from "../portAddress";
+import type { ChaseEvent, TimecodeEvent } from "../timecode";
 
 type Frame = {
   values: number[];
-  net: number;
-  subnet: number;
-  universe: number;
+  port_address: number;
 };
 
 type UniverseKey = string; // "net/subnet/universe"
 
+// ArtNzs / alternate start code frame, recorded apart from the DMX channels.
+type NzsFrame = {
+  port_address: number;
+  start_code: number;
+  sequence: number;
+  values: number[];
+};
+
 type WavRecording = {
   timestamps: number[];
   channels: number[][];
   dmx_channels?: number[];
 };
 
+// One universe of a pcap / pcapng capture, .artrec show file or JSONL
+// recording; playback sends all of them.
+type CaptureRecording = WavRecording & {
+  port_address: number;
+  universes: number[];
+  nzs: (NzsFrame & { t_ms: number })[];
+};
+
 interface RecordPlayTabProps {}
 
 const CHANNELS = 512;
@@ -185,6 +205,7 @@ type SenderConfig = {
   target_ip: string;
   port: number;
   fps: number;
+  sync: boolean;
   net: number;
   subnet: number;
   universe: number;
@@ -194,6 +215,8 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
   const [universes, setUniverses] = useState<UniverseKey[]>([]);
   const [selected, setSelected] = useState<UniverseKey>("");
   const [isRecording, setIsRecording] = useState(false);
+  const nzsRef = useRef<{ t: number; frame: NzsFrame }[]>([]);
+  const [nzsCount, setNzsCount] = useState(0);
   const [isPlaying, setIsPlaying] = useState(false);
   const [path, setPath] = useState<string>("");
   const universeLastSeenRef = useRef<Map<UniverseKey, number>>(new Map());
@@ -201,6 +224,9 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
   const [showSettings, setShowSettings] = useState(false);
   const [settings, setSettings] = useState<SenderConfig | null>(null);
   const [isLooping, setIsLooping] = useState(false);
+  const [chaseTimecode, setChaseTimecode] = useState(false);
+  const [chaseOrigin, setChaseOrigin] = useState("00:00:00:00");
+  const [incomingTimecode, setIncomingTimecode] = useState<string | null>(null);
   const [recordingFormat, setRecordingFormat] = useState<"jsonl" | "wav">(
     "jsonl"
   );
@@ -244,6 +270,7 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
   const playbackOffsetRef = useRef(0);
   const playbackActiveRef = useRef(false);
   const playbackLoopRef = useRef(false);
+  const playbackChaseRef = useRef(false);
   const timelineDragActiveRef = useRef(false);
   const timelineDragWasPlayingRef = useRef(false);
 
@@ -460,7 +487,7 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
         ctx.fillText(label, W - 8, 4);
         ctx.restore();
 
-        if (isPlayingNow && elapsed >= duration) {
+        if (isPlayingNow && elapsed >= duration && !playbackChaseRef.current) {
           if (playbackLoopRef.current) {
             playbackOffsetRef.current = 0;
             playbackStartRef.current = performance.now();
@@ -475,12 +502,30 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     }
   }, []);
 
+  useEffect(() => {
+    const timecode = listen<TimecodeEvent>("artnet:timecode", (e) =>
+      setIncomingTimecode(e.payload.text)
+    );
+    // While chasing, the playhead follows the backend's position.
+    const chase = listen<ChaseEvent>("artnet:chase", (e) => {
+      if (!playbackChaseRef.current || !playbackActiveRef.current) return;
+      const { positionMs, running } = e.payload;
+      playbackOffsetRef.current = positionMs ?? 0;
+      playbackStartRef.current =
+        running && positionMs != null ? performance.now() : null;
+    });
+    return () => {
+      timecode.then((fn) => fn());
+      chase.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     let unlisten: Promise<UnlistenFn> | null = null;
     unlisten = listen<Frame>("artnet:dmx", (e) => {
       const p = e.payload;
       if (!p) return;
-      const key: UniverseKey = `${p.net}/${p.subnet}/${p.universe}`;
+      const key: UniverseKey = portAddressKey(p.port_address);
       universeLastSeenRef.current.set(key, Date.now());
       setUniverses((prev) => (prev.includes(key) ? prev : [...prev, key]));
       setSelected((prev) => prev || key);
@@ -490,6 +535,19 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     };
   }, []);
 
+  // Alternate start code frames of the recorded universe
+  useEffect(() => {
+    if (!isRecording) return;
+    const unlisten = listen<NzsFrame>("artnet:nzs_filtered", (e) => {
+      if (!e.payload) return;
+      nzsRef.current.push({ t: Date.now(), frame: e.payload });
+      setNzsCount(nzsRef.current.length);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [isRecording]);
+
   // Universe data capture
   useEffect(() => {
     let unlisten: Promise<UnlistenFn> | null = null;
@@ -498,7 +556,7 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
       if (!p) return;
       const stamp = Date.now();
       const values = p.values || [];
-      const key: UniverseKey = `${p.net}/${p.subnet}/${p.universe}`;
+      const key: UniverseKey = portAddressKey(p.port_address);
       universeLastSeenRef.current.set(key, stamp);
       setUniverses((prev) => (prev.includes(key) ? prev : [...prev, key]));
       setSelected((prev) => prev || key);
@@ -667,9 +725,22 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     const p = await dialogOpen({
       multiple: false,
       filters: [
-        { name: "ArtNet Files", extensions: ["jsonl", "json", "wav"] },
+        {
+          name: "ArtNet Files",
+          extensions: [
+            "jsonl",
+            "json",
+            "artrec",
+            "wav",
+            "pcap",
+            "pcapng",
+            "cap",
+          ],
+        },
         { name: "ArtNet JSONL", extensions: ["jsonl", "json"] },
+        { name: "ArtNet show", extensions: ["artrec"] },
         { name: "ArtNet WAV", extensions: ["wav"] },
+        { name: "Wireshark capture", extensions: ["pcap", "pcapng", "cap"] },
       ],
     });
     if (!p) return;
@@ -682,9 +753,11 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
 
     const isWavFile = newPath.toLowerCase().endsWith(".wav");
     let loadedKey: UniverseKey | "" = "";
+    let captureKeys: UniverseKey[] = [];
     let vizChannelNums: number[] = [];
     let nextT: number[] = [];
     let nextBuf = Array.from({ length: CHANNELS }, () => new Uint8Array(0));
+    const nextNzs: { t: number; frame: NzsFrame }[] = [];
 
     try {
       if (isWavFile) {
@@ -713,40 +786,22 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
           );
         });
       } else {
-        const content = (await invoke("read_text_file", {
+        const capture = (await invoke("load_capture_recording", {
           path: newPath,
-        })) as string;
-        const lines = content.split(/\r?\n/).filter(Boolean);
-        let channels = Array.from({ length: CHANNELS }, (_, idx) => idx + 1);
-        let start = 0;
-        if (lines[0]?.includes("format") && lines[0].includes("artnet-jsonl")) {
-          const header = JSON.parse(lines[0]);
-          if (Array.isArray(header.channels)) {
-            channels = normalizeLoadedChannels(header.channels);
-          } else if (typeof header.channel === "number") {
-            channels = normalizeLoadedChannels([header.channel]);
-          }
-          start = 1;
-        }
-        vizChannelNums = normalizeLoadedChannels(channels);
-        for (let i = start; i < lines.length; i++) {
-          const rec = JSON.parse(lines[i]);
-          const values = Array.isArray(rec.values) ? rec.values : [];
-          const t = Number(rec.t_ms) || 0;
-          if (!loadedKey && typeof rec.net === "number") {
-            loadedKey = `${rec.net | 0}/${rec.subnet | 0}/${rec.universe | 0}`;
-          }
-          nextT.push(t);
-          vizChannelNums.forEach((dmx, idx) => {
-            const ch = dmx - 1;
-            const v = Number(values[idx]) | 0;
-            const prev = nextBuf[ch];
-            const next = new Uint8Array(prev.length + 1);
-            if (prev.length) next.set(prev);
-            next[prev.length] = v;
-            nextBuf[ch] = next;
-          });
-        }
+        })) as CaptureRecording;
+        nextT = capture.timestamps.map((t) => Number(t) || 0);
+        vizChannelNums = normalizeLoadedChannels(
+          capture.dmx_channels ?? capture.channels.map((_, idx) => idx + 1)
+        );
+        vizChannelNums.forEach((dmx, idx) => {
+          nextBuf[dmx - 1] = Uint8Array.from(capture.channels[idx] || []);
+        });
+        loadedKey = portAddressKey(capture.port_address);
+        captureKeys = capture.universes.map(portAddressKey);
+        // Alternate start code frames are kept apart from the DMX channels.
+        capture.nzs.forEach(({ t_ms, ...frame }) =>
+          nextNzs.push({ t: Number(t_ms) || 0, frame })
+        );
       }
       const hasSamples = vizChannelNums.some(
         (dmx) => nextBuf[dmx - 1]?.length > 0
@@ -763,6 +818,8 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     frozenVizEndRef.current = null;
     tRef.current = nextT.slice();
     bufRef.current = nextBuf;
+    nzsRef.current = nextNzs;
+    setNzsCount(nextNzs.length);
     vizTRef.current = nextT;
     vizBufRef.current = nextBuf;
     waveformRowsRef.current = vizChannelNums;
@@ -779,9 +836,11 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     setIsPlaying(false);
 
     if (loadedKey) {
-      setUniverses((prev) =>
-        prev.includes(loadedKey) ? prev : [...prev, loadedKey]
-      );
+      const keys = captureKeys.length ? captureKeys : [loadedKey];
+      setUniverses((prev) => [
+        ...prev,
+        ...keys.filter((key) => !prev.includes(key)),
+      ]);
       setSelected(loadedKey);
     }
     setPath(newPath);
@@ -802,6 +861,8 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
         { length: CHANNELS },
         () => new Uint8Array(0)
       );
+      nzsRef.current = [];
+      setNzsCount(0);
       setIsRecording(true);
     } else {
       frozenVizEndRef.current =
@@ -819,6 +880,39 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     }
   }, [draw, isRecording]);
 
+  // JSONL <-> binary show file, done by the backend without loading here.
+  const convertFile = useCallback(async () => {
+    const src = await dialogOpen({
+      multiple: false,
+      filters: [
+        { name: "ArtNet recording", extensions: ["jsonl", "json", "artrec"] },
+      ],
+    });
+    if (!src) return;
+    const toShow = !String(src).toLowerCase().endsWith(".artrec");
+    const dst = await dialogSave({
+      defaultPath: String(src).replace(
+        /\.[^./\\]+$/,
+        toShow ? ".artrec" : ".jsonl"
+      ),
+      filters: [
+        toShow
+          ? { name: "ArtNet show", extensions: ["artrec"] }
+          : { name: "ArtNet JSONL", extensions: ["jsonl"] },
+      ],
+    });
+    if (!dst) return;
+    try {
+      const frames = (await invoke("convert_recording", {
+        src: String(src),
+        dst: String(dst),
+      })) as number;
+      alert(`Wrote ${frames} frames to ${String(dst)}`);
+    } catch (e) {
+      alert(`Could not convert recording: ${String(e)}`);
+    }
+  }, []);
+
   const saveToFile = useCallback(async () => {
     if (tRef.current.length === 0) return;
     const extension = recordingFormat === "wav" ? "wav" : "jsonl";
@@ -891,6 +985,26 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
           })
         );
       }
+      // Alternate start code frames go in as their own lines, in time order;
+      // readers that only know DMX skip them by their start_code.
+      const dmxLines = lines.splice(1).map((line, i) => ({
+        t: tRef.current[i] - t0,
+        line,
+      }));
+      const nzsLines = nzsRef.current.map(({ t, frame }) => ({
+        t: Math.max(0, t - t0),
+        line: JSON.stringify({
+          t_ms: Math.max(0, t - t0),
+          ...splitPortAddress(frame.port_address),
+          start_code: frame.start_code,
+          sequence: frame.sequence,
+          length: frame.values.length,
+          values: frame.values,
+        }),
+      }));
+      [...dmxLines, ...nzsLines]
+        .sort((a, b) => a.t - b.t)
+        .forEach(({ line }) => lines.push(line));
       const content = lines.join("\n") + "\n";
       await invoke("write_text_file", { path: String(p), content });
     }
@@ -923,11 +1037,11 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
         selected && !isWavFile
           ? selected.split("/").map((value) => Number(value) | 0)
           : [undefined, undefined, undefined];
+      const portAddress =
+        net === undefined ? null : { net, subnet, universe };
       await invoke("send_dmx_values", {
         values,
-        net,
-        subnet,
-        universe,
+        portAddress,
       }).catch(() => {});
     },
     [path, selected]
@@ -967,22 +1081,27 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
 
       try {
         const isWavFile = path.toLowerCase().endsWith(".wav");
+        const chaseArg = chaseTimecode ? chaseOrigin.trim() : null;
         if (isWavFile) {
           await invoke("play_wav_file", {
             path,
             startMs: Math.round(playStartMs),
             loopPlayback: isLooping,
+            chaseTimecode: chaseArg,
           });
         } else {
           await invoke("play_file", {
             path,
             startMs: Math.round(playStartMs),
             loopPlayback: isLooping,
+            chaseTimecode: chaseArg,
           });
         }
         playbackDurationRef.current = duration;
-        playbackOffsetRef.current = playStartMs;
-        playbackStartRef.current = performance.now();
+        playbackChaseRef.current = chaseTimecode;
+        // A chase holds until the first timecode arrives.
+        playbackOffsetRef.current = chaseTimecode ? 0 : playStartMs;
+        playbackStartRef.current = chaseTimecode ? null : performance.now();
         playbackActiveRef.current = true;
         setIsPlaying(true);
         requestAnimationFrame(draw);
@@ -992,7 +1111,7 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
         alert(`Could not start playback: ${String(e)}`);
       }
     },
-    [draw, getPlaybackDuration, isLooping, path]
+    [chaseOrigin, chaseTimecode, draw, getPlaybackDuration, isLooping, path]
   );
 
   const togglePlay = useCallback(async () => {
@@ -1065,6 +1184,8 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
     resetVisualization();
     tRef.current = [];
     bufRef.current = Array.from({ length: CHANNELS }, () => new Uint8Array(0));
+    nzsRef.current = [];
+    setNzsCount(0);
     requestAnimationFrame(draw);
   }, [draw, resetVisualization]);
 
@@ -1076,9 +1197,8 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
         target_ip: String(cfg.target_ip ?? "255.255.255.255"),
         port: Number(cfg.port ?? 6454),
         fps: Number(cfg.fps ?? 44),
-        net: Number(cfg.net ?? 0),
-        subnet: Number(cfg.subnet ?? 0),
-        universe: Number(cfg.universe ?? 0),
+        sync: Boolean(cfg.sync),
+        ...splitPortAddress(Number(cfg.port_address ?? 0)),
       };
       setSettings(s);
       setShowSettings(true);
@@ -1087,6 +1207,7 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
         target_ip: "255.255.255.255",
         port: 6454,
         fps: 44,
+        sync: false,
         net: 0,
         subnet: 0,
         universe: 0,
@@ -1097,16 +1218,20 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
 
   const saveSettings = useCallback(async () => {
     if (!settings) return;
-    await invoke("set_sender_config", {
-      cfg: {
-        target_ip: settings.target_ip,
-        port: settings.port,
-        fps: settings.fps,
-        net: settings.net,
-        subnet: settings.subnet,
-        universe: settings.universe,
-      },
-    });
+    try {
+      await invoke("set_sender_config", {
+        cfg: {
+          target_ip: settings.target_ip,
+          port: settings.port,
+          fps: settings.fps,
+          sync: settings.sync,
+          port_address: joinPortAddress(settings),
+        },
+      });
+    } catch (e) {
+      alert(String(e));
+      return;
+    }
     await invoke("save_settings").catch(() => {});
     setShowSettings(false);
   }, [settings]);
@@ -1200,9 +1325,24 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
           >
             Save…
           </button>
+          {nzsCount > 0 && (
+            <span
+              className="mono"
+              title="Alternate start code frames of this universe, saved to JSONL only"
+            >
+              +{nzsCount} NZS
+            </span>
+          )}
           <button className="btn" onClick={chooseOpen}>
             Load…
           </button>
+          <button
+            className="btn"
+            onClick={convertFile}
+            title="Convert a JSONL recording to a compact .artrec show file, or back"
+          >
+            Convert…
+          </button>
           <button className="btn" onClick={togglePlay}>
             {isPlaying ? "Stop" : "Play"}
           </button>
@@ -1214,6 +1354,29 @@ export default function RecordPlayTab(_props: RecordPlayTabProps) {
             />{" "}
             Loop
           </label>
+          <label
+            className="animation-label"
+            title="Follow received ArtTimeCode; the recording starts at the given timecode"
+          >
+            <input
+              type="checkbox"
+              checked={chaseTimecode}
+              disabled={isPlaying}
+              onChange={(e) => setChaseTimecode(e.currentTarget.checked)}
+            />{" "}
+            Chase TC from
+          </label>
+          <input
+            type="text"
+            value={chaseOrigin}
+            disabled={isPlaying || !chaseTimecode}
+            placeholder="HH:MM:SS:FF"
+            onChange={(e) => setChaseOrigin(e.currentTarget.value)}
+            className="freq-input timecode-input"
+          />
+          <span className="mono" title="Last ArtTimeCode received">
+            {incomingTimecode ?? "--:--:--:--"}
+          </span>
         </div>
         <div className="controls-right">
           <button
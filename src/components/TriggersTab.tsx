@@ -0,0 +1,513 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { open as dialogOpen } from "@tauri-apps/plugin-dialog";
+import {
+  ANIMATION_MODES,
+  TRIGGER_KEYS,
+  describeAction,
+  describeMatch,
+  type CommandEvent,
+  type TriggerAction,
+  type TriggerActionEvent,
+  type TriggerEvent,
+  type TriggerMapping,
+} from "../trigger";
+
+const LOG_LIMIT = 100;
+
+type ActionType = TriggerAction["type"];
+
+const ACTION_TYPES: { value: ActionType; label: string }[] = [
+  { value: "playFile", label: "Play file" },
+  { value: "stopPlayback", label: "Stop playback" },
+  { value: "startAnimation", label: "Start animation" },
+  { value: "stopAnimation", label: "Stop animation" },
+  { value: "blackout", label: "Blackout" },
+  { value: "recallSnapshot", label: "Recall snapshot" },
+];
+
+const clampByte = (v: string) => Math.min(255, Math.max(0, Number(v) || 0));
+
+export default function TriggersTab() {
+  const [log, setLog] = useState<string[]>([]);
+  const [mappings, setMappings] = useState<TriggerMapping[]>([]);
+  const [snapshots, setSnapshots] = useState<string[]>([]);
+  const [error, setError] = useState<string | null>(null);
+
+  // New mapping
+  const [onType, setOnType] = useState<"trigger" | "command">("trigger");
+  const [onKey, setOnKey] = useState(1);
+  const [onSubKey, setOnSubKey] = useState("");
+  const [onText, setOnText] = useState("");
+  const [actionType, setActionType] = useState<ActionType>("playFile");
+  const [actionPath, setActionPath] = useState("");
+  const [actionLoop, setActionLoop] = useState(false);
+  const [actionMode, setActionMode] = useState(ANIMATION_MODES[0]);
+  const [actionFrequency, setActionFrequency] = useState(1);
+  const [actionMaster, setActionMaster] = useState(255);
+  const [actionSnapshot, setActionSnapshot] = useState("");
+
+  // Sending
+  const [sendTarget, setSendTarget] = useState("");
+  const [sendKey, setSendKey] = useState(1);
+  const [sendSubKey, setSendSubKey] = useState(0);
+  const [sendData, setSendData] = useState("");
+  const [sendText, setSendText] = useState("");
+
+  const [snapshotName, setSnapshotName] = useState("");
+
+  const addLog = (line: string) =>
+    setLog((prev) =>
+      [`${new Date().toLocaleTimeString()} ${line}`, ...prev].slice(0, LOG_LIMIT),
+    );
+
+  const refreshSnapshots = () =>
+    invoke<string[]>("list_snapshots")
+      .then(setSnapshots)
+      .catch((e) => setError(String(e)));
+
+  useEffect(() => {
+    invoke<TriggerMapping[]>("get_trigger_mappings")
+      .then(setMappings)
+      .catch((e) => setError(String(e)));
+    void refreshSnapshots();
+    const trigger = listen<TriggerEvent>("artnet:trigger", (e) => {
+      const t = e.payload;
+      addLog(
+        `Trigger ${t.keyName} (${t.key}) sub-key ${t.subKey} from ${t.sourceIp}`,
+      );
+    });
+    const command = listen<CommandEvent>("artnet:command", (e) =>
+      addLog(`Command "${e.payload.text}" from ${e.payload.sourceIp}`),
+    );
+    const action = listen<TriggerActionEvent>("artnet:trigger_action", (e) => {
+      const { action, error } = e.payload;
+      addLog(`→ ${describeAction(action)}${error ? ` failed: ${error}` : ""}`);
+    });
+    return () => {
+      trigger.then((fn) => fn());
+      command.then((fn) => fn());
+      action.then((fn) => fn());
+    };
+  }, []);
+
+  async function run(f: () => Promise<unknown>) {
+    setError(null);
+    try {
+      await f();
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function saveMappings(next: TriggerMapping[]) {
+    await run(async () => {
+      await invoke("set_trigger_mappings", { mappings: next });
+      await invoke("save_settings");
+      setMappings(next);
+    });
+  }
+
+  function newAction(): TriggerAction {
+    switch (actionType) {
+      case "playFile":
+        return { type: "playFile", path: actionPath, loopPlayback: actionLoop };
+      case "startAnimation":
+        return {
+          type: "startAnimation",
+          mode: actionMode,
+          frequency: actionFrequency,
+          masterValue: actionMaster,
+        };
+      case "recallSnapshot":
+        return { type: "recallSnapshot", name: actionSnapshot };
+      default:
+        return { type: actionType } as TriggerAction;
+    }
+  }
+
+  function addMapping() {
+    if (onType === "command" && !onText.trim()) {
+      setError("Enter the command text to react to");
+      return;
+    }
+    if (actionType === "playFile" && !actionPath) {
+      setError("Choose a file to play");
+      return;
+    }
+    if (actionType === "recallSnapshot" && !actionSnapshot) {
+      setError("Choose a snapshot to recall");
+      return;
+    }
+    const on =
+      onType === "trigger"
+        ? {
+            type: "trigger" as const,
+            key: onKey,
+            subKey: onSubKey === "" ? null : clampByte(onSubKey),
+          }
+        : { type: "command" as const, text: onText.trim() };
+    void saveMappings([...mappings, { on, action: newAction() }]);
+  }
+
+  async function chooseFile() {
+    const p = await dialogOpen({
+      multiple: false,
+      filters: [{ name: "ArtNet Files", extensions: ["jsonl", "json", "wav"] }],
+    });
+    if (typeof p === "string") setActionPath(p);
+  }
+
+  async function saveSnapshot() {
+    await run(async () => {
+      await invoke("save_snapshot", { name: snapshotName });
+      await invoke("save_settings");
+      setSnapshotName("");
+      await refreshSnapshots();
+    });
+  }
+
+  async function deleteSnapshot(name: string) {
+    await run(async () => {
+      await invoke("delete_snapshot", { name });
+      await invoke("save_settings");
+      await refreshSnapshots();
+    });
+  }
+
+  const runAction = (action: TriggerAction) =>
+    void run(() => invoke("run_trigger_action", { action }));
+
+  return (
+    <section className="discover-pane">
+      <h3>Mappings</h3>
+      <table className="discover-table">
+        <thead>
+          <tr>
+            <th>When</th>
+            <th>Do</th>
+            <th />
+          </tr>
+        </thead>
+        <tbody>
+          {mappings.length === 0 && (
+            <tr>
+              <td colSpan={3} className="discover-empty">
+                No mappings yet
+              </td>
+            </tr>
+          )}
+          {mappings.map((m, i) => (
+            <tr key={i}>
+              <td>{describeMatch(m.on)}</td>
+              <td>{describeAction(m.action)}</td>
+              <td>
+                <button type="button" className="btn" onClick={() => runAction(m.action)}>
+                  Test
+                </button>{" "}
+                <button
+                  type="button"
+                  className="btn"
+                  onClick={() => void saveMappings(mappings.filter((_, j) => j !== i))}
+                >
+                  Remove
+                </button>
+              </td>
+            </tr>
+          ))}
+        </tbody>
+      </table>
+
+      <div className="discover-toolbar">
+        <label className="discover-label">
+          When
+          <select
+            className="discover-input"
+            value={onType}
+            onChange={(e) => setOnType(e.currentTarget.value as "trigger" | "command")}
+          >
+            <option value="trigger">ArtTrigger</option>
+            <option value="command">ArtCommand</option>
+          </select>
+        </label>
+        {onType === "trigger" ? (
+          <>
+            <label className="discover-label">
+              Key
+              <select
+                className="discover-input"
+                value={onKey}
+                onChange={(e) => setOnKey(Number(e.currentTarget.value))}
+              >
+                {TRIGGER_KEYS.map((k) => (
+                  <option key={k.value} value={k.value}>
+                    {k.label}
+                  </option>
+                ))}
+              </select>
+            </label>
+            <label className="discover-label">
+              Sub-key
+              <input
+                type="number"
+                min={0}
+                max={255}
+                placeholder="any"
+                className="discover-input discover-input-narrow"
+                value={onSubKey}
+                onChange={(e) => setOnSubKey(e.currentTarget.value)}
+              />
+            </label>
+          </>
+        ) : (
+          <label className="discover-label">
+            Command
+            <input
+              type="text"
+              placeholder="SwoutText=Playback"
+              className="discover-input"
+              value={onText}
+              onChange={(e) => setOnText(e.currentTarget.value)}
+            />
+          </label>
+        )}
+      </div>
+      <div className="discover-toolbar">
+        <label className="discover-label">
+          Do
+          <select
+            className="discover-input"
+            value={actionType}
+            onChange={(e) => setActionType(e.currentTarget.value as ActionType)}
+          >
+            {ACTION_TYPES.map((a) => (
+              <option key={a.value} value={a.value}>
+                {a.label}
+              </option>
+            ))}
+          </select>
+        </label>
+        {actionType === "playFile" && (
+          <>
+            <button type="button" className="btn" onClick={() => void chooseFile()}>
+              Choose file…
+            </button>
+            <span className="mono discover-long" title={actionPath}>
+              {actionPath || "—"}
+            </span>
+            <label className="discover-label">
+              Loop
+              <input
+                type="checkbox"
+                checked={actionLoop}
+                onChange={(e) => setActionLoop(e.currentTarget.checked)}
+              />
+            </label>
+          </>
+        )}
+        {actionType === "startAnimation" && (
+          <>
+            <select
+              className="discover-input"
+              value={actionMode}
+              onChange={(e) => setActionMode(e.currentTarget.value)}
+            >
+              {ANIMATION_MODES.map((m) => (
+                <option key={m} value={m}>
+                  {m}
+                </option>
+              ))}
+            </select>
+            <label className="discover-label">
+              Hz
+              <input
+                type="number"
+                min={0.01}
+                max={100}
+                step={0.1}
+                className="discover-input discover-input-narrow"
+                value={actionFrequency}
+                onChange={(e) => setActionFrequency(Number(e.currentTarget.value) || 1)}
+              />
+            </label>
+            <label className="discover-label">
+              Master
+              <input
+                type="number"
+                min={0}
+                max={255}
+                className="discover-input discover-input-narrow"
+                value={actionMaster}
+                onChange={(e) => setActionMaster(clampByte(e.currentTarget.value))}
+              />
+            </label>
+          </>
+        )}
+        {actionType === "recallSnapshot" && (
+          <select
+            className="discover-input"
+            value={actionSnapshot}
+            onChange={(e) => setActionSnapshot(e.currentTarget.value)}
+          >
+            <option value="">Choose…</option>
+            {snapshots.map((s) => (
+              <option key={s} value={s}>
+                {s}
+              </option>
+            ))}
+          </select>
+        )}
+        <button type="button" className="btn" onClick={addMapping}>
+          Add mapping
+        </button>
+      </div>
+
+      <h3>Snapshots</h3>
+      <div className="discover-toolbar">
+        <input
+          type="text"
+          placeholder="Snapshot name"
+          className="discover-input"
+          value={snapshotName}
+          onChange={(e) => setSnapshotName(e.currentTarget.value)}
+        />
+        <button
+          type="button"
+          className="btn"
+          disabled={!snapshotName.trim()}
+          onClick={() => void saveSnapshot()}
+        >
+          Save sender output
+        </button>
+        <button type="button" className="btn" onClick={() => runAction({ type: "blackout" })}>
+          Blackout
+        </button>
+      </div>
+      {snapshots.length > 0 && (
+        <div className="discover-toolbar">
+          {snapshots.map((s) => (
+            <span key={s} className="discover-label">
+              <button
+                type="button"
+                className="btn"
+                onClick={() => runAction({ type: "recallSnapshot", name: s })}
+              >
+                {s}
+              </button>
+              <button
+                type="button"
+                className="btn"
+                title={`Delete ${s}`}
+                onClick={() => void deleteSnapshot(s)}
+              >
+                ✕
+              </button>
+            </span>
+          ))}
+        </div>
+      )}
+
+      <h3>Send</h3>
+      <div className="discover-toolbar">
+        <label className="discover-label">
+          Target IP
+          <input
+            type="text"
+            placeholder="sender target"
+            className="discover-input"
+            value={sendTarget}
+            onChange={(e) => setSendTarget(e.currentTarget.value)}
+          />
+        </label>
+      </div>
+      <div className="discover-toolbar">
+        <select
+          className="discover-input"
+          value={sendKey}
+          onChange={(e) => setSendKey(Number(e.currentTarget.value))}
+        >
+          {TRIGGER_KEYS.map((k) => (
+            <option key={k.value} value={k.value}>
+              {k.label}
+            </option>
+          ))}
+        </select>
+        <label className="discover-label">
+          Sub-key
+          <input
+            type="number"
+            min={0}
+            max={255}
+            className="discover-input discover-input-narrow"
+            value={sendSubKey}
+            onChange={(e) => setSendSubKey(clampByte(e.currentTarget.value))}
+          />
+        </label>
+        <label className="discover-label">
+          Data
+          <input
+            type="text"
+            className="discover-input"
+            value={sendData}
+            onChange={(e) => setSendData(e.currentTarget.value)}
+          />
+        </label>
+        <button
+          type="button"
+          className="btn"
+          onClick={() =>
+            void run(() =>
+              invoke("send_art_trigger", {
+                targetIp: sendTarget.trim() || null,
+                key: sendKey,
+                subKey: sendSubKey,
+                data: sendData || null,
+              }),
+            )
+          }
+        >
+          Send ArtTrigger
+        </button>
+      </div>
+      <div className="discover-toolbar">
+        <input
+          type="text"
+          placeholder="SwoutText=Playback&"
+          className="discover-input"
+          value={sendText}
+          onChange={(e) => setSendText(e.currentTarget.value)}
+        />
+        <button
+          type="button"
+          className="btn"
+          disabled={!sendText.trim()}
+          onClick={() =>
+            void run(() =>
+              invoke("send_art_command", {
+                targetIp: sendTarget.trim() || null,
+                text: sendText,
+              }),
+            )
+          }
+        >
+          Send ArtCommand
+        </button>
+      </div>
+      {error && <div className="discover-error">{error}</div>}
+
+      <h3>Received</h3>
+      <div className="discover-table-wrap">
+        {log.length === 0 ? (
+          <div className="discover-empty">Nothing received yet</div>
+        ) : (
+          log.map((line, i) => (
+            <div key={i} className="mono">
+              {line}
+            </div>
+          ))
+        )}
+      </div>
+    </section>
+  );
+}
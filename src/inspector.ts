@@ -0,0 +1,30 @@
+/** One node of a decoded packet; groups have children and no value. */
+export type PacketField = {
+  name: string;
+  value?: string;
+  children?: PacketField[];
+};
+
+export type InspectedPacket = {
+  id: number;
+  atMs: number;
+  source: string;
+  length: number;
+  opcode: number | null;
+  opcodeName: string;
+  protVer: number | null;
+  fields: PacketField[];
+  error: string | null;
+  hex: string;
+};
+
+export type CaptureStatus = {
+  enabled: boolean;
+  capacity: number;
+  count: number;
+  nextId: number;
+};
+
+export function formatOpcode(op: number | null): string {
+  return op == null ? "—" : `0x${op.toString(16).padStart(4, "0")}`;
+}
@@ -0,0 +1,36 @@
+export type PortAddressParts = {
+  net: number;
+  subnet: number;
+  universe: number;
+};
+
+// 15-bit Art-Net Port-Address: net (7 bits), subnet (4 bits), universe (4 bits).
+export function splitPortAddress(portAddress: number): PortAddressParts {
+  const pa = portAddress & 0x7fff;
+  return {
+    net: (pa >> 8) & 0x7f,
+    subnet: (pa >> 4) & 0x0f,
+    universe: pa & 0x0f,
+  };
+}
+
+function checkPart(name: string, value: number, max: number) {
+  if (!Number.isInteger(value) || value < 0 || value > max) {
+    throw new RangeError(`${name} ${value} out of range (0..=${max})`);
+  }
+}
+
+// Throws rather than masking, so an out-of-range part never selects a
+// different universe.
+export function joinPortAddress({ net, subnet, universe }: PortAddressParts) {
+  checkPart("Net", net, 0x7f);
+  checkPart("Sub-Net", subnet, 0x0f);
+  checkPart("Universe", universe, 0x0f);
+  return (net << 8) | (subnet << 4) | universe;
+}
+
+// "net/subnet/universe" key used for universe tabs and selectors.
+export function portAddressKey(portAddress: number): string {
+  const { net, subnet, universe } = splitPortAddress(portAddress);
+  return `${net}/${subnet}/${universe}`;
+}
@@ -0,0 +1,30 @@
+export type TimecodeType = "film" | "ebu" | "df" | "smpte";
+
+export const TIMECODE_TYPES: { value: TimecodeType; label: string }[] = [
+  { value: "film", label: "Film (24 fps)" },
+  { value: "ebu", label: "EBU (25 fps)" },
+  { value: "df", label: "DF (29.97 fps drop-frame)" },
+  { value: "smpte", label: "SMPTE (30 fps)" },
+];
+
+export type TimecodeConfig = {
+  with_sender: boolean;
+  kind: TimecodeType;
+  start: string;
+  stream_id: number;
+};
+
+/** "artnet:timecode" */
+export type TimecodeEvent = {
+  sourceIp: string;
+  streamId: number;
+  text: string;
+  ms: number;
+};
+
+/** "artnet:chase": playback position while chasing timecode. */
+export type ChaseEvent = {
+  timecode: string;
+  positionMs: number | null;
+  running: boolean;
+};
@@ -0,0 +1,68 @@
+export type TriggerMatch =
+  | { type: "trigger"; key: number; subKey?: number | null }
+  | { type: "command"; text: string };
+
+export type TriggerAction =
+  | { type: "playFile"; path: string; loopPlayback: boolean }
+  | { type: "stopPlayback" }
+  | { type: "startAnimation"; mode: string; frequency: number; masterValue: number }
+  | { type: "stopAnimation" }
+  | { type: "blackout" }
+  | { type: "recallSnapshot"; name: string };
+
+export type TriggerMapping = { on: TriggerMatch; action: TriggerAction };
+
+export const TRIGGER_KEYS: { value: number; label: string }[] = [
+  { value: 0, label: "ASCII" },
+  { value: 1, label: "Macro" },
+  { value: 2, label: "Soft" },
+  { value: 3, label: "Show" },
+];
+
+export const ANIMATION_MODES = ["sinusoid", "ramp", "square", "chaser", "noise"];
+
+/** "artnet:trigger" */
+export type TriggerEvent = {
+  sourceIp: string;
+  keyName: string;
+  oem: number;
+  key: number;
+  subKey: number;
+  data: number[];
+};
+
+/** "artnet:command" */
+export type CommandEvent = {
+  sourceIp: string;
+  esta: number;
+  text: string;
+};
+
+/** "artnet:trigger_action" */
+export type TriggerActionEvent = {
+  action: TriggerAction;
+  error: string | null;
+};
+
+export function describeMatch(m: TriggerMatch): string {
+  if (m.type === "command") return `Command "${m.text}"`;
+  const key = TRIGGER_KEYS.find((k) => k.value === m.key)?.label ?? `Key ${m.key}`;
+  return m.subKey == null ? `${key}, any sub-key` : `${key} ${m.subKey}`;
+}
+
+export function describeAction(a: TriggerAction): string {
+  switch (a.type) {
+    case "playFile":
+      return `Play ${a.path}${a.loopPlayback ? " (loop)" : ""}`;
+    case "stopPlayback":
+      return "Stop playback";
+    case "startAnimation":
+      return `Animate ${a.mode} at ${a.frequency} Hz, master ${a.masterValue}`;
+    case "stopAnimation":
+      return "Stop animation";
+    case "blackout":
+      return "Blackout";
+    case "recallSnapshot":
+      return `Recall snapshot "${a.name}"`;
+  }
+}